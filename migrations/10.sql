@@ -0,0 +1,5 @@
+ALTER TABLE transactions ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE transactions ADD COLUMN event_type VARCHAR(255);
+ALTER TABLE transactions ADD COLUMN status VARCHAR(255) NOT NULL DEFAULT 'processed';
+
+CREATE INDEX transactions_status_asc ON transactions (status ASC);
@@ -0,0 +1,25 @@
+CREATE TABLE products(
+    id VARCHAR(255) PRIMARY KEY,
+    store VARCHAR(255) NOT NULL,
+    product_id VARCHAR(255) NOT NULL,
+    ink_amount BIGINT NOT NULL,
+    bonus_multiplier DOUBLE PRECISION NOT NULL DEFAULT 1,
+    active BOOLEAN NOT NULL,
+    starts_at BIGINT,
+    ends_at BIGINT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE (store, product_id)
+);
+
+CREATE INDEX products_created_at_desc ON products (created_at DESC);
+
+INSERT INTO products (
+    id, store, product_id, ink_amount, bonus_multiplier,
+    active, starts_at, ends_at, updated_at, created_at
+)
+VALUES
+    (gen_random_uuid()::text, 'APP_STORE', 'com.greenknightlabs.mist.ios.ink_small.111622', 5000, 1, TRUE, NULL, NULL, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT),
+    (gen_random_uuid()::text, 'APP_STORE', 'com.greenknightlabs.mist.ios.ink_medium.111622', 10000, 1, TRUE, NULL, NULL, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT),
+    (gen_random_uuid()::text, 'APP_STORE', 'com.greenknightlabs.mist.ios.ink_large.111622', 22000, 1, TRUE, NULL, NULL, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT),
+    (gen_random_uuid()::text, 'APP_STORE', 'com.greenknightlabs.mist.ios.ink_mega.111622', 55000, 1, TRUE, NULL, NULL, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT);
@@ -0,0 +1,17 @@
+ALTER TABLE products ADD COLUMN kind VARCHAR(255) NOT NULL DEFAULT 'consumable';
+ALTER TABLE products ADD COLUMN entitlement VARCHAR(255);
+
+CREATE TABLE subscriptions(
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    store VARCHAR(255) NOT NULL,
+    product_id VARCHAR(255) NOT NULL,
+    original_transaction_id VARCHAR(255) NOT NULL UNIQUE,
+    entitlement VARCHAR(255) NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    expires_at BIGINT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX subscriptions_user_id_asc ON subscriptions (user_id ASC);
@@ -0,0 +1,12 @@
+CREATE TABLE promo_codes(
+    id VARCHAR(255) PRIMARY KEY,
+    code VARCHAR(255) NOT NULL UNIQUE,
+    ink_amount BIGINT NOT NULL,
+    max_redemptions INTEGER NOT NULL,
+    redemptions_count INTEGER NOT NULL DEFAULT 0,
+    expires_at BIGINT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX promo_codes_created_at_desc ON promo_codes (created_at DESC);
+CREATE INDEX transactions_user_id_event_type_created_at_desc ON transactions (user_id ASC, event_type ASC, created_at DESC);
@@ -0,0 +1,22 @@
+CREATE TABLE ink_prices(
+    id VARCHAR(255) PRIMARY KEY,
+    generator VARCHAR(255) NOT NULL,
+    model VARCHAR(255),
+    mode VARCHAR(255),
+    max_steps SMALLINT,
+    base_ink DOUBLE PRECISION NOT NULL,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX ink_prices_generator ON ink_prices (generator ASC);
+
+INSERT INTO ink_prices (
+    id, generator, model, mode, max_steps, base_ink, updated_at, created_at
+)
+VALUES
+    (gen_random_uuid()::text, 'mist', NULL, NULL, NULL, 10, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT),
+    (gen_random_uuid()::text, 'stable_horde', NULL, NULL, NULL, 10, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT),
+    (gen_random_uuid()::text, 'dalle', NULL, NULL, NULL, 40, EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT);
+
+ALTER TABLE generate_media_requests ADD COLUMN ink_cost BIGINT;
@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN email_verified_at BIGINT;
+
+-- existing users signed up before verification existed and keep full access
+UPDATE users SET email_verified_at = created_at;
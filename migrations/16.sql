@@ -0,0 +1,20 @@
+-- refresh tokens are stored as sha256 hex digests from now on
+UPDATE devices SET refresh_token = encode(sha256(refresh_token::bytea), 'hex');
+
+-- absolute session lifetime, existing devices get a full lifetime from today
+ALTER TABLE devices ADD COLUMN expires_at BIGINT;
+UPDATE devices SET expires_at = EXTRACT(EPOCH FROM NOW())::BIGINT + 7776000;
+ALTER TABLE devices ALTER COLUMN expires_at SET NOT NULL;
+
+-- every refresh token ever issued to a device, to detect replays of rotated ones
+CREATE TABLE refresh_tokens(
+    token_hash VARCHAR(255) PRIMARY KEY,
+    device_id VARCHAR(255) NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    rotated_at BIGINT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX refresh_tokens_device_id_asc ON refresh_tokens (device_id ASC);
+
+INSERT INTO refresh_tokens (token_hash, device_id, rotated_at, created_at)
+SELECT refresh_token, id, NULL, updated_at FROM devices;
@@ -0,0 +1,14 @@
+ALTER TABLE users ADD COLUMN totp_secret VARCHAR(255);
+ALTER TABLE users ADD COLUMN totp_enabled_at BIGINT;
+-- the last accepted time step, so a code cannot be replayed
+ALTER TABLE users ADD COLUMN totp_last_used_step BIGINT;
+
+CREATE TABLE recovery_codes(
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash VARCHAR(255) NOT NULL,
+    used_at BIGINT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX recovery_codes_user_id_asc ON recovery_codes (user_id ASC);
@@ -0,0 +1,20 @@
+CREATE TABLE api_keys(
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    prefix VARCHAR(32) NOT NULL UNIQUE,
+    secret_hash VARCHAR(255) NOT NULL,
+    scopes TEXT[] NOT NULL,
+    rate_limit_per_minute INTEGER NOT NULL,
+    ink_spending_cap BIGINT,
+    last_used_at BIGINT,
+    revoked_at BIGINT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX api_keys_user_id_created_at_desc ON api_keys (user_id, created_at DESC);
+
+-- requests made with an api key, to enforce its spending cap
+ALTER TABLE generate_media_requests ADD COLUMN api_key_id VARCHAR(255) REFERENCES api_keys(id) ON DELETE SET NULL;
+
+CREATE INDEX generate_media_requests_api_key_id_created_at_desc ON generate_media_requests (api_key_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- failed logins and password mails, counted per account and per ip
+CREATE TABLE auth_throttles(
+    key VARCHAR(255) PRIMARY KEY,
+    attempts INTEGER NOT NULL,
+    window_started_at BIGINT NOT NULL,
+    locked_until BIGINT
+);
@@ -0,0 +1,18 @@
+-- permissions granted on top of roles
+ALTER TABLE users ADD COLUMN permissions TEXT [];
+ALTER TABLE devices ADD COLUMN permissions TEXT [];
+
+-- devices kept whatever roles their user had when they logged in
+UPDATE devices SET roles = users.roles FROM users WHERE devices.user_id = users.id;
+
+CREATE TABLE role_changes(
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    changed_by VARCHAR(255) REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(16) NOT NULL,
+    role VARCHAR(64),
+    permission VARCHAR(64),
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX role_changes_user_id_created_at_desc ON role_changes (user_id, created_at DESC);
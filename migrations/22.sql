@@ -0,0 +1,30 @@
+ALTER TABLE media ADD COLUMN ink_cost BIGINT;
+
+-- media generated before its charge was stored gets its share of the request's charge
+UPDATE media SET ink_cost = ROUND(
+    generate_media_requests.ink_cost::NUMERIC
+    / GREATEST((generate_media_requests.generate_media_dto->>'number')::NUMERIC, 1)
+)
+FROM generate_media_requests
+WHERE media.post_id = generate_media_requests.id AND generate_media_requests.ink_cost IS NOT NULL;
+
+ALTER TABLE ink_prices ADD COLUMN multiplier DOUBLE PRECISION NOT NULL DEFAULT 1;
+ALTER TABLE ink_prices ADD COLUMN min_strength REAL;
+ALTER TABLE ink_prices ADD COLUMN base_pixels INTEGER NOT NULL DEFAULT 262144;
+
+-- the mode multipliers that used to be hardcoded, for every generator priced so far
+INSERT INTO ink_prices (
+    id, generator, model, mode, max_steps, base_ink, multiplier, min_strength, updated_at, created_at
+)
+SELECT
+    gen_random_uuid()::text, ink_prices.generator, NULL, modes.mode, NULL, ink_prices.base_ink,
+    modes.multiplier, modes.min_strength,
+    EXTRACT(EPOCH FROM NOW())::BIGINT, EXTRACT(EPOCH FROM NOW())::BIGINT
+FROM ink_prices
+CROSS JOIN (
+    VALUES
+        ('img2img', 1.0::DOUBLE PRECISION, 0.5::REAL),
+        ('inpaint', 1.5::DOUBLE PRECISION, NULL::REAL),
+        ('outpaint', 1.5::DOUBLE PRECISION, NULL::REAL)
+) AS modes (mode, multiplier, min_strength)
+WHERE ink_prices.model IS NULL AND ink_prices.mode IS NULL AND ink_prices.max_steps IS NULL;
@@ -0,0 +1,2 @@
+-- generators or models only subscribers can use, set on a row without mode or max_steps
+ALTER TABLE ink_prices ADD COLUMN subscription_required BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- starter ink held back at signup, granted as-is once the email is verified
+ALTER TABLE users ADD COLUMN ink_withheld BIGINT NOT NULL DEFAULT 0;
+
+-- unverified users got whatever the signup grant fell short of the starter ink
+UPDATE users SET ink_withheld = GREATEST(100 - ink_ledger.ink_delta, 0)
+FROM ink_ledger
+WHERE ink_ledger.user_id = users.id AND ink_ledger.reason = 'signup'
+AND users.email_verified_at IS NULL;
@@ -0,0 +1,8 @@
+ALTER TABLE generate_media_requests ADD COLUMN attempts SMALLINT NOT NULL DEFAULT 0;
+ALTER TABLE generate_media_requests ADD COLUMN run_at BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE generate_media_requests ADD COLUMN locked_until BIGINT;
+
+UPDATE generate_media_requests SET attempts = 1, locked_until = created_at + 600 WHERE status = 'processing';
+
+CREATE INDEX generate_media_requests_run_at_asc ON generate_media_requests (run_at ASC);
+CREATE INDEX generate_media_requests_locked_until_asc ON generate_media_requests (locked_until ASC);
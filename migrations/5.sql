@@ -0,0 +1 @@
+ALTER TABLE generate_media_requests ADD COLUMN provider_request_id TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE media ADD COLUMN input_media_id VARCHAR(255) REFERENCES media(id) ON DELETE SET NULL;
+
+CREATE INDEX media_input_media_id_asc ON media (input_media_id ASC);
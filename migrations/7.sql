@@ -0,0 +1,5 @@
+ALTER TABLE upscales ADD COLUMN media_id VARCHAR(255) REFERENCES media(id) ON DELETE SET NULL;
+
+CREATE INDEX upscales_user_id_asc ON upscales (user_id ASC);
+CREATE INDEX upscales_media_id_asc ON upscales (media_id ASC);
+CREATE INDEX upscales_created_at_desc ON upscales (created_at DESC);
@@ -0,0 +1 @@
+ALTER TABLE media ADD COLUMN file_size BIGINT NOT NULL DEFAULT 0;
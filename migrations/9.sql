@@ -0,0 +1,22 @@
+CREATE TABLE ink_ledger(
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ink_delta BIGINT NOT NULL,
+    ink_sum_delta BIGINT NOT NULL,
+    ink_pending_delta BIGINT NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    reference_id VARCHAR(255),
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX ink_ledger_user_id_created_at_desc ON ink_ledger (user_id ASC, created_at DESC);
+CREATE INDEX ink_ledger_reference_id_asc ON ink_ledger (reference_id ASC);
+
+INSERT INTO ink_ledger (
+    id, user_id, ink_delta, ink_sum_delta, ink_pending_delta,
+    reason, reference_id, created_at
+)
+SELECT
+    gen_random_uuid()::text, id, ink, ink_sum, ink_pending,
+    'opening_balance', NULL, EXTRACT(EPOCH FROM NOW())::BIGINT
+FROM users;
@@ -0,0 +1,9 @@
+// every api key starts with this, which is how they are told apart from jwts
+pub static API_KEY_PREFIX: &str = "mist_";
+pub static API_KEY_PREFIX_BYTES: usize = 6;
+pub static API_KEY_SECRET_BYTES: usize = 32;
+// active keys a user can have at once
+pub static MAX_API_KEYS: i64 = 10;
+pub static DEFAULT_RATE_LIMIT_PER_MINUTE: i32 = 30;
+// seconds over which ink spent with a key counts towards its cap
+pub static INK_SPENDING_CAP_WINDOW: i64 = 86400;
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
+
+use super::{
+    dtos::create_api_key_dto::CreateApiKeyDto,
+    models::{api_key::ApiKey, created_api_key::CreatedApiKey},
+    service,
+};
+
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<CreateApiKeyDto>,
+) -> Result<Json<CreatedApiKey>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::create_api_key(&dto, &claims, &state.pool).await {
+                Ok(created_api_key) => Ok(Json(created_api_key)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_api_keys(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::get_api_keys(&claims, &state.pool).await {
+            Ok(api_keys) => Ok(Json(api_keys)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn revoke_api_key_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::revoke_api_key_by_id(&id, &claims, &state.pool).await,
+        Err(e) => Err(e),
+    }
+}
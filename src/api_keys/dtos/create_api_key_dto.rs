@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyDto {
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "name must be between 1 and 64 characters."
+    ))]
+    pub name: String,
+    #[validate(length(min = 1, message = "scopes must not be empty."))]
+    pub scopes: Vec<String>,
+    #[validate(range(
+        min = 1,
+        max = 600,
+        message = "rate_limit_per_minute must be between 1 and 600."
+    ))]
+    pub rate_limit_per_minute: Option<i32>,
+    #[validate(range(min = 0, message = "ink_spending_cap must not be negative."))]
+    pub ink_spending_cap: Option<i64>,
+}
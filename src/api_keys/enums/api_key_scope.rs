@@ -0,0 +1,11 @@
+#[non_exhaustive]
+pub struct ApiKeyScope;
+
+impl ApiKeyScope {
+    pub const MEDIA_GENERATE: &'static str = "media:generate";
+    pub const MEDIA_READ: &'static str = "media:read";
+
+    pub fn all() -> [&'static str; 2] {
+        return [Self::MEDIA_GENERATE, Self::MEDIA_READ];
+    }
+}
@@ -0,0 +1,54 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum ApiKeysApiError {
+    ApiKeyNotFound,
+    InvalidApiKey,
+    ApiKeyRevoked,
+    InvalidScope,
+    MissingScope,
+    TooManyApiKeys,
+    RateLimitExceeded,
+    SpendingCapReached,
+}
+
+impl ApiKeysApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::ApiKeyNotFound => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "API key not found.".to_string(),
+            },
+            Self::InvalidApiKey => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "Invalid API key.".to_string(),
+            },
+            Self::ApiKeyRevoked => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "API key was revoked.".to_string(),
+            },
+            Self::InvalidScope => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "scopes must be media:generate or media:read.".to_string(),
+            },
+            Self::MissingScope => ApiError {
+                code: StatusCode::FORBIDDEN,
+                message: "API key does not have the scope required for this request.".to_string(),
+            },
+            Self::TooManyApiKeys => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Maximum number of API keys reached, revoke one first.".to_string(),
+            },
+            Self::RateLimitExceeded => ApiError {
+                code: StatusCode::TOO_MANY_REQUESTS,
+                message: "API key rate limit exceeded, try again later.".to_string(),
+            },
+            Self::SpendingCapReached => ApiError {
+                code: StatusCode::NOT_ACCEPTABLE,
+                message: "API key ink spending cap reached.".to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    api_keys::{config::DEFAULT_RATE_LIMIT_PER_MINUTE, dtos::create_api_key_dto::CreateApiKeyDto},
+    app::util::time,
+    auth::jwt::models::claims::Claims,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    // public part of the key, used to look it up
+    pub prefix: String,
+    // sha256 of the secret part of the key
+    #[serde(skip_serializing)]
+    pub secret_hash: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    // ink that can be spent with this key over a day
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ink_spending_cap: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl ApiKey {
+    pub fn new(
+        dto: &CreateApiKeyDto,
+        claims: &Claims,
+        prefix: String,
+        secret_hash: String,
+    ) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: claims.id.to_string(),
+            name: dto.name.to_string(),
+            prefix,
+            secret_hash,
+            scopes: dto.scopes.clone(),
+            rate_limit_per_minute: dto
+                .rate_limit_per_minute
+                .unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE),
+            ink_spending_cap: dto.ink_spending_cap,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        return self.scopes.iter().any(|s| s == scope);
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        return self.revoked_at.is_some();
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+use super::api_key::ApiKey;
+
+// the only time the full key is ever returned
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
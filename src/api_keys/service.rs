@@ -0,0 +1,288 @@
+use std::sync::Arc;
+
+use rand::RngCore;
+use sqlx::{PgPool, Postgres};
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError, util::hasher, util::time},
+    auth::jwt::models::claims::Claims,
+    generate_media_requests::enums::generate_media_request_status::GenerateMediaRequestStatus,
+    AppState,
+};
+
+use super::{
+    config::{
+        API_KEY_PREFIX, API_KEY_PREFIX_BYTES, API_KEY_SECRET_BYTES, INK_SPENDING_CAP_WINDOW,
+        MAX_API_KEYS,
+    },
+    dtos::create_api_key_dto::CreateApiKeyDto,
+    enums::api_key_scope::ApiKeyScope,
+    errors::ApiKeysApiError,
+    models::{api_key::ApiKey, created_api_key::CreatedApiKey},
+};
+
+pub async fn create_api_key(
+    dto: &CreateApiKeyDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<CreatedApiKey, ApiError> {
+    for scope in &dto.scopes {
+        if !ApiKeyScope::all().contains(&scope.as_str()) {
+            return Err(ApiKeysApiError::InvalidScope.value());
+        }
+    }
+
+    let count_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT COUNT(*) FROM api_keys
+        WHERE user_id = $1 AND revoked_at IS NULL
+        ",
+    )
+    .bind(&claims.id)
+    .fetch_one(pool)
+    .await;
+
+    match count_result {
+        Ok((count,)) => {
+            if count >= MAX_API_KEYS {
+                return Err(ApiKeysApiError::TooManyApiKeys.value());
+            }
+        }
+        Err(e) => {
+            tracing::error!("create_api_key: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+
+    let prefix = random_hex(API_KEY_PREFIX_BYTES);
+    let secret = random_hex(API_KEY_SECRET_BYTES);
+    let key = [API_KEY_PREFIX, &prefix, "_", &secret].concat();
+
+    let api_key = ApiKey::new(dto, claims, prefix, hasher::hash_token(&secret));
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO api_keys (
+            id, user_id, name, prefix, secret_hash, scopes,
+            rate_limit_per_minute, ink_spending_cap, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ",
+    )
+    .bind(&api_key.id)
+    .bind(&api_key.user_id)
+    .bind(&api_key.name)
+    .bind(&api_key.prefix)
+    .bind(&api_key.secret_hash)
+    .bind(&api_key.scopes)
+    .bind(api_key.rate_limit_per_minute)
+    .bind(api_key.ink_spending_cap)
+    .bind(api_key.created_at)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(CreatedApiKey { api_key, key }),
+        Err(e) => {
+            tracing::error!("create_api_key: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_api_keys(claims: &Claims, pool: &PgPool) -> Result<Vec<ApiKey>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, ApiKey>(
+        "
+        SELECT * FROM api_keys
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(&claims.id)
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(api_keys) => Ok(api_keys),
+        Err(e) => {
+            tracing::error!("get_api_keys: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn revoke_api_key_by_id(
+    id: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE api_keys SET revoked_at = $1
+        WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+        ",
+    )
+    .bind(time::current_time_in_secs() as i64)
+    .bind(id)
+    .bind(&claims.id)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiKeysApiError::ApiKeyNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("revoke_api_key_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    return token.starts_with(API_KEY_PREFIX);
+}
+
+fn split_api_key(key: &str) -> Option<(&str, &str)> {
+    return key
+        .strip_prefix(API_KEY_PREFIX)
+        .and_then(|key| key.split_once('_'))
+        .filter(|(prefix, secret)| !prefix.is_empty() && !secret.is_empty());
+}
+
+// resolves a key presented as a bearer token, counting it against its rate limit
+pub async fn authenticate_api_key(
+    key: &str,
+    scope: &str,
+    state: &Arc<AppState>,
+) -> Result<ApiKey, ApiError> {
+    match verify_api_key(key, &state.pool).await {
+        Ok(api_key) => authorize_api_key(api_key, scope, state).await,
+        Err(e) => Err(e),
+    }
+}
+
+// checks the key exists, matches its secret and is not revoked
+pub async fn verify_api_key(key: &str, pool: &PgPool) -> Result<ApiKey, ApiError> {
+    let Some((prefix, secret)) = split_api_key(key) else {
+        return Err(ApiKeysApiError::InvalidApiKey.value());
+    };
+
+    let sqlx_result = sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE prefix = $1")
+        .bind(prefix)
+        .fetch_optional(pool)
+        .await;
+
+    let api_key = match sqlx_result {
+        Ok(Some(api_key)) => api_key,
+        Ok(None) => return Err(ApiKeysApiError::InvalidApiKey.value()),
+        Err(e) => {
+            tracing::error!("verify_api_key: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    if hasher::hash_token(secret) != api_key.secret_hash {
+        return Err(ApiKeysApiError::InvalidApiKey.value());
+    }
+
+    if api_key.is_revoked() {
+        return Err(ApiKeysApiError::ApiKeyRevoked.value());
+    }
+
+    return Ok(api_key);
+}
+
+// checks a verified key may be used for the scope, counting it against its rate limit
+pub async fn authorize_api_key(
+    api_key: ApiKey,
+    scope: &str,
+    state: &Arc<AppState>,
+) -> Result<ApiKey, ApiError> {
+    if !api_key.has_scope(scope) {
+        return Err(ApiKeysApiError::MissingScope.value());
+    }
+
+    let rate_limit_decision = state.rate_limiter.check(
+        &["api-key:", &api_key.id].concat(),
+        api_key.rate_limit_per_minute.max(0) as u32,
+        60,
+    );
+    if !rate_limit_decision.allowed {
+        return Err(ApiKeysApiError::RateLimitExceeded.value());
+    }
+
+    let sqlx_result = sqlx::query("UPDATE api_keys SET last_used_at = $1 WHERE id = $2")
+        .bind(time::current_time_in_secs() as i64)
+        .bind(&api_key.id)
+        .execute(&state.pool)
+        .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("authorize_api_key: {:?}", e);
+    }
+
+    return Ok(api_key);
+}
+
+// locks the key so concurrent requests made with it cannot overspend its cap together
+pub async fn check_ink_spending_cap_as_tx(
+    api_key_id: &str,
+    ink_cost: i64,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result =
+        sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE id = $1 FOR UPDATE")
+            .bind(api_key_id)
+            .fetch_optional(&mut *tx)
+            .await;
+
+    let api_key = match sqlx_result {
+        Ok(Some(api_key)) => api_key,
+        Ok(None) => return Err(ApiKeysApiError::InvalidApiKey.value()),
+        Err(e) => {
+            tracing::error!("check_ink_spending_cap_as_tx: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    if api_key.is_revoked() {
+        return Err(ApiKeysApiError::ApiKeyRevoked.value());
+    }
+
+    let Some(ink_spending_cap) = api_key.ink_spending_cap else {
+        return Ok(());
+    };
+
+    let sqlx_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT COALESCE(SUM(ink_cost), 0)::BIGINT FROM generate_media_requests
+        WHERE api_key_id = $1 AND created_at > $2 AND status <> $3 AND status <> $4
+        ",
+    )
+    .bind(api_key_id)
+    .bind(time::current_time_in_secs() as i64 - INK_SPENDING_CAP_WINDOW)
+    .bind(GenerateMediaRequestStatus::Canceled.value())
+    .bind(GenerateMediaRequestStatus::Error.value())
+    .fetch_one(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok((ink_spent,)) => match ink_spent + ink_cost > ink_spending_cap {
+            true => Err(ApiKeysApiError::SpendingCapReached.value()),
+            false => Ok(()),
+        },
+        Err(e) => {
+            tracing::error!("check_ink_spending_cap_as_tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+
+    return hex::encode(buf);
+}
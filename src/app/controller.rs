@@ -1,16 +1,15 @@
 use std::sync::Arc;
 
-use axum::{
-    extract::State,
-    headers::{authorization::Bearer, Authorization},
-    Json, TypedHeader,
-};
+use axum::{extract::State, Json};
 use axum_macros::debug_handler;
 use reqwest::StatusCode;
 use serde_json::Value;
 use validator::Validate;
 
-use crate::{auth::jwt::models::claims::Claims, AppState};
+use crate::{
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
 
 use super::{
     dtos::edit_api_status_dto::EditApiStatusDto, models::api_error::ApiError, service,
@@ -28,10 +27,10 @@ pub async fn get_api_state(State(state): State<Arc<AppState>>) -> Json<Value> {
 
 pub async fn edit_api_state(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<EditApiStatusDto>,
 ) -> Result<Json<Value>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
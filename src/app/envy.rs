@@ -5,12 +5,20 @@ pub struct Envy {
     pub app_env: String,
     pub frontend_url: String,
     pub port: Option<u16>,
+    pub trusted_proxy_hops: Option<usize>,
     pub role: Option<String>,
+    pub email_verification_policy: Option<String>,
 
     pub database_url: String,
 
-    pub jwt_secret: String,
+    pub jwt_signing_key: String,
+    pub jwt_signing_key_id: String,
+    pub jwt_verification_keys: String,
     pub revenuecat_webhook_secret: String,
+    pub stripe_webhook_secret: String,
+
+    pub apple_client_id: String,
+    pub google_client_id: String,
 
     pub openai_api_key: String,
     pub dream_api_key: String,
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::{
-    auth::jwt::{enums::roles::Roles, models::claims::Claims},
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
     AppState,
 };
 
@@ -19,6 +19,7 @@ pub async fn get_api_state(state: &Arc<AppState>) -> Value {
 
     return json!({
         "api_status": api_status,
+        "providers": state.media_generators.health(),
     });
 }
 
@@ -27,11 +28,7 @@ pub async fn edit_api_state(
     claims: &Claims,
     state: &Arc<AppState>,
 ) -> Result<Value, ApiError> {
-    let Some(roles) = &claims.roles else {
-        return Err(DefaultApiError::PermissionDenied.value());
-    };
-
-    if !roles.contains(&Roles::ADMIN.to_string()) {
+    if !claims.has_permission(Permissions::EDIT_API_STATUS) {
         return Err(DefaultApiError::PermissionDenied.value());
     }
 
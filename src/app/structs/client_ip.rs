@@ -0,0 +1,44 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+use crate::{app::models::api_error::ApiError, AppState};
+
+// address of the client. x-forwarded-for is only read behind the configured number
+// of trusted proxies, each of which appends the address it received from, so the
+// entry that many hops from the end is the client and anything before it is made up.
+// without trusted proxies the header is ignored and the peer address is used.
+pub struct ClientIp(pub String);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(trusted_proxy_hops) = state.envy.trusted_proxy_hops.filter(|hops| *hops > 0) {
+            let forwarded_for = parts
+                .headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').nth(trusted_proxy_hops - 1))
+                .map(|ip| ip.trim().to_string())
+                .filter(|ip| !ip.is_empty());
+            if let Some(ip) = forwarded_for {
+                return Ok(Self(ip));
+            }
+        }
+
+        if let Some(ConnectInfo(addr)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() {
+            return Ok(Self(addr.ip().to_string()));
+        }
+
+        return Ok(Self("unknown".to_string()));
+    }
+}
@@ -1 +1,2 @@
+pub mod client_ip;
 pub mod json_from_request;
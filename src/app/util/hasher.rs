@@ -2,6 +2,7 @@ use argon2::{
     password_hash::{self, SaltString},
     Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
 };
+use sha2::{Digest, Sha256};
 use tokio::task;
 
 use crate::app::models::app_error::AppError;
@@ -27,6 +28,11 @@ pub async fn hash(password: String) -> Result<String, AppError> {
     }
 }
 
+// for high entropy tokens, which need a fast lookup rather than a slow hash
+pub fn hash_token(token: &str) -> String {
+    return hex::encode(Sha256::digest(token.as_bytes()));
+}
+
 pub async fn verify(password: String, hash: String) -> Result<bool, AppError> {
     let task_result = task::spawn_blocking(move || match PasswordHash::new(&hash) {
         Ok(hash) => match Argon2::default().verify_password(password.as_bytes(), &hash) {
@@ -6,15 +6,16 @@ use tokio::{
 };
 
 use crate::{
-    app::{self, util::time::current_time_in_secs},
+    auth,
     generate_media_requests::{
-        self, dtos::get_generate_media_requests_filter_dto::GetGenerateMediaRequestsFilterDto,
-        enums::generate_media_request_status::GenerateMediaRequestStatus,
+        self, enums::generate_media_request_status::GenerateMediaRequestStatus,
     },
-    media, AppState,
+    media, users, AppState,
 };
 
 pub fn spawn(state: Arc<AppState>) {
+    let reconcile_state = state.clone();
+
     task::spawn(async move {
         sleep(Duration::from_secs(600)).await;
         let mut interval = interval(Duration::from_secs(600));
@@ -24,22 +25,23 @@ pub fn spawn(state: Arc<AppState>) {
             cleanup_requests(&state).await;
         }
     });
+
+    task::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600));
+
+        loop {
+            interval.tick().await;
+            reconcile_ink(&reconcile_state).await;
+            cleanup_auth_throttles(&reconcile_state).await;
+        }
+    });
 }
 
 async fn cleanup_requests(state: &Arc<AppState>) {
-    let ten_minutes_ago = (current_time_in_secs() as i64) - 600;
-    let dto = GetGenerateMediaRequestsFilterDto {
-        id: None,
-        user_id: None,
-        status: Some(GenerateMediaRequestStatus::Processing.value().to_string()),
-        api_v: Some(app::config::API_V),
-        sort: Some("created_at,asc".to_string()),
-        cursor: None,
-        limit: None,
-    };
-
-    match generate_media_requests::service::get_generate_media_requests_as_admin(&dto, &state.pool)
-        .await
+    match generate_media_requests::service::get_exhausted_generate_media_requests_as_admin(
+        &state.pool,
+    )
+    .await
     {
         Ok(requests) => {
             if requests.len() > 0 {
@@ -49,10 +51,6 @@ async fn cleanup_requests(state: &Arc<AppState>) {
             let mut futures = Vec::new();
 
             for request in &requests {
-                if request.created_at > ten_minutes_ago {
-                    continue;
-                }
-
                 futures.push(media::service::on_generate_media_completion_with_retry(
                     request,
                     &GenerateMediaRequestStatus::Error,
@@ -68,3 +66,25 @@ async fn cleanup_requests(state: &Arc<AppState>) {
         }
     }
 }
+
+async fn reconcile_ink(state: &Arc<AppState>) {
+    match users::util::ink::ink::reconcile_ink_as_admin(&state.pool).await {
+        Ok(drifts) => {
+            if !drifts.is_empty() {
+                tracing::warn!(
+                    "reconcile_ink found {} user(s) with ink drift",
+                    drifts.len()
+                );
+            }
+        }
+        Err(e) => {
+            tracing::error!("reconcile_ink: {:?}", e);
+        }
+    }
+}
+
+async fn cleanup_auth_throttles(state: &Arc<AppState>) {
+    if let Err(e) = auth::throttles::service::delete_expired_throttles_as_admin(&state.pool).await {
+        tracing::error!("cleanup_auth_throttles: {:?}", e);
+    }
+}
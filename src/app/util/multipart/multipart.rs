@@ -6,7 +6,7 @@ use super::models::file_properties::FileProperties;
 pub async fn get_files_properties(mut multipart: Multipart) -> Vec<FileProperties> {
     let mut vec = Vec::new();
 
-    while let Some(field) = multipart.next_field().await.unwrap() {
+    while let Ok(Some(field)) = multipart.next_field().await {
         let field_name = field.name().unwrap_or("file").to_string();
         let file_name = field.file_name().unwrap_or("file-name").to_string();
         let mime_type = field
@@ -6,10 +6,14 @@ use axum::{
     http::StatusCode,
     Json, TypedHeader,
 };
+use jsonwebtoken::jwk::JwkSet;
 use validator::Validate;
 
 use crate::{
-    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    app::{
+        models::api_error::ApiError,
+        structs::{client_ip::ClientIp, json_from_request::JsonFromRequest},
+    },
     devices::{
         dtos::{
             get_devices_filter_dto::GetDevicesFilterDto, logout_device_dto::LogoutDeviceDto,
@@ -22,20 +26,29 @@ use crate::{
 
 use super::{
     dtos::{
-        delete_account_dto::DeleteAccountDto, edit_password_dto::EditPasswordDto,
+        confirm_totp_dto::ConfirmTotpDto, delete_account_dto::DeleteAccountDto,
+        disable_totp_dto::DisableTotpDto, edit_password_dto::EditPasswordDto,
+        identity_login_dto::IdentityLoginDto, link_identity_dto::LinkIdentityDto,
         login_dto::LoginDto, register_dto::RegisterDto,
         request_email_update_dto::RequestEmailUpdateDto,
         request_password_update_dto::RequestPasswordUpdateDto,
+        two_factor_login_dto::TwoFactorLoginDto,
     },
-    jwt::models::claims::Claims,
-    models::access_info::AccessInfo,
+    identity::enums::identity_provider::IdentityProvider,
+    jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    models::{access_info::AccessInfo, login_response::LoginResponse},
     service,
+    totp::{
+        self,
+        models::{recovery_codes::RecoveryCodes, totp_enrollment::TotpEnrollment},
+    },
 };
 
 pub async fn register(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     JsonFromRequest(dto): JsonFromRequest<RegisterDto>,
-) -> Result<Json<AccessInfo>, ApiError> {
+) -> Result<Json<LoginResponse>, ApiError> {
     if let Err(_) = dto.validate() {
         return Err(ApiError {
             code: StatusCode::BAD_REQUEST,
@@ -43,7 +56,7 @@ pub async fn register(
         });
     }
 
-    match service::register(&dto, &state).await {
+    match service::register(&dto, &client_ip, &state).await {
         Ok(user) => Ok(Json(user)),
         Err(e) => Err(e),
     }
@@ -51,8 +64,9 @@ pub async fn register(
 
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     JsonFromRequest(dto): JsonFromRequest<LoginDto>,
-) -> Result<Json<AccessInfo>, ApiError> {
+) -> Result<Json<LoginResponse>, ApiError> {
     if let Err(_) = dto.validate() {
         return Err(ApiError {
             code: StatusCode::BAD_REQUEST,
@@ -60,19 +74,195 @@ pub async fn login(
         });
     }
 
-    match service::login(&dto, &state).await {
+    match service::login(&dto, &client_ip, &state).await {
         Ok(user) => Ok(Json(user)),
         Err(e) => Err(e),
     }
 }
 
+pub async fn login_with_apple(
+    State(state): State<Arc<AppState>>,
+    JsonFromRequest(dto): JsonFromRequest<IdentityLoginDto>,
+) -> Result<Json<LoginResponse>, ApiError> {
+    if let Err(e) = dto.validate() {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: e.to_string(),
+        });
+    }
+
+    match service::login_with_identity(IdentityProvider::APPLE, &dto, &state).await {
+        Ok(access_info) => Ok(Json(access_info)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn login_with_google(
+    State(state): State<Arc<AppState>>,
+    JsonFromRequest(dto): JsonFromRequest<IdentityLoginDto>,
+) -> Result<Json<LoginResponse>, ApiError> {
+    if let Err(e) = dto.validate() {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: e.to_string(),
+        });
+    }
+
+    match service::login_with_identity(IdentityProvider::GOOGLE, &dto, &state).await {
+        Ok(access_info) => Ok(Json(access_info)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn link_apple(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<LinkIdentityDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            service::link_identity(IdentityProvider::APPLE, &dto, &claims, &state).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn link_google(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<LinkIdentityDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            service::link_identity(IdentityProvider::GOOGLE, &dto, &claims, &state).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn set_password(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<EditPasswordDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            service::set_password(&dto, &claims, &state.pool).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn login_with_second_factor(
+    State(state): State<Arc<AppState>>,
+    JsonFromRequest(dto): JsonFromRequest<TwoFactorLoginDto>,
+) -> Result<Json<AccessInfo>, ApiError> {
+    if let Err(e) = dto.validate() {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: e.to_string(),
+        });
+    }
+
+    match service::login_with_second_factor(&dto, &state).await {
+        Ok(access_info) => Ok(Json(access_info)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn begin_totp_enrollment(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+) -> Result<Json<TotpEnrollment>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match totp::service::begin_totp_enrollment(&claims, &state.pool).await {
+            Ok(totp_enrollment) => Ok(Json(totp_enrollment)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn confirm_totp_enrollment(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<ConfirmTotpDto>,
+) -> Result<Json<RecoveryCodes>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match totp::service::confirm_totp_enrollment(&dto, &claims, &state.pool).await {
+                Ok(recovery_codes) => Ok(Json(recovery_codes)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn disable_totp(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<DisableTotpDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            totp::service::disable_totp(&dto, &claims, &state.pool).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
 pub async fn request_email_update_mail(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<RequestEmailUpdateDto>,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
-        Ok(claims) => service::request_email_update_mail(&dto, &claims, &state).await,
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            service::request_email_update_mail(&dto, &claims, &state).await
+        }
         Err(e) => Err(e),
     }
 }
@@ -85,8 +275,35 @@ pub async fn process_email_edit(
     service::process_email_edit(&access_token, &state).await
 }
 
+pub async fn process_account_unlock(
+    State(state): State<Arc<AppState>>,
+    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+) -> Result<(), ApiError> {
+    let access_token = authorization.0.token();
+    service::process_account_unlock(access_token, &state).await
+}
+
+pub async fn request_email_verification_mail(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::request_email_verification_mail(&claims, &state).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn process_email_verification(
+    State(state): State<Arc<AppState>>,
+    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+) -> Result<(), ApiError> {
+    let access_token = authorization.0.token();
+    service::process_email_verification(&access_token, &state).await
+}
+
 pub async fn request_password_update_mail(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     JsonFromRequest(dto): JsonFromRequest<RequestPasswordUpdateDto>,
 ) -> Result<(), ApiError> {
     if let Err(e) = dto.validate() {
@@ -96,7 +313,7 @@ pub async fn request_password_update_mail(
         });
     }
 
-    service::request_password_update_mail(&dto, &state).await
+    service::request_password_update_mail(&dto, &client_ip, &state).await
 }
 
 pub async fn process_password_edit(
@@ -117,10 +334,10 @@ pub async fn process_password_edit(
 
 pub async fn get_devices(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetDevicesFilterDto>,
 ) -> Result<Json<Vec<Device>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -157,10 +374,10 @@ pub async fn refresh(
 
 pub async fn logout(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<LogoutDeviceDto>,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -177,10 +394,10 @@ pub async fn logout(
 
 pub async fn delete_account(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<DeleteAccountDto>,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -194,3 +411,7 @@ pub async fn delete_account(
         Err(e) => Err(e),
     }
 }
+
+pub async fn get_jwks(State(state): State<Arc<AppState>>) -> Json<JwkSet> {
+    return Json(state.jwt_keys.jwk_set().clone());
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmTotpDto {
+    #[validate(length(equal = 6, message = "code must be 6 characters."))]
+    pub code: String,
+}
@@ -11,5 +11,10 @@ pub struct DeleteAccountDto {
         ),
         custom = "super::validate_password"
     )]
-    pub password: String,
+    // not needed by users who never set a password
+    pub password: Option<String>,
+    #[validate(length(equal = 6, message = "totp_code must be 6 characters."))]
+    pub totp_code: Option<String>,
+    #[validate(length(max = 32, message = "recovery_code must be 32 characters or less."))]
+    pub recovery_code: Option<String>,
 }
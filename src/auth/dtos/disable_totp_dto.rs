@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DisableTotpDto {
+    #[validate(length(equal = 6, message = "totp_code must be 6 characters."))]
+    pub totp_code: Option<String>,
+    #[validate(length(max = 32, message = "recovery_code must be 32 characters or less."))]
+    pub recovery_code: Option<String>,
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct LinkIdentityDto {
+    #[validate(length(
+        min = 1,
+        max = 4096,
+        message = "identity_token must be between 1 and 4096 characters."
+    ))]
+    pub identity_token: String,
+    // the raw nonce the client generated for this sign in, apple receives its sha256
+    #[validate(length(
+        min = 16,
+        max = 256,
+        message = "nonce must be between 16 and 256 characters."
+    ))]
+    pub nonce: String,
+}
@@ -1,12 +1,17 @@
 use regex::Regex;
 use validator::ValidationError;
 
+pub mod confirm_totp_dto;
 pub mod delete_account_dto;
+pub mod disable_totp_dto;
 pub mod edit_password_dto;
+pub mod identity_login_dto;
+pub mod link_identity_dto;
 pub mod login_dto;
 pub mod register_dto;
 pub mod request_email_update_dto;
 pub mod request_password_update_dto;
+pub mod two_factor_login_dto;
 
 lazy_static! {
     pub static ref USERNAME_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9_.-]{3,24}$").unwrap();
@@ -5,4 +5,8 @@ use validator::Validate;
 pub struct RequestEmailUpdateDto {
     #[validate(email)]
     pub email: String,
+    #[validate(length(equal = 6, message = "totp_code must be 6 characters."))]
+    pub totp_code: Option<String>,
+    #[validate(length(max = 32, message = "recovery_code must be 32 characters or less."))]
+    pub recovery_code: Option<String>,
 }
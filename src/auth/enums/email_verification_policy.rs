@@ -0,0 +1,19 @@
+use crate::app::envy::Envy;
+
+// what an unverified email address keeps a user from
+#[non_exhaustive]
+pub struct EmailVerificationPolicy;
+
+impl EmailVerificationPolicy {
+    pub const NONE: &'static str = "none";
+    // the starter ink is granted once the email is verified
+    pub const WITHHOLD_INK: &'static str = "withhold_ink";
+    pub const BLOCK_GENERATION: &'static str = "block_generation";
+
+    pub fn from_envy(envy: &Envy) -> &str {
+        return envy
+            .email_verification_policy
+            .as_deref()
+            .unwrap_or(Self::NONE);
+    }
+}
@@ -5,6 +5,16 @@ use crate::app::models::api_error::ApiError;
 #[derive(Debug)]
 pub enum AuthApiError {
     BadLogin,
+    EmailNotVerified,
+    InvalidIdentityToken,
+    UsernameRequired,
+    EmailRequired,
+    PasswordRequired,
+    TwoFactorRequired,
+    InvalidTwoFactorCode,
+    TotpAlreadyEnabled,
+    TotpNotEnrolled,
+    TooManyAttempts,
 }
 
 impl AuthApiError {
@@ -14,6 +24,46 @@ impl AuthApiError {
                 code: StatusCode::UNAUTHORIZED,
                 message: "Invalid username or password.".to_string(),
             },
+            Self::EmailNotVerified => ApiError {
+                code: StatusCode::FORBIDDEN,
+                message: "Verify your email to continue.".to_string(),
+            },
+            Self::InvalidIdentityToken => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "Invalid identity token.".to_string(),
+            },
+            Self::UsernameRequired => ApiError {
+                code: StatusCode::UNPROCESSABLE_ENTITY,
+                message: "A username is required to sign up.".to_string(),
+            },
+            Self::EmailRequired => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "An email is required to sign up.".to_string(),
+            },
+            Self::PasswordRequired => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "password is required.".to_string(),
+            },
+            Self::TwoFactorRequired => ApiError {
+                code: StatusCode::FORBIDDEN,
+                message: "totp_code or recovery_code is required.".to_string(),
+            },
+            Self::InvalidTwoFactorCode => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "Invalid two factor code.".to_string(),
+            },
+            Self::TotpAlreadyEnabled => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Two factor authentication is already enabled.".to_string(),
+            },
+            Self::TotpNotEnrolled => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Two factor authentication is not set up.".to_string(),
+            },
+            Self::TooManyAttempts => ApiError {
+                code: StatusCode::TOO_MANY_REQUESTS,
+                message: "Too many attempts, try again later.".to_string(),
+            },
         }
     }
 }
@@ -0,0 +1,8 @@
+pub static APPLE_ISSUER: &str = "https://appleid.apple.com";
+pub static APPLE_JWKS_URL: &str = "https://appleid.apple.com/auth/keys";
+pub static GOOGLE_ISSUERS: [&str; 2] = ["https://accounts.google.com", "accounts.google.com"];
+pub static GOOGLE_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+// seconds signing keys are cached before being fetched again
+pub static JWKS_CACHE_TTL: u64 = 3600;
+// unknown key ids refetch the keys at most this often, so bad tokens cannot hammer providers
+pub static JWKS_MIN_REFRESH_INTERVAL: u64 = 60;
@@ -0,0 +1,7 @@
+#[non_exhaustive]
+pub struct IdentityProvider;
+
+impl IdentityProvider {
+    pub const APPLE: &'static str = "apple";
+    pub const GOOGLE: &'static str = "google";
+}
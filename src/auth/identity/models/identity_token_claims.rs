@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+pub struct IdentityTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    // apple sends "true" as a string, google sends a boolean
+    pub email_verified: Option<Value>,
+    pub nonce: Option<String>,
+}
+
+impl IdentityTokenClaims {
+    pub fn is_email_verified(&self) -> bool {
+        return match &self.email_verified {
+            Some(Value::Bool(email_verified)) => *email_verified,
+            Some(Value::String(email_verified)) => email_verified == "true",
+            _ => false,
+        };
+    }
+}
@@ -0,0 +1,2 @@
+pub mod identity_token_claims;
+pub mod user_identity;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::app::util::time;
+
+// an apple or google account linked to a user
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct UserIdentity {
+    pub id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub created_at: i64,
+}
+
+impl UserIdentity {
+    pub fn new(user_id: &str, provider: &str, subject: &str, email: Option<String>) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            email,
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::{jwk::JwkSet, DecodingKey};
+use tokio::sync::RwLock;
+
+use crate::{
+    app::{models::app_error::AppError, util::time},
+    auth::identity::config::{JWKS_CACHE_TTL, JWKS_MIN_REFRESH_INTERVAL},
+};
+
+struct CachedJwkSet {
+    jwk_set: JwkSet,
+    fetched_at: u64,
+}
+
+// signing keys of identity providers, keyed by jwks url
+pub struct JwksCache {
+    jwk_sets: RwLock<HashMap<&'static str, CachedJwkSet>>,
+}
+
+impl JwksCache {
+    pub fn new() -> Self {
+        return Self {
+            jwk_sets: RwLock::new(HashMap::new()),
+        };
+    }
+
+    pub async fn get_decoding_key(
+        &self,
+        url: &'static str,
+        kid: &str,
+        client: &reqwest::Client,
+    ) -> Result<DecodingKey, AppError> {
+        let current_time = time::current_time_in_secs();
+
+        {
+            let jwk_sets = self.jwk_sets.read().await;
+
+            if let Some(cached) = jwk_sets.get(url) {
+                let age = current_time - cached.fetched_at;
+
+                match cached.jwk_set.find(kid) {
+                    Some(jwk) if age < JWKS_CACHE_TTL => return to_decoding_key(jwk),
+                    None if age < JWKS_MIN_REFRESH_INTERVAL => {
+                        return Err(AppError {
+                            message: format!("unknown kid {}", kid),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut jwk_sets = self.jwk_sets.write().await;
+
+        match fetch_jwk_set(url, client).await {
+            Ok(jwk_set) => {
+                jwk_sets.insert(
+                    url,
+                    CachedJwkSet {
+                        jwk_set,
+                        fetched_at: current_time,
+                    },
+                );
+            }
+            Err(e) => {
+                // stale keys still verify while the provider is unreachable
+                tracing::warn!("get_decoding_key failed to fetch {}: {:?}", url, e);
+            }
+        }
+
+        let Some(jwk) = jwk_sets
+            .get(url)
+            .and_then(|cached| cached.jwk_set.find(kid))
+        else {
+            return Err(AppError {
+                message: format!("unknown kid {}", kid),
+            });
+        };
+
+        return to_decoding_key(jwk);
+    }
+}
+
+async fn fetch_jwk_set(url: &str, client: &reqwest::Client) -> Result<JwkSet, reqwest::Error> {
+    return client.get(url).send().await?.json::<JwkSet>().await;
+}
+
+fn to_decoding_key(jwk: &jsonwebtoken::jwk::Jwk) -> Result<DecodingKey, AppError> {
+    return DecodingKey::from_jwk(jwk).map_err(|e| AppError {
+        message: e.to_string(),
+    });
+}
@@ -0,0 +1 @@
+pub mod jwks_cache;
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{decode, decode_header, Algorithm, Validation};
+
+use crate::{
+    app::{models::api_error::ApiError, util::hasher},
+    auth::{
+        errors::AuthApiError,
+        identity::{
+            config::{APPLE_ISSUER, APPLE_JWKS_URL, GOOGLE_ISSUERS, GOOGLE_JWKS_URL},
+            enums::identity_provider::IdentityProvider,
+            models::identity_token_claims::IdentityTokenClaims,
+        },
+    },
+    AppState,
+};
+
+pub async fn verify_identity_token(
+    provider: &str,
+    identity_token: &str,
+    nonce: &str,
+    state: &Arc<AppState>,
+) -> Result<IdentityTokenClaims, ApiError> {
+    let (jwks_url, issuers, audience): (&'static str, Vec<&str>, &str) = match provider {
+        IdentityProvider::APPLE => (
+            APPLE_JWKS_URL,
+            vec![APPLE_ISSUER],
+            &state.envy.apple_client_id,
+        ),
+        IdentityProvider::GOOGLE => (
+            GOOGLE_JWKS_URL,
+            GOOGLE_ISSUERS.to_vec(),
+            &state.envy.google_client_id,
+        ),
+        _ => return Err(AuthApiError::InvalidIdentityToken.value()),
+    };
+
+    let Ok(header) = decode_header(identity_token) else {
+        return Err(AuthApiError::InvalidIdentityToken.value());
+    };
+    let Some(kid) = header.kid else {
+        return Err(AuthApiError::InvalidIdentityToken.value());
+    };
+
+    let decoding_key = match state
+        .jwks_cache
+        .get_decoding_key(jwks_url, &kid, &state.client)
+        .await
+    {
+        Ok(decoding_key) => decoding_key,
+        Err(e) => {
+            tracing::warn!("verify_identity_token: {:?}", e);
+            return Err(AuthApiError::InvalidIdentityToken.value());
+        }
+    };
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&issuers);
+    validation.set_audience(&[audience]);
+
+    let claims = match decode::<IdentityTokenClaims>(identity_token, &decoding_key, &validation) {
+        Ok(data) => data.claims,
+        Err(e) => {
+            tracing::debug!("verify_identity_token: {:?}", e);
+            return Err(AuthApiError::InvalidIdentityToken.value());
+        }
+    };
+
+    // binds the token to the sign in that requested it, so a leaked token can't be replayed
+    let expected_nonce = match provider {
+        IdentityProvider::APPLE => hasher::hash_token(nonce),
+        _ => nonce.to_string(),
+    };
+    if claims.nonce.as_deref() != Some(expected_nonce.as_str()) {
+        return Err(AuthApiError::InvalidIdentityToken.value());
+    }
+
+    return Ok(claims);
+}
@@ -1 +1,3 @@
 pub static JWT_EXP: u64 = 3600;
+// prefix of the aud claim of every token this api signs
+pub static JWT_AUDIENCE: &str = "mist-api";
@@ -1,2 +1,3 @@
-pub mod pepper_type;
+pub mod permissions;
 pub mod roles;
+pub mod token_type;
@@ -0,0 +1,42 @@
+// granted through a role, or directly to a user on top of their roles
+#[non_exhaustive]
+pub struct Permissions;
+
+impl Permissions {
+    pub const FEATURE_POSTS: &'static str = "posts:feature";
+    pub const DELETE_ANY_POST: &'static str = "posts:delete_any";
+    pub const EDIT_API_STATUS: &'static str = "api_status:edit";
+    pub const ADJUST_INK: &'static str = "ink:adjust";
+    pub const MANAGE_ROLES: &'static str = "roles:manage";
+    pub const MANAGE_PRODUCTS: &'static str = "products:manage";
+    pub const MANAGE_PRICING: &'static str = "pricing:manage";
+    pub const MANAGE_PROMO_CODES: &'static str = "promo_codes:manage";
+
+    pub const ALL: [&'static str; 8] = [
+        Self::FEATURE_POSTS,
+        Self::DELETE_ANY_POST,
+        Self::EDIT_API_STATUS,
+        Self::ADJUST_INK,
+        Self::MANAGE_ROLES,
+        Self::MANAGE_PRODUCTS,
+        Self::MANAGE_PRICING,
+        Self::MANAGE_PROMO_CODES,
+    ];
+
+    // permissions that move ink or money, or hand out other permissions
+    pub const ADMIN_ONLY: [&'static str; 5] = [
+        Self::ADJUST_INK,
+        Self::MANAGE_ROLES,
+        Self::MANAGE_PRODUCTS,
+        Self::MANAGE_PRICING,
+        Self::MANAGE_PROMO_CODES,
+    ];
+
+    pub fn all() -> [&'static str; 8] {
+        return Self::ALL;
+    }
+
+    pub fn is_admin_only(permission: &str) -> bool {
+        return Self::ADMIN_ONLY.contains(&permission);
+    }
+}
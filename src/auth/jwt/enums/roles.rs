@@ -1,7 +1,22 @@
+use super::permissions::Permissions;
+
 #[non_exhaustive]
 pub struct Roles;
 
 impl Roles {
     pub const MODERATOR: &'static str = "mod";
     pub const ADMIN: &'static str = "admin";
+
+    pub fn all() -> [&'static str; 2] {
+        return [Self::MODERATOR, Self::ADMIN];
+    }
+
+    // permissions every holder of the role has
+    pub fn permissions(role: &str) -> &'static [&'static str] {
+        match role {
+            Self::MODERATOR => &[Permissions::FEATURE_POSTS, Permissions::DELETE_ANY_POST],
+            Self::ADMIN => &Permissions::ALL,
+            _ => &[],
+        }
+    }
 }
@@ -0,0 +1,18 @@
+use crate::auth::jwt::config::JWT_AUDIENCE;
+
+// what a token may be used for, carried in its typ claim and audience
+#[non_exhaustive]
+pub struct TokenType;
+
+impl TokenType {
+    pub const ACCESS: &'static str = "access";
+    pub const EDIT_PASSWORD: &'static str = "edit-password";
+    pub const EDIT_EMAIL: &'static str = "edit-email";
+    pub const VERIFY_EMAIL: &'static str = "verify-email";
+    pub const TWO_FACTOR: &'static str = "two-factor";
+    pub const UNLOCK_ACCOUNT: &'static str = "unlock-account";
+
+    pub fn audience(token_type: &str) -> String {
+        return [JWT_AUDIENCE, ":", token_type].concat();
+    }
+}
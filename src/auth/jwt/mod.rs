@@ -1,4 +1,5 @@
 pub mod config;
 pub mod enums;
 pub mod models;
+pub mod structs;
 pub mod util;
@@ -1,13 +1,18 @@
-use axum::{
-    headers::{authorization::Bearer, Authorization},
-    http::StatusCode,
-};
+use std::sync::Arc;
+
+use axum::http::StatusCode;
 use jsonwebtoken::errors::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app::models::api_error::ApiError,
-    auth::jwt::{enums::roles::Roles, util::decode_jwt},
+    api_keys::{self, models::api_key::ApiKey},
+    app::{models::api_error::ApiError, util::time},
+    auth::jwt::{
+        enums::{roles::Roles, token_type::TokenType},
+        structs::{bearer_authorization::BearerAuthorization, jwt_keys::JwtKeys},
+        util::decode_jwt,
+    },
+    AppState,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,16 +20,31 @@ pub struct Claims {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    pub typ: String,
+    pub aud: String,
     pub iat: u64,
     pub exp: u64,
+    // set when the request was authenticated with an api key instead of a jwt
+    #[serde(skip)]
+    pub api_key_id: Option<String>,
 }
 
 impl Claims {
     pub fn from_header(
-        authorization: Authorization<Bearer>,
-        secret: &str,
+        authorization: BearerAuthorization,
+        keys: &JwtKeys,
     ) -> Result<Self, ApiError> {
-        match decode_jwt(authorization.0.token().to_string(), secret, None) {
+        if let Some(claims) = authorization.claims {
+            return Ok(claims);
+        }
+
+        match decode_jwt(
+            authorization.authorization.0.token().to_string(),
+            keys,
+            TokenType::ACCESS,
+        ) {
             Ok(claims) => return Ok(claims),
             Err(e) => match e {
                 ErrorKind::ExpiredSignature => {
@@ -43,6 +63,46 @@ impl Claims {
         }
     }
 
+    // accepts an api key in place of a jwt, as long as it has the scope
+    pub async fn from_header_or_api_key(
+        authorization: BearerAuthorization,
+        scope: &str,
+        state: &Arc<AppState>,
+    ) -> Result<Self, ApiError> {
+        let api_key_result = match authorization.api_key {
+            Some(api_key) => api_keys::service::authorize_api_key(api_key, scope, state).await,
+            None => {
+                let token = authorization.authorization.0.token();
+
+                if !api_keys::service::is_api_key(token) {
+                    return Self::from_header(authorization, &state.jwt_keys);
+                }
+
+                api_keys::service::authenticate_api_key(token, scope, state).await
+            }
+        };
+
+        match api_key_result {
+            Ok(api_key) => return Ok(Self::from_api_key(api_key)),
+            Err(e) => return Err(e),
+        }
+    }
+
+    fn from_api_key(api_key: ApiKey) -> Self {
+        let current_time = time::current_time_in_secs();
+
+        return Self {
+            id: api_key.user_id,
+            roles: None,
+            permissions: None,
+            typ: TokenType::ACCESS.to_string(),
+            aud: TokenType::audience(TokenType::ACCESS),
+            iat: current_time,
+            exp: current_time,
+            api_key_id: Some(api_key.id),
+        };
+    }
+
     pub fn is_mod(&self) -> bool {
         let Some(roles) = &self.roles else {
             return false;
@@ -50,4 +110,29 @@ impl Claims {
 
         return roles.contains(&Roles::MODERATOR.to_string());
     }
+
+    pub fn is_admin(&self) -> bool {
+        let Some(roles) = &self.roles else {
+            return false;
+        };
+
+        return roles.contains(&Roles::ADMIN.to_string());
+    }
+
+    // from any of the roles, or granted directly
+    pub fn has_permission(&self, permission: &str) -> bool {
+        if let Some(permissions) = &self.permissions {
+            if permissions.iter().any(|p| p == permission) {
+                return true;
+            }
+        }
+
+        let Some(roles) = &self.roles else {
+            return false;
+        };
+
+        return roles
+            .iter()
+            .any(|role| Roles::permissions(role).contains(&permission));
+    }
 }
@@ -0,0 +1,39 @@
+use axum::{
+    async_trait,
+    extract::{rejection::TypedHeaderRejection, FromRequestParts},
+    headers::{authorization::Bearer, Authorization},
+    http::request::Parts,
+    TypedHeader,
+};
+
+use crate::{api_keys::models::api_key::ApiKey, auth::jwt::models::claims::Claims};
+
+// the bearer token of a request, along with what the rate limit middleware
+// already verified from it, so handlers don't verify the same token twice
+pub struct BearerAuthorization {
+    pub authorization: Authorization<Bearer>,
+    pub claims: Option<Claims>,
+    pub api_key: Option<ApiKey>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for BearerAuthorization
+where
+    S: Send + Sync,
+{
+    type Rejection = TypedHeaderRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authorization =
+            match TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state).await {
+                Ok(TypedHeader(authorization)) => authorization,
+                Err(e) => return Err(e),
+            };
+
+        return Ok(Self {
+            authorization,
+            claims: parts.extensions.get::<Claims>().cloned(),
+            api_key: parts.extensions.get::<ApiKey>().cloned(),
+        });
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::ErrorKind,
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet},
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::app::{envy::Envy, models::app_error::AppError};
+
+struct VerificationKey {
+    algorithm: Algorithm,
+    decoding_key: DecodingKey,
+}
+
+// signs with a single private key and verifies against every published public key,
+// so a new key can be rolled out while tokens signed by the previous one stay valid
+pub struct JwtKeys {
+    kid: String,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    verification_keys: HashMap<String, VerificationKey>,
+    jwk_set: JwkSet,
+}
+
+impl JwtKeys {
+    pub fn from_envy(envy: &Envy) -> Result<Self, AppError> {
+        let Ok(jwk_set) = serde_json::from_str::<JwkSet>(&envy.jwt_verification_keys) else {
+            return Err(AppError {
+                message: "jwt_verification_keys is not a jwk set.".to_string(),
+            });
+        };
+
+        let mut verification_keys = HashMap::new();
+
+        for jwk in &jwk_set.keys {
+            let Some(kid) = &jwk.common.key_id else {
+                return Err(AppError {
+                    message: "Every jwt verification key needs a kid.".to_string(),
+                });
+            };
+
+            let Some(algorithm) = algorithm_of(jwk) else {
+                return Err(AppError {
+                    message: ["Unsupported jwt verification key ", kid, "."].concat(),
+                });
+            };
+
+            let Ok(decoding_key) = DecodingKey::from_jwk(jwk) else {
+                return Err(AppError {
+                    message: ["Invalid jwt verification key ", kid, "."].concat(),
+                });
+            };
+
+            verification_keys.insert(
+                kid.to_string(),
+                VerificationKey {
+                    algorithm,
+                    decoding_key,
+                },
+            );
+        }
+
+        let kid = envy.jwt_signing_key_id.to_string();
+
+        let Some(verification_key) = verification_keys.get(&kid) else {
+            return Err(AppError {
+                message: "jwt_signing_key_id is not in jwt_verification_keys.".to_string(),
+            });
+        };
+        let algorithm = verification_key.algorithm;
+
+        // env files can't hold newlines, so pem keys may come with them escaped
+        let pem = envy.jwt_signing_key.replace("\\n", "\n");
+        let encoding_key_result = match algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(pem.as_bytes()),
+            Algorithm::ES256 | Algorithm::ES384 => EncodingKey::from_ec_pem(pem.as_bytes()),
+            _ => EncodingKey::from_rsa_pem(pem.as_bytes()),
+        };
+        let Ok(encoding_key) = encoding_key_result else {
+            return Err(AppError {
+                message: "jwt_signing_key is not a valid pem private key.".to_string(),
+            });
+        };
+
+        let jwt_keys = Self {
+            kid,
+            algorithm,
+            encoding_key,
+            verification_keys,
+            jwk_set,
+        };
+
+        // a signing key that doesn't match its published public key would
+        // issue tokens nobody can verify
+        let probe = jwt_keys.sign(&HashMap::from([("exp", u64::MAX)]));
+        let probe_result = probe.map(|probe| {
+            let mut validation = Validation::new(algorithm);
+            validation.required_spec_claims.clear();
+
+            return jwt_keys.decode_with::<HashMap<String, u64>>(&probe, &validation);
+        });
+        if !matches!(probe_result, Ok(Ok(_))) {
+            return Err(AppError {
+                message: "jwt_signing_key does not match its verification key.".to_string(),
+            });
+        }
+
+        return Ok(jwt_keys);
+    }
+
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, ErrorKind> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = Some(self.kid.to_string());
+
+        match encode(&header, claims, &self.encoding_key) {
+            Ok(jwt) => Ok(jwt),
+            Err(e) => Err(e.kind().to_owned()),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, jwt: &str, audience: &str) -> Result<T, ErrorKind> {
+        let mut validation = Validation::new(self.algorithm);
+        validation.set_audience(&[audience]);
+
+        return self.decode_with(jwt, &validation);
+    }
+
+    fn decode_with<T: DeserializeOwned>(
+        &self,
+        jwt: &str,
+        validation: &Validation,
+    ) -> Result<T, ErrorKind> {
+        let header = match decode_header(jwt) {
+            Ok(header) => header,
+            Err(e) => return Err(e.kind().to_owned()),
+        };
+
+        let Some(verification_key) = header.kid.and_then(|kid| self.verification_keys.get(&kid))
+        else {
+            return Err(ErrorKind::InvalidToken);
+        };
+
+        let mut validation = validation.clone();
+        validation.algorithms = vec![verification_key.algorithm];
+
+        match decode::<T>(jwt, &verification_key.decoding_key, &validation) {
+            Ok(data) => Ok(data.claims),
+            Err(e) => Err(e.kind().to_owned()),
+        }
+    }
+
+    // public keys, served so other services can verify our tokens
+    pub fn jwk_set(&self) -> &JwkSet {
+        return &self.jwk_set;
+    }
+}
+
+// symmetric keys are refused, publishing one would hand out the signing secret
+fn algorithm_of(jwk: &Jwk) -> Option<Algorithm> {
+    if let Some(algorithm) = jwk.common.algorithm {
+        return match algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => None,
+            _ => Some(algorithm),
+        };
+    }
+
+    return match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => Some(Algorithm::RS256),
+        AlgorithmParameters::OctetKeyPair(params) => match params.curve {
+            EllipticCurve::Ed25519 => Some(Algorithm::EdDSA),
+            _ => None,
+        },
+        AlgorithmParameters::EllipticCurve(params) => match params.curve {
+            EllipticCurve::P256 => Some(Algorithm::ES256),
+            EllipticCurve::P384 => Some(Algorithm::ES384),
+            _ => None,
+        },
+        AlgorithmParameters::OctetKey(_) => None,
+    };
+}
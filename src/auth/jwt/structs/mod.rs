@@ -0,0 +1,2 @@
+pub mod bearer_authorization;
+pub mod jwt_keys;
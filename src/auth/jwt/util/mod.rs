@@ -1,18 +1,21 @@
-use jsonwebtoken::{
-    decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
-};
+use jsonwebtoken::errors::ErrorKind;
 
 use crate::{
-    app::util::time, auth::jwt::models::claims::Claims, devices::models::device::Device,
+    app::util::time,
+    auth::jwt::{enums::token_type::TokenType, models::claims::Claims, structs::jwt_keys::JwtKeys},
+    devices::models::device::Device,
     users::models::user::User,
 };
 
 use super::config::JWT_EXP;
 
-pub fn sign_jwt(user: &User, secret: &str, pepper: Option<&str>) -> String {
-    let mut secret = secret.to_string();
+pub fn sign_jwt(user: &User, keys: &JwtKeys, token_type: &str) -> String {
+    return sign_jwt_with_exp(user, keys, token_type, JWT_EXP);
+}
+
+pub fn sign_jwt_with_exp(user: &User, keys: &JwtKeys, token_type: &str, exp: u64) -> String {
     let iat = time::current_time_in_secs();
-    let exp = iat + JWT_EXP;
+    let exp = iat + exp;
 
     let claims = Claims {
         id: user.id.to_string(),
@@ -20,23 +23,19 @@ pub fn sign_jwt(user: &User, secret: &str, pepper: Option<&str>) -> String {
             Some(roles) => Some(roles.clone()),
             None => None,
         },
+        permissions: user.permissions.clone(),
+        typ: token_type.to_string(),
+        aud: TokenType::audience(token_type),
         iat,
         exp,
+        api_key_id: None,
     };
-    if let Some(pepper) = pepper {
-        secret = [&secret, pepper].concat();
-    }
 
     // FIXME: unsafe unwrap
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
-    .unwrap()
+    keys.sign(&claims).unwrap()
 }
 
-pub fn sign_jwt_with_device(device: Device, secret: &str) -> String {
+pub fn sign_jwt_with_device(device: Device, keys: &JwtKeys) -> String {
     let iat = time::current_time_in_secs();
     let exp = iat + JWT_EXP;
 
@@ -46,34 +45,25 @@ pub fn sign_jwt_with_device(device: Device, secret: &str) -> String {
             Some(roles) => Some(roles),
             None => None,
         },
+        permissions: device.permissions,
+        typ: TokenType::ACCESS.to_string(),
+        aud: TokenType::audience(TokenType::ACCESS),
         iat,
         exp,
+        api_key_id: None,
     };
 
     // FIXME: unsafe unwrap
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
-    .unwrap()
+    keys.sign(&claims).unwrap()
 }
 
-pub fn decode_jwt(jwt: String, secret: &str, pepper: Option<&str>) -> Result<Claims, ErrorKind> {
-    let mut secret = secret.to_string();
+// a token only decodes for the purpose it was signed for
+pub fn decode_jwt(jwt: String, keys: &JwtKeys, token_type: &str) -> Result<Claims, ErrorKind> {
+    let claims = keys.decode::<Claims>(&jwt, &TokenType::audience(token_type))?;
 
-    if let Some(pepper) = pepper {
-        secret = [&secret, pepper].concat();
+    if claims.typ != token_type {
+        return Err(ErrorKind::InvalidToken);
     }
 
-    let result = decode::<Claims>(
-        &jwt,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::new(Algorithm::HS256),
-    );
-
-    match result {
-        Ok(data) => Ok(data.claims),
-        Err(e) => Err(e.kind().to_owned()),
-    }
+    Ok(claims)
 }
@@ -1,6 +1,10 @@
 pub mod controller;
 pub mod dtos;
+pub mod enums;
 pub mod errors;
+pub mod identity;
 pub mod jwt;
 pub mod models;
 pub mod service;
+pub mod throttles;
+pub mod totp;
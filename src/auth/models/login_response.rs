@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+use super::{access_info::AccessInfo, two_factor_challenge::TwoFactorChallenge};
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    AccessInfo(AccessInfo),
+    TwoFactorChallenge(TwoFactorChallenge),
+}
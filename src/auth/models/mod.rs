@@ -1 +1,3 @@
 pub mod access_info;
+pub mod login_response;
+pub mod two_factor_challenge;
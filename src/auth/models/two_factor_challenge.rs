@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+// returned by login instead of access info when the user has two factor enabled
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallenge {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+}
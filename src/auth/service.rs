@@ -7,7 +7,7 @@ use sqlx::PgPool;
 use tokio::time::sleep;
 
 use crate::{
-    app::{errors::DefaultApiError, models::api_error::ApiError, util::hasher},
+    app::{envy::Envy, errors::DefaultApiError, models::api_error::ApiError, util::hasher},
     devices::{
         self,
         dtos::{
@@ -21,84 +21,459 @@ use crate::{
         templates::{
             request_email_update_template::request_email_update_template,
             request_password_update_template::request_password_update_template,
+            unlock_account_template::unlock_account_template,
+            verify_email_template::verify_email_template,
         },
     },
-    users, AppState,
+    users::{self, config::STARTER_INK, errors::UsersApiError, models::user::User},
+    AppState,
 };
 
 use super::{
     dtos::{
         delete_account_dto::DeleteAccountDto, edit_password_dto::EditPasswordDto,
+        identity_login_dto::IdentityLoginDto, link_identity_dto::LinkIdentityDto,
         login_dto::LoginDto, register_dto::RegisterDto,
         request_email_update_dto::RequestEmailUpdateDto,
         request_password_update_dto::RequestPasswordUpdateDto,
+        two_factor_login_dto::TwoFactorLoginDto,
     },
+    enums::email_verification_policy::EmailVerificationPolicy,
     errors::AuthApiError,
+    identity::{
+        models::{identity_token_claims::IdentityTokenClaims, user_identity::UserIdentity},
+        util::verify_identity_token,
+    },
     jwt::{
-        enums::pepper_type::PepperType,
+        enums::token_type::TokenType,
         models::claims::Claims,
-        util::{decode_jwt, sign_jwt, sign_jwt_with_device},
+        util::{decode_jwt, sign_jwt, sign_jwt_with_device, sign_jwt_with_exp},
+    },
+    models::{
+        access_info::AccessInfo, login_response::LoginResponse,
+        two_factor_challenge::TwoFactorChallenge,
+    },
+    throttles::{
+        self,
+        config::{
+            ACCOUNT_LOCKOUT_THRESHOLD, ATTEMPT_WINDOW, IP_LOCKOUT_THRESHOLD, LOCKOUT_DURATION,
+            PASSWORD_MAIL_ACCOUNT_LIMIT, PASSWORD_MAIL_IP_LIMIT,
+        },
+        enums::throttle_action::ThrottleAction,
     },
-    models::access_info::AccessInfo,
+    totp::{self, config::TWO_FACTOR_CHALLENGE_EXP},
 };
 
-pub async fn register(dto: &RegisterDto, state: &Arc<AppState>) -> Result<AccessInfo, ApiError> {
-    match users::service::create_user_as_admin(dto, &state.pool).await {
-        Ok(_) => {
+pub async fn register(
+    dto: &RegisterDto,
+    client_ip: &str,
+    state: &Arc<AppState>,
+) -> Result<LoginResponse, ApiError> {
+    let ink_withheld = withheld_starter_ink(&state.envy);
+
+    match users::service::create_user_as_admin(
+        dto,
+        STARTER_INK - ink_withheld,
+        ink_withheld,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(user) => {
+            spawn_verify_email_mail(user, state);
+
             let login_dto = LoginDto {
                 username: None,
                 email: Some(dto.email.to_string()),
                 password: dto.password.to_string(),
             };
 
-            return login(&login_dto, state).await;
+            return login(&login_dto, client_ip, state).await;
         }
         Err(e) => Err(e),
     }
 }
 
-pub async fn login(dto: &LoginDto, state: &Arc<AppState>) -> Result<AccessInfo, ApiError> {
-    match users::service::get_user_by_login_dto_as_admin(dto, &state.pool).await {
-        Ok(user) => {
-            let sleep_duration = rand::thread_rng().gen_range(0.2..1.0);
-            sleep(Duration::from_secs_f32(sleep_duration)).await;
+// attempts are counted before the password is checked, so concurrent guesses
+// can't outrun the lockout. locked and unknown accounts fail like a wrong password.
+pub async fn login(
+    dto: &LoginDto,
+    client_ip: &str,
+    state: &Arc<AppState>,
+) -> Result<LoginResponse, ApiError> {
+    let ip_key = throttles::util::ip_key(ThrottleAction::LOGIN, client_ip);
+    let ip_throttle = match throttles::service::record_attempt(
+        &ip_key,
+        IP_LOCKOUT_THRESHOLD,
+        LOCKOUT_DURATION,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(ip_throttle) => ip_throttle,
+        Err(e) => return Err(e),
+    };
+    if ip_throttle.is_locked() {
+        return Err(AuthApiError::TooManyAttempts.value());
+    }
 
-            if user.delete_pending {
+    let user = match users::service::get_user_by_login_dto_as_admin(dto, &state.pool).await {
+        Ok(user) => user,
+        Err(e) => {
+            sleep(login_delay(ip_throttle.login_delay_millis())).await;
+
+            if e.code == StatusCode::NOT_FOUND {
                 return Err(AuthApiError::BadLogin.value());
+            } else {
+                return Err(e);
             }
+        }
+    };
 
-            let Ok(matches) =
-                hasher::verify(dto.password.to_string(), user.password_hash.to_string()).await
-            else {
-                return Err(AuthApiError::BadLogin.value());
-            };
+    let account_key = throttles::util::account_key(ThrottleAction::LOGIN, &user.id);
+    let account_throttle = match throttles::service::record_attempt(
+        &account_key,
+        ACCOUNT_LOCKOUT_THRESHOLD,
+        LOCKOUT_DURATION,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(account_throttle) => account_throttle,
+        Err(e) => return Err(e),
+    };
 
-            if !matches {
-                return Err(AuthApiError::BadLogin.value());
+    sleep(login_delay(
+        ip_throttle
+            .login_delay_millis()
+            .max(account_throttle.login_delay_millis()),
+    ))
+    .await;
+
+    if account_throttle.is_locked() {
+        if account_throttle.is_newly_locked() {
+            on_account_locked(&user, state);
+        }
+
+        return Err(AuthApiError::BadLogin.value());
+    }
+
+    if user.delete_pending {
+        return Err(AuthApiError::BadLogin.value());
+    }
+
+    let Some(password_hash) = &user.password_hash else {
+        return Err(AuthApiError::BadLogin.value());
+    };
+
+    let Ok(matches) = hasher::verify(dto.password.to_string(), password_hash.to_string()).await
+    else {
+        return Err(AuthApiError::BadLogin.value());
+    };
+
+    if !matches {
+        return Err(AuthApiError::BadLogin.value());
+    }
+
+    if let Err(e) = throttles::service::clear_throttle(&account_key, &state.pool).await {
+        tracing::error!("login: {:?}", e);
+    }
+    if let Err(e) = throttles::service::release_attempt(&ip_key, &state.pool).await {
+        tracing::error!("login: {:?}", e);
+    }
+
+    return create_login_response(&user, state).await;
+}
+
+// random jitter so timings don't tell whether the account exists,
+// plus the progressive delay earned by previous attempts
+fn login_delay(delay_millis: u64) -> Duration {
+    let jitter = rand::thread_rng().gen_range(0.2..1.0);
+
+    return Duration::from_secs_f32(jitter) + Duration::from_millis(delay_millis);
+}
+
+// the owner learns about the lock by mail rather than from the login response,
+// which would tell anyone guessing that the account exists
+fn on_account_locked(user: &User, state: &Arc<AppState>) {
+    tracing::warn!("locked account {} after failed logins", user.id);
+
+    let envy = state.envy.clone();
+    let user = user.clone();
+    let access_token = sign_jwt(&user, &state.jwt_keys, TokenType::UNLOCK_ACCOUNT);
+
+    tokio::spawn(async move {
+        let template = unlock_account_template(&user, &access_token, &envy.frontend_url);
+        mail::service::send_mail(&user.email, &template.0, &template.1, &envy).await
+    });
+}
+
+pub async fn process_account_unlock(
+    access_token: &str,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    match decode_jwt(
+        access_token.to_string(),
+        &state.jwt_keys,
+        TokenType::UNLOCK_ACCOUNT,
+    ) {
+        Ok(claims) => {
+            let account_key = throttles::util::account_key(ThrottleAction::LOGIN, &claims.id);
+            throttles::service::clear_throttle(&account_key, &state.pool).await
+        }
+        Err(e) => match e {
+            ErrorKind::ExpiredSignature => Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Token has expired.".to_string(),
+            }),
+            _ => Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Invalid token.".to_string(),
+            }),
+        },
+    }
+}
+
+pub async fn login_with_identity(
+    provider: &str,
+    dto: &IdentityLoginDto,
+    state: &Arc<AppState>,
+) -> Result<LoginResponse, ApiError> {
+    let claims_result =
+        verify_identity_token(provider, &dto.identity_token, &dto.nonce, state).await;
+    let Ok(claims) = claims_result else {
+        return Err(claims_result.unwrap_err());
+    };
+
+    let user =
+        match users::service::get_user_by_identity_as_admin(provider, &claims.sub, &state.pool)
+            .await
+        {
+            Ok(user) => user,
+            Err(e) if e.code == StatusCode::NOT_FOUND => {
+                match link_or_create_user_with_identity(provider, &claims, dto, state).await {
+                    Ok(user) => user,
+                    Err(e) => return Err(e),
+                }
             }
+            Err(e) => return Err(e),
+        };
 
-            match devices::service::create_device_as_admin(&user, &state.pool).await {
-                Ok(device) => Ok(AccessInfo {
-                    access_token: sign_jwt(&user, &state.envy.jwt_secret, None),
-                    refresh_token: Some(device.refresh_token),
-                    device_id: Some(device.id),
-                }),
-                Err(e) => Err(e),
+    if user.delete_pending {
+        return Err(AuthApiError::BadLogin.value());
+    }
+
+    return create_login_response(&user, state).await;
+}
+
+// links an apple or google account to the logged in user, so it can be used to log in
+pub async fn link_identity(
+    provider: &str,
+    dto: &LinkIdentityDto,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    let identity_claims_result =
+        verify_identity_token(provider, &dto.identity_token, &dto.nonce, state).await;
+    let Ok(identity_claims) = identity_claims_result else {
+        return Err(identity_claims_result.unwrap_err());
+    };
+
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, &state.pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.delete_pending {
+        return Err(ApiError {
+            code: StatusCode::UNAUTHORIZED,
+            message: "This user is being deleted.".to_string(),
+        });
+    }
+
+    let identity = UserIdentity::new(
+        &user.id,
+        provider,
+        &identity_claims.sub,
+        identity_claims.email.clone(),
+    );
+
+    return users::service::create_user_identity_as_admin(&identity, &state.pool).await;
+}
+
+// completes a login challenged for a second factor
+pub async fn login_with_second_factor(
+    dto: &TwoFactorLoginDto,
+    state: &Arc<AppState>,
+) -> Result<AccessInfo, ApiError> {
+    let claims = match decode_jwt(
+        dto.challenge_token.to_string(),
+        &state.jwt_keys,
+        TokenType::TWO_FACTOR,
+    ) {
+        Ok(claims) => claims,
+        Err(e) => match e {
+            ErrorKind::ExpiredSignature => {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: "Token has expired.".to_string(),
+                })
+            }
+            _ => {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: "Invalid token.".to_string(),
+                })
+            }
+        },
+    };
+
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, &state.pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.delete_pending {
+        return Err(AuthApiError::BadLogin.value());
+    }
+
+    if let Err(e) =
+        totp::service::verify_second_factor(&user, &dto.totp_code, &dto.recovery_code, &state.pool)
+            .await
+    {
+        return Err(e);
+    }
+
+    return create_access_info(&user, state).await;
+}
+
+async fn create_login_response(
+    user: &User,
+    state: &Arc<AppState>,
+) -> Result<LoginResponse, ApiError> {
+    if user.totp_enabled_at.is_some() {
+        return Ok(LoginResponse::TwoFactorChallenge(TwoFactorChallenge {
+            two_factor_required: true,
+            challenge_token: sign_jwt_with_exp(
+                user,
+                &state.jwt_keys,
+                TokenType::TWO_FACTOR,
+                TWO_FACTOR_CHALLENGE_EXP,
+            ),
+        }));
+    }
+
+    match create_access_info(user, state).await {
+        Ok(access_info) => Ok(LoginResponse::AccessInfo(access_info)),
+        Err(e) => Err(e),
+    }
+}
+
+// an identity with a verified email is linked to the user owning that email,
+// otherwise it signs up a new user. accounts whose own email was never verified
+// are not linked, whoever registered them may not own the email and would keep
+// access through their password and devices
+async fn link_or_create_user_with_identity(
+    provider: &str,
+    claims: &IdentityTokenClaims,
+    dto: &IdentityLoginDto,
+    state: &Arc<AppState>,
+) -> Result<User, ApiError> {
+    let Some(email) = &claims.email else {
+        return Err(AuthApiError::EmailRequired.value());
+    };
+    let email_verified = claims.is_email_verified();
+
+    match users::service::get_user_by_email_as_admin(email, &state.pool).await {
+        Ok(user) => {
+            if !email_verified {
+                return Err(ApiError {
+                    code: StatusCode::CONFLICT,
+                    message: "Email already exists.".to_string(),
+                });
+            }
+
+            if user.email_verified_at.is_none() {
+                return Err(ApiError {
+                    code: StatusCode::CONFLICT,
+                    message: "Email already exists. Log in and link this account from settings."
+                        .to_string(),
+                });
+            }
+
+            let identity = UserIdentity::new(&user.id, provider, &claims.sub, Some(email.clone()));
+
+            if let Err(e) =
+                users::service::create_user_identity_as_admin(&identity, &state.pool).await
+            {
+                return Err(e);
             }
+
+            Ok(user)
         }
-        Err(e) => {
-            let sleep_duration = rand::thread_rng().gen_range(0.2..1.0);
-            sleep(Duration::from_secs_f32(sleep_duration)).await;
+        Err(e) if e.code == StatusCode::NOT_FOUND => {
+            let Some(username) = &dto.username else {
+                return Err(AuthApiError::UsernameRequired.value());
+            };
 
-            if e.code == StatusCode::NOT_FOUND {
-                return Err(AuthApiError::BadLogin.value());
-            } else {
-                Err(e)
+            let ink_withheld = match email_verified {
+                true => 0,
+                false => withheld_starter_ink(&state.envy),
+            };
+
+            let user = User::new_from_identity(
+                username,
+                email,
+                email_verified,
+                STARTER_INK - ink_withheld,
+                ink_withheld,
+            );
+            let identity = UserIdentity::new(&user.id, provider, &claims.sub, Some(email.clone()));
+
+            if let Err(e) =
+                users::service::create_user_with_identity_as_admin(&user, &identity, &state.pool)
+                    .await
+            {
+                return Err(e);
+            }
+
+            if !email_verified {
+                spawn_verify_email_mail(user.clone(), state);
             }
+
+            Ok(user)
         }
+        Err(e) => Err(e),
+    }
+}
+
+async fn create_access_info(user: &User, state: &Arc<AppState>) -> Result<AccessInfo, ApiError> {
+    match devices::service::create_device_as_admin(user, &state.pool).await {
+        Ok((device, refresh_token)) => Ok(AccessInfo {
+            access_token: sign_jwt(user, &state.jwt_keys, TokenType::ACCESS),
+            refresh_token: Some(refresh_token),
+            device_id: Some(device.id),
+        }),
+        Err(e) => Err(e),
     }
 }
 
+// starter ink held back from users until they verify their email
+fn withheld_starter_ink(envy: &Envy) -> i64 {
+    return match EmailVerificationPolicy::from_envy(envy) {
+        EmailVerificationPolicy::WITHHOLD_INK => STARTER_INK,
+        _ => 0,
+    };
+}
+
+pub async fn set_password(
+    dto: &EditPasswordDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    return users::service::set_user_password_by_id_as_admin(&claims.id, dto, pool).await;
+}
+
 pub async fn request_email_update_mail(
     dto: &RequestEmailUpdateDto,
     claims: &Claims,
@@ -116,6 +491,13 @@ pub async fn request_email_update_mail(
         });
     }
 
+    if let Err(e) =
+        totp::service::verify_second_factor(&user, &dto.totp_code, &dto.recovery_code, &state.pool)
+            .await
+    {
+        return Err(e);
+    }
+
     if let Ok(_) = users::service::get_user_by_email_as_admin(&dto.email, &state.pool).await {
         return Err(ApiError {
             code: StatusCode::CONFLICT,
@@ -133,9 +515,9 @@ pub async fn request_email_update_mail(
         Ok(_) => {
             let envy = state.envy.clone();
             let email = dto.email.clone();
+            let access_token = sign_jwt(&user, &state.jwt_keys, TokenType::EDIT_EMAIL);
 
             tokio::spawn(async move {
-                let access_token = sign_jwt(&user, &envy.jwt_secret, Some(PepperType::EDIT_EMAIL));
                 let template =
                     request_email_update_template(&user, &access_token, &envy.frontend_url);
                 mail::service::send_mail(&email, &template.0, &template.1, &envy).await
@@ -150,8 +532,8 @@ pub async fn request_email_update_mail(
 pub async fn process_email_edit(access_token: &str, state: &Arc<AppState>) -> Result<(), ApiError> {
     match decode_jwt(
         access_token.to_string(),
-        &state.envy.jwt_secret,
-        Some(PepperType::EDIT_EMAIL),
+        &state.jwt_keys,
+        TokenType::EDIT_EMAIL,
     ) {
         Ok(claims) => {
             users::service::approve_user_email_pending_by_id_as_admin(&claims.id, &state.pool).await
@@ -169,17 +551,106 @@ pub async fn process_email_edit(access_token: &str, state: &Arc<AppState>) -> Re
     }
 }
 
+pub async fn request_email_verification_mail(
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, &state.pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.email_verified_at.is_some() {
+        return Err(UsersApiError::EmailAlreadyVerified.value());
+    }
+
+    spawn_verify_email_mail(user, state);
+
+    Ok(())
+}
+
+fn spawn_verify_email_mail(user: User, state: &Arc<AppState>) {
+    let envy = state.envy.clone();
+    let access_token = sign_jwt(&user, &state.jwt_keys, TokenType::VERIFY_EMAIL);
+
+    tokio::spawn(async move {
+        let template = verify_email_template(&user, &access_token, &envy.frontend_url);
+        mail::service::send_mail(&user.email, &template.0, &template.1, &envy).await
+    });
+}
+
+pub async fn process_email_verification(
+    access_token: &str,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    match decode_jwt(
+        access_token.to_string(),
+        &state.jwt_keys,
+        TokenType::VERIFY_EMAIL,
+    ) {
+        Ok(claims) => {
+            users::service::verify_user_email_by_id_as_admin(&claims.id, &state.pool).await
+        }
+        Err(e) => match e {
+            ErrorKind::ExpiredSignature => Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Token has expired.".to_string(),
+            }),
+            _ => Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Invalid token.".to_string(),
+            }),
+        },
+    }
+}
+
 pub async fn request_password_update_mail(
     dto: &RequestPasswordUpdateDto,
+    client_ip: &str,
     state: &Arc<AppState>,
 ) -> Result<(), ApiError> {
+    let ip_key = throttles::util::ip_key(ThrottleAction::PASSWORD_MAIL, client_ip);
+
+    match throttles::service::record_attempt(
+        &ip_key,
+        PASSWORD_MAIL_IP_LIMIT,
+        ATTEMPT_WINDOW,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(ip_throttle) => {
+            if ip_throttle.is_locked() {
+                return Err(AuthApiError::TooManyAttempts.value());
+            }
+        }
+        Err(e) => return Err(e),
+    }
+
     match users::service::get_user_by_email_as_admin(&dto.email, &state.pool).await {
         Ok(user) => {
+            let account_key = throttles::util::account_key(ThrottleAction::PASSWORD_MAIL, &user.id);
+
+            match throttles::service::record_attempt(
+                &account_key,
+                PASSWORD_MAIL_ACCOUNT_LIMIT,
+                ATTEMPT_WINDOW,
+                &state.pool,
+            )
+            .await
+            {
+                Ok(account_throttle) => {
+                    if account_throttle.is_locked() {
+                        return Err(AuthApiError::TooManyAttempts.value());
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+
             let envy = state.envy.clone();
+            let access_token = sign_jwt(&user, &state.jwt_keys, TokenType::EDIT_PASSWORD);
 
             tokio::spawn(async move {
-                let access_token =
-                    sign_jwt(&user, &envy.jwt_secret, Some(PepperType::EDIT_PASSWORD));
                 let template =
                     request_password_update_template(&user, &access_token, &envy.frontend_url);
                 mail::service::send_mail(&user.email, &template.0, &template.1, &envy).await
@@ -198,8 +669,8 @@ pub async fn process_password_edit(
 ) -> Result<(), ApiError> {
     match decode_jwt(
         access_token.to_string(),
-        &state.envy.jwt_secret,
-        Some(PepperType::EDIT_PASSWORD),
+        &state.jwt_keys,
+        TokenType::EDIT_PASSWORD,
     ) {
         Ok(claims) => {
             users::service::edit_user_password_by_id_as_admin(&claims.id, dto, &state.pool).await
@@ -230,9 +701,9 @@ pub async fn refresh(
     state: &Arc<AppState>,
 ) -> Result<AccessInfo, ApiError> {
     match devices::service::refresh_device_as_admin(dto, &state.pool).await {
-        Ok(device) => Ok(AccessInfo {
-            access_token: sign_jwt_with_device(device, &state.envy.jwt_secret),
-            refresh_token: None,
+        Ok((device, refresh_token)) => Ok(AccessInfo {
+            access_token: sign_jwt_with_device(device, &state.jwt_keys),
+            refresh_token: Some(refresh_token),
             device_id: None,
         }),
         Err(e) => Err(e),
@@ -253,17 +724,30 @@ pub async fn delete_account(
 ) -> Result<(), ApiError> {
     match users::service::get_user_by_id_as_admin(&claims.id, pool).await {
         Ok(user) => {
-            let Ok(matches) =
-                hasher::verify(dto.password.to_string(), user.password_hash.to_string()).await
-            else {
-                return Err(DefaultApiError::InternalServerError.value());
-            };
+            if let Some(password_hash) = &user.password_hash {
+                let Some(password) = &dto.password else {
+                    return Err(AuthApiError::PasswordRequired.value());
+                };
 
-            if !matches {
-                return Err(ApiError {
-                    code: StatusCode::UNAUTHORIZED,
-                    message: "Invalid password.".to_string(),
-                });
+                let Ok(matches) =
+                    hasher::verify(password.to_string(), password_hash.to_string()).await
+                else {
+                    return Err(DefaultApiError::InternalServerError.value());
+                };
+
+                if !matches {
+                    return Err(ApiError {
+                        code: StatusCode::UNAUTHORIZED,
+                        message: "Invalid password.".to_string(),
+                    });
+                }
+            }
+
+            if let Err(e) =
+                totp::service::verify_second_factor(&user, &dto.totp_code, &dto.recovery_code, pool)
+                    .await
+            {
+                return Err(e);
             }
 
             match users::service::set_user_delete_pending_by_id_as_admin(&claims.id, pool).await {
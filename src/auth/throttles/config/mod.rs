@@ -0,0 +1,16 @@
+// seconds after which attempts stop counting
+pub static ATTEMPT_WINDOW: i64 = 3600;
+// failed logins before delays kick in
+pub static FREE_LOGIN_ATTEMPTS: i32 = 3;
+pub static LOGIN_DELAY_BASE_MILLIS: u64 = 250;
+pub static LOGIN_DELAY_MAX_MILLIS: u64 = 8000;
+// login attempts allowed before an account or ip gets locked
+pub static ACCOUNT_LOCKOUT_THRESHOLD: i32 = 10;
+pub static IP_LOCKOUT_THRESHOLD: i32 = 50;
+pub static LOCKOUT_DURATION: i64 = 900;
+// second factor codes allowed before the account's second factor gets locked,
+// shared by login and the settings that ask for a code so it can be much lower
+pub static TWO_FACTOR_LOCKOUT_THRESHOLD: i32 = 5;
+// password mails per attempt window
+pub static PASSWORD_MAIL_ACCOUNT_LIMIT: i32 = 3;
+pub static PASSWORD_MAIL_IP_LIMIT: i32 = 10;
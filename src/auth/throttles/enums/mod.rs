@@ -0,0 +1 @@
+pub mod throttle_action;
@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct ThrottleAction;
+
+impl ThrottleAction {
+    pub const LOGIN: &'static str = "login";
+    pub const PASSWORD_MAIL: &'static str = "password-mail";
+    pub const TWO_FACTOR: &'static str = "two-factor";
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    app::util::time,
+    auth::throttles::config::{
+        FREE_LOGIN_ATTEMPTS, LOGIN_DELAY_BASE_MILLIS, LOGIN_DELAY_MAX_MILLIS,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AuthThrottle {
+    pub key: String,
+    pub attempts: i32,
+    pub window_started_at: i64,
+    pub locked_until: Option<i64>,
+}
+
+impl AuthThrottle {
+    pub fn is_locked(&self) -> bool {
+        let Some(locked_until) = self.locked_until else {
+            return false;
+        };
+
+        return locked_until > time::current_time_in_secs() as i64;
+    }
+
+    // the attempt that locked it, attempts start over from there
+    pub fn is_newly_locked(&self) -> bool {
+        return self.is_locked() && self.attempts == 0;
+    }
+
+    // doubles with every login attempt past the free ones, attempts include
+    // the one being made and successful logins give theirs back
+    pub fn login_delay_millis(&self) -> u64 {
+        if self.attempts <= FREE_LOGIN_ATTEMPTS {
+            return 0;
+        }
+
+        let exponent = (self.attempts - FREE_LOGIN_ATTEMPTS - 1).min(16) as u32;
+
+        return (LOGIN_DELAY_BASE_MILLIS * 2_u64.pow(exponent)).min(LOGIN_DELAY_MAX_MILLIS);
+    }
+}
@@ -0,0 +1 @@
+pub mod auth_throttle;
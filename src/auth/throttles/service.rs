@@ -0,0 +1,122 @@
+use sqlx::PgPool;
+
+use crate::app::{errors::DefaultApiError, models::api_error::ApiError, util::time};
+
+use super::{config::ATTEMPT_WINDOW, models::auth_throttle::AuthThrottle};
+
+// counts an attempt before it is made, in one statement so concurrent attempts
+// can't slip past the limit together. the attempt past the limit locks the key,
+// attempts made while locked keep counting without extending the lock.
+pub async fn record_attempt(
+    key: &str,
+    limit: i32,
+    lockout_duration: i64,
+    pool: &PgPool,
+) -> Result<AuthThrottle, ApiError> {
+    let current_time = time::current_time_in_secs() as i64;
+
+    let sqlx_result = sqlx::query_as::<_, AuthThrottle>(
+        "
+        INSERT INTO auth_throttles (key, attempts, window_started_at, locked_until)
+        VALUES ($1, 1, $2, NULL)
+        ON CONFLICT (key) DO UPDATE SET
+            attempts = CASE
+                WHEN auth_throttles.locked_until > $2 THEN auth_throttles.attempts + 1
+                WHEN auth_throttles.window_started_at <= $3
+                    OR auth_throttles.locked_until <= $2 THEN 1
+                WHEN auth_throttles.attempts + 1 > $4 THEN 0
+                ELSE auth_throttles.attempts + 1
+            END,
+            window_started_at = CASE
+                WHEN auth_throttles.locked_until > $2 THEN auth_throttles.window_started_at
+                WHEN auth_throttles.window_started_at <= $3
+                    OR auth_throttles.locked_until <= $2 THEN $2
+                WHEN auth_throttles.attempts + 1 > $4 THEN $2
+                ELSE auth_throttles.window_started_at
+            END,
+            locked_until = CASE
+                WHEN auth_throttles.locked_until > $2 THEN auth_throttles.locked_until
+                WHEN auth_throttles.window_started_at <= $3
+                    OR auth_throttles.locked_until <= $2 THEN NULL
+                WHEN auth_throttles.attempts + 1 > $4 THEN $5
+                ELSE NULL
+            END
+        RETURNING *
+        ",
+    )
+    .bind(key)
+    .bind(current_time)
+    .bind(current_time - ATTEMPT_WINDOW)
+    .bind(limit)
+    .bind(current_time + lockout_duration)
+    .fetch_one(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(throttle) => Ok(throttle),
+        Err(e) => {
+            tracing::error!("record_attempt: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// gives back an attempt that turned out fine, without touching a lock
+pub async fn release_attempt(key: &str, pool: &PgPool) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE auth_throttles SET attempts = attempts - 1
+        WHERE key = $1 AND attempts > 0 AND (locked_until IS NULL OR locked_until <= $2)
+        ",
+    )
+    .bind(key)
+    .bind(time::current_time_in_secs() as i64)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("release_attempt: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn clear_throttle(key: &str, pool: &PgPool) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query("DELETE FROM auth_throttles WHERE key = $1")
+        .bind(key)
+        .execute(pool)
+        .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("clear_throttle: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn delete_expired_throttles_as_admin(pool: &PgPool) -> Result<u64, ApiError> {
+    let current_time = time::current_time_in_secs() as i64;
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM auth_throttles
+        WHERE window_started_at <= $1 AND (locked_until IS NULL OR locked_until <= $2)
+        ",
+    )
+    .bind(current_time - ATTEMPT_WINDOW)
+    .bind(current_time)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            tracing::error!("delete_expired_throttles_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
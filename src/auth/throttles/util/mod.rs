@@ -0,0 +1,7 @@
+pub fn account_key(action: &str, user_id: &str) -> String {
+    return [action, ":user:", user_id].concat();
+}
+
+pub fn ip_key(action: &str, ip: &str) -> String {
+    return [action, ":ip:", ip].concat();
+}
@@ -0,0 +1,9 @@
+pub static TOTP_PERIOD: u64 = 30;
+pub static TOTP_DIGITS: u32 = 6;
+// time steps either side of the current one that are still accepted, for clock drift
+pub static TOTP_SKEW: i64 = 1;
+pub static TOTP_SECRET_BYTES: usize = 20;
+pub static RECOVERY_CODE_COUNT: usize = 10;
+pub static RECOVERY_CODE_LENGTH: usize = 10;
+// seconds a login challenge stays valid
+pub static TWO_FACTOR_CHALLENGE_EXP: u64 = 300;
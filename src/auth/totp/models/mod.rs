@@ -0,0 +1,3 @@
+pub mod recovery_code;
+pub mod recovery_codes;
+pub mod totp_enrollment;
@@ -0,0 +1,25 @@
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::app::util::time;
+
+#[derive(Debug, FromRow)]
+pub struct RecoveryCode {
+    pub id: String,
+    pub user_id: String,
+    pub code_hash: String,
+    pub used_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl RecoveryCode {
+    pub fn new(user_id: &str, code_hash: String) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            code_hash,
+            used_at: None,
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+// shown once, only hashes are kept
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
@@ -0,0 +1,389 @@
+use sqlx::{PgPool, Postgres};
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError, util::hasher, util::time},
+    auth::{
+        dtos::{confirm_totp_dto::ConfirmTotpDto, disable_totp_dto::DisableTotpDto},
+        errors::AuthApiError,
+        jwt::models::claims::Claims,
+        throttles::{
+            self,
+            config::{LOCKOUT_DURATION, TWO_FACTOR_LOCKOUT_THRESHOLD},
+            enums::throttle_action::ThrottleAction,
+        },
+    },
+    users::{self, models::user::User},
+};
+
+use super::{
+    config::RECOVERY_CODE_COUNT,
+    models::{
+        recovery_code::RecoveryCode, recovery_codes::RecoveryCodes, totp_enrollment::TotpEnrollment,
+    },
+    util,
+};
+
+// the secret is only enabled once a code generated from it is confirmed
+pub async fn begin_totp_enrollment(
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<TotpEnrollment, ApiError> {
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.totp_enabled_at.is_some() {
+        return Err(AuthApiError::TotpAlreadyEnabled.value());
+    }
+
+    let secret = util::generate_secret();
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_secret = $1
+        WHERE id = $2 AND totp_enabled_at IS NULL
+        ",
+    )
+    .bind(&secret)
+    .bind(&user.id)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(TotpEnrollment {
+                otpauth_uri: util::otpauth_uri(&secret, &user.username),
+                secret,
+            }),
+            false => Err(AuthApiError::TotpAlreadyEnabled.value()),
+        },
+        Err(e) => {
+            tracing::error!("begin_totp_enrollment: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn confirm_totp_enrollment(
+    dto: &ConfirmTotpDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<RecoveryCodes, ApiError> {
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.totp_enabled_at.is_some() {
+        return Err(AuthApiError::TotpAlreadyEnabled.value());
+    }
+
+    let Some(secret) = &user.totp_secret else {
+        return Err(AuthApiError::TotpNotEnrolled.value());
+    };
+
+    let Some(step) = util::verify_code(secret, &dto.code) else {
+        return Err(AuthApiError::InvalidTwoFactorCode.value());
+    };
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_enabled_at = $1, totp_last_used_step = $2
+        WHERE id = $3 AND totp_enabled_at IS NULL
+        ",
+    )
+    .bind(time::current_time_in_secs() as i64)
+    .bind(step)
+    .bind(&user.id)
+    .execute(&mut tx)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => {
+            if result.rows_affected() == 0 {
+                if let Err(e) = tx.rollback().await {
+                    tracing::error!("confirm_totp_enrollment failed to roll back: {:?}", e);
+                }
+                return Err(AuthApiError::TotpAlreadyEnabled.value());
+            }
+        }
+        Err(e) => {
+            tracing::error!("confirm_totp_enrollment: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+
+    let recovery_codes_result = replace_recovery_codes_as_tx(&user.id, &mut tx).await;
+    let Ok(recovery_codes) = recovery_codes_result else {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("confirm_totp_enrollment failed to roll back: {:?}", e);
+        }
+        return Err(recovery_codes_result.unwrap_err());
+    };
+
+    match tx.commit().await {
+        Ok(_) => Ok(RecoveryCodes { recovery_codes }),
+        Err(e) => {
+            tracing::error!("confirm_totp_enrollment failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn disable_totp(
+    dto: &DisableTotpDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let user_result = users::service::get_user_by_id_as_admin(&claims.id, pool).await;
+    let Ok(user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    if user.totp_enabled_at.is_none() {
+        return Err(AuthApiError::TotpNotEnrolled.value());
+    }
+
+    if let Err(e) = verify_second_factor(&user, &dto.totp_code, &dto.recovery_code, pool).await {
+        return Err(e);
+    }
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_used_step = NULL
+        WHERE id = $1
+        ",
+    )
+    .bind(&user.id)
+    .execute(&mut tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("disable_totp: {:?}", e);
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("disable_totp failed to roll back: {:?}", e);
+        }
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    let sqlx_result = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(&user.id)
+        .execute(&mut tx)
+        .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("disable_totp: {:?}", e);
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("disable_totp failed to roll back: {:?}", e);
+        }
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("disable_totp failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// passes for users without two factor, otherwise requires a totp or an unused recovery code
+pub async fn verify_second_factor(
+    user: &User,
+    totp_code: &Option<String>,
+    recovery_code: &Option<String>,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    if user.totp_enabled_at.is_none() {
+        return Ok(());
+    }
+
+    if totp_code.is_none() && recovery_code.is_none() {
+        return Err(AuthApiError::TwoFactorRequired.value());
+    }
+
+    // counted apart from logins, so passing the password step doesn't reset it
+    // and recovery codes, each costing a slow hash, are throttled too
+    let two_factor_key = throttles::util::account_key(ThrottleAction::TWO_FACTOR, &user.id);
+    match throttles::service::record_attempt(
+        &two_factor_key,
+        TWO_FACTOR_LOCKOUT_THRESHOLD,
+        LOCKOUT_DURATION,
+        pool,
+    )
+    .await
+    {
+        Ok(two_factor_throttle) => {
+            if two_factor_throttle.is_locked() {
+                return Err(AuthApiError::TooManyAttempts.value());
+            }
+        }
+        Err(e) => return Err(e),
+    }
+
+    let verify_result = match totp_code {
+        Some(totp_code) => match user
+            .totp_secret
+            .as_ref()
+            .and_then(|secret| util::verify_code(secret, totp_code))
+        {
+            Some(step) => consume_totp_step(&user.id, step, pool).await,
+            None => Err(AuthApiError::InvalidTwoFactorCode.value()),
+        },
+        None => match recovery_code {
+            Some(recovery_code) => consume_recovery_code(&user.id, recovery_code, pool).await,
+            None => Err(AuthApiError::TwoFactorRequired.value()),
+        },
+    };
+
+    if verify_result.is_ok() {
+        if let Err(e) = throttles::service::clear_throttle(&two_factor_key, pool).await {
+            tracing::error!("verify_second_factor: {:?}", e);
+        }
+    }
+
+    return verify_result;
+}
+
+async fn consume_totp_step(user_id: &str, step: i64, pool: &PgPool) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_last_used_step = $1
+        WHERE id = $2 AND (totp_last_used_step IS NULL OR totp_last_used_step < $1)
+        ",
+    )
+    .bind(step)
+    .bind(user_id)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(AuthApiError::InvalidTwoFactorCode.value()),
+        },
+        Err(e) => {
+            tracing::error!("consume_totp_step: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn consume_recovery_code(
+    user_id: &str,
+    recovery_code: &str,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query_as::<_, RecoveryCode>(
+        "
+        SELECT * FROM recovery_codes
+        WHERE user_id = $1 AND used_at IS NULL
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await;
+
+    let recovery_codes = match sqlx_result {
+        Ok(recovery_codes) => recovery_codes,
+        Err(e) => {
+            tracing::error!("consume_recovery_code: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let normalized = util::normalize_recovery_code(recovery_code);
+
+    for code in recovery_codes {
+        let Ok(matches) = hasher::verify(normalized.to_string(), code.code_hash).await else {
+            continue;
+        };
+
+        if !matches {
+            continue;
+        }
+
+        let sqlx_result = sqlx::query(
+            "
+            UPDATE recovery_codes SET used_at = $1
+            WHERE id = $2 AND used_at IS NULL
+            ",
+        )
+        .bind(time::current_time_in_secs() as i64)
+        .bind(&code.id)
+        .execute(pool)
+        .await;
+
+        return match sqlx_result {
+            Ok(result) => match result.rows_affected() > 0 {
+                true => Ok(()),
+                false => Err(AuthApiError::InvalidTwoFactorCode.value()),
+            },
+            Err(e) => {
+                tracing::error!("consume_recovery_code: {:?}", e);
+                Err(DefaultApiError::InternalServerError.value())
+            }
+        };
+    }
+
+    return Err(AuthApiError::InvalidTwoFactorCode.value());
+}
+
+async fn replace_recovery_codes_as_tx(
+    user_id: &str,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<Vec<String>, ApiError> {
+    let sqlx_result = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("replace_recovery_codes_as_tx: {:?}", e);
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    let mut codes = Vec::new();
+
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let code = util::generate_recovery_code();
+
+        let Ok(code_hash) = hasher::hash(util::normalize_recovery_code(&code)).await else {
+            return Err(DefaultApiError::InternalServerError.value());
+        };
+        let recovery_code = RecoveryCode::new(user_id, code_hash);
+
+        let sqlx_result = sqlx::query(
+            "
+            INSERT INTO recovery_codes (id, user_id, code_hash, used_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ",
+        )
+        .bind(&recovery_code.id)
+        .bind(&recovery_code.user_id)
+        .bind(&recovery_code.code_hash)
+        .bind(recovery_code.used_at)
+        .bind(recovery_code.created_at)
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = sqlx_result {
+            tracing::error!("replace_recovery_codes_as_tx: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+
+        codes.push(code);
+    }
+
+    return Ok(codes);
+}
@@ -0,0 +1,144 @@
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
+use sha1::Sha1;
+
+use crate::{
+    app::{self, util::time},
+    auth::totp::config::{
+        RECOVERY_CODE_LENGTH, TOTP_DIGITS, TOTP_PERIOD, TOTP_SECRET_BYTES, TOTP_SKEW,
+    },
+};
+
+static BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+// no 0/o, 1/i/l to keep handwritten codes readable
+static RECOVERY_CODE_ALPHABET: &[u8; 31] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub fn generate_secret() -> String {
+    let mut bytes = vec![0u8; TOTP_SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    return base32_encode(&bytes);
+}
+
+pub fn otpauth_uri(secret: &str, username: &str) -> String {
+    return format!(
+        "otpauth://totp/{issuer}:{username}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = app::config::APP_NAME,
+        username = username,
+        secret = secret,
+        digits = TOTP_DIGITS,
+        period = TOTP_PERIOD,
+    );
+}
+
+// returns the time step the code matched, if any
+pub fn verify_code(secret: &str, code: &str) -> Option<i64> {
+    let key = base32_decode(secret)?;
+
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current_step = (time::current_time_in_secs() / TOTP_PERIOD) as i64;
+
+    for step in (current_step - TOTP_SKEW)..=(current_step + TOTP_SKEW) {
+        let expected = format!(
+            "{:0width$}",
+            code_at_step(&key, step as u64),
+            width = TOTP_DIGITS as usize
+        );
+
+        if constant_time_eq(expected.as_bytes(), code.as_bytes()) {
+            return Some(step);
+        }
+    }
+
+    return None;
+}
+
+// rfc 4226 dynamic truncation over hmac-sha1
+fn code_at_step(key: &[u8], step: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("hmac accepts keys of any size");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = ((hash[offset] as u32 & 0x7f) << 24)
+        | ((hash[offset + 1] as u32) << 16)
+        | ((hash[offset + 2] as u32) << 8)
+        | (hash[offset + 3] as u32);
+
+    return binary % 10_u32.pow(TOTP_DIGITS);
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    return a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0;
+}
+
+pub fn generate_recovery_code() -> String {
+    let mut rng = rand::thread_rng();
+    let code: String = (0..RECOVERY_CODE_LENGTH)
+        .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+        .collect();
+
+    let (head, tail) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+    return format!("{}-{}", head, tail);
+}
+
+// recovery codes are hashed without the dash, whatever case they are typed in
+pub fn normalize_recovery_code(code: &str) -> String {
+    return code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    return encoded;
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in encoded.trim_end_matches('=').chars() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())?;
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+
+    return Some(bytes);
+}
@@ -2,22 +2,25 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    headers::{authorization::Bearer, Authorization},
-    Json, TypedHeader,
+    Json,
 };
 use reqwest::StatusCode;
 use validator::Validate;
 
-use crate::{app::models::api_error::ApiError, auth::jwt::models::claims::Claims, AppState};
+use crate::{
+    app::models::api_error::ApiError,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
 
 use super::{dtos::get_blocks_filter_dto::GetBlocksFilterDto, models::block::Block, service};
 
 pub async fn block(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::block(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
@@ -25,10 +28,10 @@ pub async fn block(
 
 pub async fn get_blocks(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetBlocksFilterDto>,
 ) -> Result<Json<Vec<Block>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -49,9 +52,9 @@ pub async fn get_blocks(
 pub async fn unblock(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::unblock(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
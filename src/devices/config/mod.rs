@@ -0,0 +1,2 @@
+// seconds a device stays logged in after login, however often it refreshes
+pub static DEVICE_SESSION_LIFETIME: i64 = 7776000;
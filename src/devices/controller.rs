@@ -2,13 +2,12 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, State},
-    headers::{authorization::Bearer, Authorization},
-    Json, TypedHeader,
+    Json,
 };
 
 use crate::{
     app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
-    auth::jwt::models::claims::Claims,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
     AppState,
 };
 
@@ -17,10 +16,10 @@ use super::{dtos::edit_device_dto::EditDeviceDto, models::device::Device, servic
 pub async fn edit_device_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<EditDeviceDto>,
 ) -> Result<Json<Device>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => match service::edit_device_by_id(&id, &dto, &claims, &state.pool).await {
             Ok(device) => Ok(Json(device)),
             Err(e) => Err(e),
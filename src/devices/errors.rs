@@ -5,6 +5,9 @@ use crate::app::models::api_error::ApiError;
 #[derive(Debug)]
 pub enum DevicesApiError {
     DeviceNotFound,
+    RefreshFailed,
+    RefreshTokenReused,
+    SessionExpired,
 }
 
 impl DevicesApiError {
@@ -14,6 +17,18 @@ impl DevicesApiError {
                 code: StatusCode::NOT_FOUND,
                 message: "Device not found.".to_string(),
             },
+            Self::RefreshFailed => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "Failed to refresh.".to_string(),
+            },
+            Self::RefreshTokenReused => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "Refresh token was already used, log in again.".to_string(),
+            },
+            Self::SessionExpired => ApiError {
+                code: StatusCode::UNAUTHORIZED,
+                message: "Session has expired, log in again.".to_string(),
+            },
         }
     }
 }
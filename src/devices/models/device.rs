@@ -2,34 +2,39 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-use crate::{app::util::time, users::models::user::User};
+use crate::{app::util::time, devices::config::DEVICE_SESSION_LIFETIME, users::models::user::User};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Device {
     pub id: String,
     pub user_id: String,
+    // sha256 of the current refresh token
     #[serde(skip_serializing)]
     pub refresh_token: String,
     #[serde(skip_serializing)]
     pub messaging_token: Option<String>,
     pub roles: Option<Vec<String>>,
+    pub permissions: Option<Vec<String>>,
+    pub expires_at: i64,
     pub updated_at: i64,
     pub created_at: i64,
 }
 
 impl Device {
-    pub fn new(user: &User) -> Self {
+    pub fn new(user: &User, refresh_token_hash: String) -> Self {
         let current_time = time::current_time_in_secs() as i64;
 
         return Self {
             id: Uuid::new_v4().to_string(),
             user_id: user.id.to_string(),
-            refresh_token: Uuid::new_v4().to_string(),
+            refresh_token: refresh_token_hash,
             messaging_token: None,
             roles: match &user.roles {
                 Some(roles) => Some(roles.clone()),
                 None => None,
             },
+            permissions: user.permissions.clone(),
+            expires_at: current_time + DEVICE_SESSION_LIFETIME,
             updated_at: current_time,
             created_at: current_time,
         };
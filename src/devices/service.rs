@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use axum::http::StatusCode;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
 
 use crate::{
     app::{
@@ -9,6 +10,7 @@ use crate::{
         models::api_error::ApiError,
         util::{
             fcm::{self, client::fcm_client::FcmMessage},
+            hasher,
             sqlx::{get_code_from_db_err, SqlStateCodes},
             time,
         },
@@ -27,49 +29,98 @@ use super::{
     models::device::Device,
 };
 
-pub async fn create_device_as_admin(user: &User, pool: &PgPool) -> Result<Device, ApiError> {
-    let device = Device::new(user);
+// returns the device along with its refresh token, which is only ever stored hashed
+pub async fn create_device_as_admin(
+    user: &User,
+    pool: &PgPool,
+) -> Result<(Device, String), ApiError> {
+    let refresh_token = Uuid::new_v4().to_string();
+    let device = Device::new(user, hasher::hash_token(&refresh_token));
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
 
     let sqlx_result = sqlx::query(
         "
         INSERT INTO devices (
-            id, user_id, refresh_token, roles, updated_at, created_at
+            id, user_id, refresh_token, roles, permissions, expires_at, updated_at, created_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
         ",
     )
     .bind(&device.id)
     .bind(&device.user_id)
     .bind(&device.refresh_token)
     .bind(&device.roles)
+    .bind(&device.permissions)
+    .bind(device.expires_at)
     .bind(device.updated_at.to_owned() as i64)
     .bind(device.created_at.to_owned() as i64)
-    .execute(pool)
+    .execute(&mut tx)
     .await;
 
-    match sqlx_result {
-        Ok(_) => Ok(device),
-        Err(e) => {
-            let Some(db_err) = e.as_database_error() else {
-                tracing::error!(%e);
-                return Err(DefaultApiError::InternalServerError.value());
-            };
+    if let Err(e) = sqlx_result {
+        let Some(db_err) = e.as_database_error() else {
+            tracing::error!(%e);
+            return Err(DefaultApiError::InternalServerError.value());
+        };
+
+        let Some(code) = get_code_from_db_err(db_err) else {
+            tracing::error!(%e);
+            return Err(DefaultApiError::InternalServerError.value());
+        };
 
-            let Some(code) = get_code_from_db_err(db_err) else {
+        return match code.as_str() {
+            SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Device already exists.".to_string(),
+            }),
+            _ => {
                 tracing::error!(%e);
-                return Err(DefaultApiError::InternalServerError.value());
-            };
-
-            match code.as_str() {
-                SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
-                    code: StatusCode::CONFLICT,
-                    message: "Device already exists.".to_string(),
-                }),
-                _ => {
-                    tracing::error!(%e);
-                    Err(DefaultApiError::InternalServerError.value())
-                }
+                Err(DefaultApiError::InternalServerError.value())
             }
+        };
+    }
+
+    if let Err(e) = create_refresh_token_as_tx(&device.refresh_token, &device.id, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("create_device_as_admin failed to roll back: {:?}", e);
+        }
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok((device, refresh_token)),
+        Err(e) => {
+            tracing::error!("create_device_as_admin failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn create_refresh_token_as_tx(
+    token_hash: &str,
+    device_id: &str,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO refresh_tokens (token_hash, device_id, rotated_at, created_at)
+        VALUES ($1, $2, NULL, $3)
+        ",
+    )
+    .bind(token_hash)
+    .bind(device_id)
+    .bind(time::current_time_in_secs() as i64)
+    .execute(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("create_refresh_token_as_tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
         }
     }
 }
@@ -173,34 +224,165 @@ pub async fn get_devices_as_admin(
     }
 }
 
+// rotates the refresh token. replaying a rotated token means it leaked,
+// so the device is logged out along with every token it was issued.
 pub async fn refresh_device_as_admin(
     dto: &RefreshDeviceDto,
     pool: &PgPool,
-) -> Result<Device, ApiError> {
+) -> Result<(Device, String), ApiError> {
+    let token_hash = hasher::hash_token(&dto.refresh_token);
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
     let sqlx_result = sqlx::query_as::<_, Device>(
         "
-        UPDATE devices SET updated_at = $1
-        WHERE id = $2 AND user_id = $3 AND refresh_token = $4
-        RETURNING *;
+        SELECT * FROM devices
+        WHERE id = $1 AND user_id = $2
+        FOR UPDATE
         ",
     )
-    .bind(time::current_time_in_secs() as i64)
     .bind(&dto.device_id)
     .bind(&dto.user_id)
-    .bind(&dto.refresh_token)
-    .fetch_optional(pool)
+    .fetch_optional(&mut tx)
     .await;
 
-    match sqlx_result {
-        Ok(device) => match device {
-            Some(dev) => Ok(dev),
-            None => Err(ApiError {
-                code: StatusCode::NOT_FOUND,
-                message: "Failed to refresh.".to_string(),
-            }),
-        },
+    let device = match sqlx_result {
+        Ok(Some(device)) => device,
+        Ok(None) => return Err(DevicesApiError::RefreshFailed.value()),
         Err(e) => {
-            tracing::error!(%e);
+            tracing::error!("refresh_device_as_admin: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    if device.refresh_token != token_hash {
+        let sqlx_result = sqlx::query_as::<_, (bool,)>(
+            "
+            SELECT EXISTS (
+                SELECT 1 FROM refresh_tokens
+                WHERE token_hash = $1 AND device_id = $2
+            )
+            ",
+        )
+        .bind(&token_hash)
+        .bind(&device.id)
+        .fetch_one(&mut tx)
+        .await;
+
+        return match sqlx_result {
+            Ok((true,)) => {
+                tracing::warn!(
+                    "refresh_device_as_admin revoked device {} after refresh token reuse",
+                    device.id
+                );
+                match delete_device_by_id_as_tx(&device.id, tx).await {
+                    Ok(_) => Err(DevicesApiError::RefreshTokenReused.value()),
+                    Err(e) => Err(e),
+                }
+            }
+            Ok((false,)) => Err(DevicesApiError::RefreshFailed.value()),
+            Err(e) => {
+                tracing::error!("refresh_device_as_admin: {:?}", e);
+                Err(DefaultApiError::InternalServerError.value())
+            }
+        };
+    }
+
+    let current_time = time::current_time_in_secs() as i64;
+
+    if device.expires_at <= current_time {
+        return match delete_device_by_id_as_tx(&device.id, tx).await {
+            Ok(_) => Err(DevicesApiError::SessionExpired.value()),
+            Err(e) => Err(e),
+        };
+    }
+
+    let refresh_token = Uuid::new_v4().to_string();
+    let refresh_token_hash = hasher::hash_token(&refresh_token);
+
+    let sqlx_result = sqlx::query_as::<_, Device>(
+        "
+        UPDATE devices SET refresh_token = $1, updated_at = $2
+        WHERE id = $3
+        RETURNING *
+        ",
+    )
+    .bind(&refresh_token_hash)
+    .bind(current_time)
+    .bind(&device.id)
+    .fetch_one(&mut tx)
+    .await;
+
+    let device = match sqlx_result {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::error!("refresh_device_as_admin: {:?}", e);
+            if let Err(e) = tx.rollback().await {
+                tracing::error!("refresh_device_as_admin failed to roll back: {:?}", e);
+            }
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE refresh_tokens SET rotated_at = $1
+        WHERE token_hash = $2
+        ",
+    )
+    .bind(current_time)
+    .bind(&token_hash)
+    .execute(&mut tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("refresh_device_as_admin: {:?}", e);
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("refresh_device_as_admin failed to roll back: {:?}", e);
+        }
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    if let Err(e) = create_refresh_token_as_tx(&refresh_token_hash, &device.id, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("refresh_device_as_admin failed to roll back: {:?}", e);
+        }
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok((device, refresh_token)),
+        Err(e) => {
+            tracing::error!("refresh_device_as_admin failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// commits the transaction, the device's refresh tokens are deleted along with it
+async fn delete_device_by_id_as_tx(
+    id: &str,
+    mut tx: sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query("DELETE FROM devices WHERE id = $1")
+        .bind(id)
+        .execute(&mut tx)
+        .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("delete_device_by_id_as_tx: {:?}", e);
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("delete_device_by_id_as_tx failed to roll back: {:?}", e);
+        }
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("delete_device_by_id_as_tx failed to commit tx: {:?}", e);
             Err(DefaultApiError::InternalServerError.value())
         }
     }
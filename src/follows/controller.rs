@@ -2,22 +2,25 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    headers::{authorization::Bearer, Authorization},
-    Json, TypedHeader,
+    Json,
 };
 use reqwest::StatusCode;
 use validator::Validate;
 
-use crate::{app::models::api_error::ApiError, auth::jwt::models::claims::Claims, AppState};
+use crate::{
+    app::models::api_error::ApiError,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
 
 use super::{dtos::get_follows_filter_dto::GetFollowsFilterDto, models::follow::Follow, service};
 
 pub async fn follow(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::follow(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
@@ -25,10 +28,10 @@ pub async fn follow(
 
 pub async fn get_follows(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetFollowsFilterDto>,
 ) -> Result<Json<Vec<Follow>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -49,9 +52,9 @@ pub async fn get_follows(
 pub async fn unfollow(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::unfollow(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
@@ -0,0 +1,7 @@
+pub static MAX_ATTEMPTS: i16 = 3;
+pub static LEASE_DURATION: i64 = 900;
+pub static BACKOFF_BASE: i64 = 30;
+pub static CLAIM_BATCH_SIZE: i64 = 10;
+pub static POLL_INTERVAL: u64 = 5;
+pub static EVENTS_CHANNEL: &str = "generate_media_request_events";
+pub static EVENTS_BUFFER_SIZE: usize = 256;
@@ -1,26 +1,32 @@
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc};
 
 use axum::{
-    extract::{Query, State},
-    headers::{authorization::Bearer, Authorization},
+    extract::{Path, Query, State},
     http::StatusCode,
-    Json, TypedHeader,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
 };
+use futures::Stream;
 use validator::Validate;
 
-use crate::{app::models::api_error::ApiError, auth::jwt::models::claims::Claims, AppState};
+use crate::{
+    api_keys::enums::api_key_scope::ApiKeyScope,
+    app::models::api_error::ApiError,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
 
 use super::{
-    dtos::get_generate_media_requests_filter_dto::GetGenerateMediaRequestsFilterDto,
+    dtos::get_generate_media_requests_filter_dto::GetGenerateMediaRequestsFilterDto, events,
     models::generate_media_request::GenerateMediaRequest, service,
 };
 
 pub async fn get_generate_media_requests(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetGenerateMediaRequestsFilterDto>,
 ) -> Result<Json<Vec<GenerateMediaRequest>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(_) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -37,3 +43,28 @@ pub async fn get_generate_media_requests(
         Err(e) => Err(e),
     }
 }
+
+pub async fn cancel_generate_media_request_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::cancel_generate_media_request_by_id(&id, &claims, &state).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_generate_media_request_events(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    match Claims::from_header_or_api_key(authorization, ApiKeyScope::MEDIA_READ, &state).await {
+        Ok(claims) => match events::get_generate_media_request_events(&id, &claims, &state).await {
+            Ok(stream) => Ok(Sse::new(stream).keep_alive(KeepAlive::default())),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,44 @@
+use super::generate_media_request_status::GenerateMediaRequestStatus;
+
+#[derive(Debug)]
+pub enum GenerateMediaRequestEventType {
+    Queued,
+    Processing,
+    MediaUploaded,
+    Completed,
+    Canceled,
+    Error,
+}
+
+impl GenerateMediaRequestEventType {
+    pub fn value(&self) -> &str {
+        match *self {
+            Self::Queued => "queued",
+            Self::Processing => "processing",
+            Self::MediaUploaded => "media_uploaded",
+            Self::Completed => "completed",
+            Self::Canceled => "canceled",
+            Self::Error => "error",
+        }
+    }
+
+    pub fn from_status(status: &str) -> Option<Self> {
+        if status == GenerateMediaRequestStatus::Pending.value() {
+            return Some(Self::Queued);
+        }
+        if status == GenerateMediaRequestStatus::Processing.value() {
+            return Some(Self::Processing);
+        }
+        if status == GenerateMediaRequestStatus::Completed.value() {
+            return Some(Self::Completed);
+        }
+        if status == GenerateMediaRequestStatus::Canceled.value() {
+            return Some(Self::Canceled);
+        }
+        if status == GenerateMediaRequestStatus::Error.value() {
+            return Some(Self::Error);
+        }
+
+        return None;
+    }
+}
@@ -1 +1,2 @@
+pub mod generate_media_request_event_type;
 pub mod generate_media_request_status;
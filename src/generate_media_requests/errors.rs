@@ -5,6 +5,8 @@ use crate::app::models::api_error::ApiError;
 #[derive(Debug)]
 pub enum GenerateMediaRequestsApiError {
     RequestNotFound,
+    RequestNotProcessing,
+    CannotCancel,
 }
 
 impl GenerateMediaRequestsApiError {
@@ -14,6 +16,14 @@ impl GenerateMediaRequestsApiError {
                 code: StatusCode::NOT_FOUND,
                 message: "Request not found.".to_string(),
             },
+            Self::RequestNotProcessing => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Request is not processing.".to_string(),
+            },
+            Self::CannotCancel => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Request can no longer be canceled.".to_string(),
+            },
         }
     }
 }
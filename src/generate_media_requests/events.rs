@@ -0,0 +1,155 @@
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::response::sse::Event;
+use futures::{stream, Stream, StreamExt};
+use sqlx::postgres::PgListener;
+use tokio::{sync::broadcast::error::RecvError, task, time::sleep};
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError},
+    auth::jwt::models::claims::Claims,
+    media, AppState,
+};
+
+use super::{
+    config::EVENTS_CHANNEL,
+    enums::generate_media_request_event_type::GenerateMediaRequestEventType,
+    models::generate_media_request_event::GenerateMediaRequestEvent, service,
+};
+
+// events go through postgres so that clients receive them regardless of
+// which instance processed the request
+pub async fn publish(
+    request_id: &str,
+    event_type: &GenerateMediaRequestEventType,
+    media_ids: Option<Vec<String>>,
+    state: &Arc<AppState>,
+) {
+    let event = GenerateMediaRequestEvent::new(request_id, event_type, media_ids);
+
+    let Ok(payload) = serde_json::to_string(&event) else {
+        tracing::error!("publish failed to serialize event: {:?}", event);
+        return;
+    };
+
+    let sqlx_result = sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(EVENTS_CHANNEL)
+        .bind(payload)
+        .execute(&state.pool)
+        .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("publish: {:?}", e);
+    }
+}
+
+pub fn spawn_listener(state: Arc<AppState>) {
+    task::spawn(async move {
+        loop {
+            if let Err(e) = listen(&state).await {
+                tracing::error!("spawn_listener: {:?}", e);
+            }
+
+            sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
+
+async fn listen(state: &Arc<AppState>) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(&state.pool).await?;
+    listener.listen(EVENTS_CHANNEL).await?;
+
+    loop {
+        let notification = listener.recv().await?;
+
+        match serde_json::from_str::<GenerateMediaRequestEvent>(notification.payload()) {
+            // sending only fails when nobody is subscribed
+            Ok(event) => {
+                let _ = state.generate_media_events.send(event);
+            }
+            Err(e) => {
+                tracing::warn!("listen failed to parse event: {:?}", e);
+            }
+        }
+    }
+}
+
+pub async fn get_generate_media_request_events(
+    id: &str,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<impl Stream<Item = Result<Event, Infallible>>, ApiError> {
+    // subscribe before reading the request so no transition is missed in between
+    let receiver = state.generate_media_events.subscribe();
+
+    let get_generate_media_request_result =
+        service::get_generate_media_request_by_id_as_admin(id, &state.pool).await;
+    let Ok(generate_media_request) = get_generate_media_request_result else {
+        return Err(get_generate_media_request_result.unwrap_err());
+    };
+
+    if generate_media_request.user_id != claims.id {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let mut events = Vec::new();
+
+    if let Some(event_type) =
+        GenerateMediaRequestEventType::from_status(&generate_media_request.status)
+    {
+        // a client connecting after completion still needs the resulting media
+        let media_ids = match event_type {
+            GenerateMediaRequestEventType::Completed => {
+                let media_ids_result =
+                    media::service::get_media_ids_by_post_id_as_admin(id, &state.pool).await;
+                let Ok(media_ids) = media_ids_result else {
+                    return Err(media_ids_result.unwrap_err());
+                };
+                Some(media_ids)
+            }
+            _ => None,
+        };
+
+        events.push(GenerateMediaRequestEvent::new(id, &event_type, media_ids));
+    }
+
+    let is_final = events.iter().any(|event| event.is_final());
+
+    let updates = stream::unfold(
+        (receiver, id.to_string(), is_final),
+        |(mut receiver, id, is_final)| async move {
+            if is_final {
+                return None;
+            }
+
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.request_id == id => {
+                        let is_final = event.is_final();
+                        return Some((event, (receiver, id, is_final)));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("get_generate_media_request_events lagged by {}", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    let stream = stream::iter(events).chain(updates).map(|event| {
+        let sse_event = Event::default().event(&event.event);
+
+        return Ok(match sse_event.json_data(&event) {
+            Ok(sse_event) => sse_event,
+            Err(e) => {
+                tracing::error!("get_generate_media_request_events: {:?}", e);
+                Event::default().event(&event.event)
+            }
+        });
+    });
+
+    Ok(stream)
+}
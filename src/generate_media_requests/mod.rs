@@ -1,6 +1,9 @@
+pub mod config;
 pub mod controller;
 pub mod dtos;
 pub mod enums;
 pub mod errors;
+pub mod events;
 pub mod models;
 pub mod service;
+pub mod worker;
@@ -6,7 +6,10 @@ use validator::Validate;
 use crate::{
     app::{self, util::time},
     auth::jwt::models::claims::Claims,
-    generate_media_requests::enums::generate_media_request_status::GenerateMediaRequestStatus,
+    generate_media_requests::{
+        config::{BACKOFF_BASE, MAX_ATTEMPTS},
+        enums::generate_media_request_status::GenerateMediaRequestStatus,
+    },
     media::dtos::generate_media_dto::GenerateMediaDto,
 };
 
@@ -19,20 +22,70 @@ pub struct GenerateMediaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_v: Option<i16>,
     pub created_at: i64,
+    #[serde(skip_serializing)]
+    pub attempts: i16,
+    #[serde(skip_serializing)]
+    pub run_at: i64,
+    #[serde(skip_serializing)]
+    pub locked_until: Option<i64>,
+    #[serde(skip_serializing)]
+    pub provider_request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ink_cost: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_id: Option<String>,
 }
 
 impl GenerateMediaRequest {
-    pub fn new(claims: &Claims, generate_media_dto: &GenerateMediaDto) -> Self {
+    pub fn new(claims: &Claims, generate_media_dto: &GenerateMediaDto, ink_cost: i64) -> Self {
+        let current_time = time::current_time_in_secs() as i64;
+
         return Self {
             id: Uuid::new_v4().to_string(),
             user_id: claims.id.to_string(),
-            status: GenerateMediaRequestStatus::Processing.value().to_string(),
-            generate_media_dto: sqlx::types::Json(generate_media_dto.sanitized()),
+            status: GenerateMediaRequestStatus::Pending.value().to_string(),
+            generate_media_dto: sqlx::types::Json(generate_media_dto.clone()),
             api_v: Some(app::config::API_V),
-            created_at: time::current_time_in_secs() as i64,
+            created_at: current_time,
+            attempts: 0,
+            run_at: current_time,
+            locked_until: None,
+            provider_request_id: None,
+            ink_cost: Some(ink_cost),
+            api_key_id: claims.api_key_id.clone(),
         };
     }
 
+    // ink quoted when the request was made, prorated by the media actually generated.
+    // requests made before quotes were stored have none.
+    pub fn quoted_ink_cost(&self, number_generated: Option<u8>) -> Option<i64> {
+        let number = self.generate_media_dto.number;
+
+        return self.ink_cost.map(|ink_cost| match number_generated {
+            Some(number_generated) => {
+                let number_generated = number_generated.min(number);
+                ((ink_cost * number_generated as i64) as f64 / number as f64).round() as i64
+            }
+            None => ink_cost,
+        });
+    }
+
+    pub fn is_processing(&self) -> bool {
+        return self.status == GenerateMediaRequestStatus::Processing.value();
+    }
+
+    pub fn has_attempts_left(&self) -> bool {
+        return self.attempts < MAX_ATTEMPTS;
+    }
+
+    // exponential backoff based on attempts made so far
+    pub fn next_run_at(&self) -> i64 {
+        let exponent = (self.attempts.max(1) - 1) as u32;
+        let delay = BACKOFF_BASE * 2_i64.pow(exponent);
+
+        return time::current_time_in_secs() as i64 + delay;
+    }
+
     pub fn sortable_fields() -> [&'static str; 1] {
         return ["created_at"];
     }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::util::time,
+    generate_media_requests::enums::generate_media_request_event_type::GenerateMediaRequestEventType,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateMediaRequestEvent {
+    pub request_id: String,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_ids: Option<Vec<String>>,
+    pub created_at: i64,
+}
+
+impl GenerateMediaRequestEvent {
+    pub fn new(
+        request_id: &str,
+        event_type: &GenerateMediaRequestEventType,
+        media_ids: Option<Vec<String>>,
+    ) -> Self {
+        return Self {
+            request_id: request_id.to_string(),
+            event: event_type.value().to_string(),
+            media_ids,
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+
+    pub fn is_final(&self) -> bool {
+        return self.event == GenerateMediaRequestEventType::Completed.value()
+            || self.event == GenerateMediaRequestEventType::Canceled.value()
+            || self.event == GenerateMediaRequestEventType::Error.value();
+    }
+}
@@ -1 +1,2 @@
 pub mod generate_media_request;
+pub mod generate_media_request_event;
@@ -1,35 +1,56 @@
+use std::sync::Arc;
+
 use axum::http::StatusCode;
 use sqlx::{PgPool, Postgres};
 
 use crate::{
     app::{
+        self,
         errors::DefaultApiError,
         models::api_error::ApiError,
-        util::sqlx::{get_code_from_db_err, SqlStateCodes},
+        util::{
+            sqlx::{get_code_from_db_err, SqlStateCodes},
+            time,
+        },
     },
     auth::jwt::models::claims::Claims,
-    media::dtos::generate_media_dto::GenerateMediaDto,
+    media::{dtos::generate_media_dto::GenerateMediaDto, errors::MediaApiError},
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+    AppState,
 };
 
 use super::{
+    config::{LEASE_DURATION, MAX_ATTEMPTS},
     dtos::get_generate_media_requests_filter_dto::GetGenerateMediaRequestsFilterDto,
-    enums::generate_media_request_status::GenerateMediaRequestStatus,
-    errors::GenerateMediaRequestsApiError, models::generate_media_request::GenerateMediaRequest,
+    enums::{
+        generate_media_request_event_type::GenerateMediaRequestEventType,
+        generate_media_request_status::GenerateMediaRequestStatus,
+    },
+    errors::GenerateMediaRequestsApiError,
+    events,
+    models::generate_media_request::GenerateMediaRequest,
 };
 
 pub async fn create_request(
     dto: &GenerateMediaDto,
+    ink_cost: i64,
     claims: &Claims,
     tx: &mut sqlx::Transaction<'_, Postgres>,
 ) -> Result<GenerateMediaRequest, ApiError> {
-    let generate_media_request = GenerateMediaRequest::new(claims, dto);
+    let generate_media_request = GenerateMediaRequest::new(claims, dto, ink_cost);
 
     let sqlx_result = sqlx::query(
         "
         INSERT INTO generate_media_requests (
-            id, user_id, status, generate_media_dto, api_v, created_at
+            id, user_id, status, generate_media_dto, api_v, created_at,
+            attempts, run_at, locked_until, ink_cost, api_key_id
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         ",
     )
     .bind(&generate_media_request.id)
@@ -38,6 +59,11 @@ pub async fn create_request(
     .bind(&generate_media_request.generate_media_dto)
     .bind(&generate_media_request.api_v)
     .bind(&generate_media_request.created_at)
+    .bind(generate_media_request.attempts)
+    .bind(generate_media_request.run_at)
+    .bind(generate_media_request.locked_until)
+    .bind(generate_media_request.ink_cost)
+    .bind(&generate_media_request.api_key_id)
     .execute(&mut *tx)
     .await;
 
@@ -197,22 +223,20 @@ pub async fn edit_generate_media_request_by_id_as_tx_as_admin(
 ) -> Result<(), ApiError> {
     let sqlx_result = sqlx::query(
         "
-        UPDATE generate_media_requests SET status = $1
-        WHERE id = $2
+        UPDATE generate_media_requests SET status = $1, locked_until = NULL
+        WHERE id = $2 AND status = $3
         ",
     )
     .bind(status.value())
     .bind(id)
+    .bind(GenerateMediaRequestStatus::Processing.value())
     .execute(&mut *tx)
     .await;
 
     match sqlx_result {
         Ok(result) => match result.rows_affected() > 0 {
             true => Ok(()),
-            false => Err(ApiError {
-                code: StatusCode::NOT_FOUND,
-                message: "Failed to update.".to_string(),
-            }),
+            false => Err(GenerateMediaRequestsApiError::RequestNotProcessing.value()),
         },
         Err(e) => {
             tracing::error!(%e);
@@ -220,3 +244,258 @@ pub async fn edit_generate_media_request_by_id_as_tx_as_admin(
         }
     }
 }
+
+pub async fn edit_generate_media_request_provider_request_id_by_id_as_admin(
+    id: &str,
+    provider_request_id: &str,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE generate_media_requests SET provider_request_id = $1
+        WHERE id = $2 AND status = $3
+        ",
+    )
+    .bind(provider_request_id)
+    .bind(id)
+    .bind(GenerateMediaRequestStatus::Processing.value())
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(GenerateMediaRequestsApiError::RequestNotProcessing.value()),
+        },
+        Err(e) => {
+            tracing::error!(
+                "edit_generate_media_request_provider_request_id_by_id_as_admin: {:?}",
+                e
+            );
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn cancel_generate_media_request_by_id(
+    id: &str,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    let get_generate_media_request_result =
+        get_generate_media_request_by_id_as_admin(id, &state.pool).await;
+    let Ok(generate_media_request) = get_generate_media_request_result else {
+        return Err(get_generate_media_request_result.unwrap_err());
+    };
+
+    if generate_media_request.user_id != claims.id {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let dto = &generate_media_request.generate_media_dto;
+    let Some(provider) = state.media_generators.get(&dto.generator) else {
+        tracing::error!(
+            "cancel_generate_media_request_by_id failed: generator {} is not registered",
+            dto.generator
+        );
+        return Err(MediaApiError::GeneratorNotSupported.value());
+    };
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        tracing::warn!("cancel_generate_media_request_by_id failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = cancel_generate_media_request_by_id_as_tx(id, &claims.id, &mut tx).await {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(e) = rollback_result.err() {
+            tracing::error!("cancel_generate_media_request_by_id failed to roll back cancel_generate_media_request_by_id_as_tx: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    // the request never finished, so the ink held for it is released without charge
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: None,
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: Some(
+            generate_media_request
+                .quoted_ink_cost(None)
+                .unwrap_or_else(|| state.ink_pricing.calculate_ink_cost(provider, dto, None)),
+        ),
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &edit_user_ink_dto,
+        InkLedgerReason::GENERATION_CANCEL,
+        Some(id),
+        &mut tx,
+    )
+    .await
+    {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(e) = rollback_result.err() {
+            tracing::error!(
+                "cancel_generate_media_request_by_id failed to roll back edit_user_ink_by_id: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    if let Err(e) = tx.commit().await {
+        tracing::error!(
+            "cancel_generate_media_request_by_id failed to commit tx: {:?}",
+            e
+        );
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    events::publish(id, &GenerateMediaRequestEventType::Canceled, None, state).await;
+
+    provider.cancel_generate_media_task(generate_media_request, state.clone());
+
+    Ok(())
+}
+
+async fn cancel_generate_media_request_by_id_as_tx(
+    id: &str,
+    user_id: &str,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE generate_media_requests SET status = $1, locked_until = NULL
+        WHERE id = $2 AND user_id = $3 AND status IN ($4, $5)
+        ",
+    )
+    .bind(GenerateMediaRequestStatus::Canceled.value())
+    .bind(id)
+    .bind(user_id)
+    .bind(GenerateMediaRequestStatus::Pending.value())
+    .bind(GenerateMediaRequestStatus::Processing.value())
+    .execute(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(GenerateMediaRequestsApiError::CannotCancel.value()),
+        },
+        Err(e) => {
+            tracing::error!("cancel_generate_media_request_by_id_as_tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn claim_generate_media_requests_as_admin(
+    generators: &[&str],
+    limit: i64,
+    pool: &PgPool,
+) -> Result<Vec<GenerateMediaRequest>, ApiError> {
+    let current_time = time::current_time_in_secs() as i64;
+
+    let sqlx_result = sqlx::query_as::<_, GenerateMediaRequest>(
+        "
+        UPDATE generate_media_requests
+        SET status = $1, attempts = attempts + 1, locked_until = $2
+        WHERE id IN (
+            SELECT id FROM generate_media_requests
+            WHERE api_v = $3
+            AND attempts < $4
+            AND generate_media_dto->>'generator' = ANY($5)
+            AND (
+                (status = $6 AND run_at <= $7)
+                OR (status = $1 AND locked_until < $7)
+            )
+            ORDER BY created_at ASC
+            LIMIT $8
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *
+        ",
+    )
+    .bind(GenerateMediaRequestStatus::Processing.value())
+    .bind(current_time + LEASE_DURATION)
+    .bind(app::config::API_V)
+    .bind(MAX_ATTEMPTS)
+    .bind(generators)
+    .bind(GenerateMediaRequestStatus::Pending.value())
+    .bind(current_time)
+    .bind(limit)
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(generate_media_requests) => Ok(generate_media_requests),
+        Err(e) => {
+            tracing::error!("claim_generate_media_requests_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn requeue_generate_media_request_by_id_as_admin(
+    id: &str,
+    run_at: i64,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE generate_media_requests SET status = $1, run_at = $2, locked_until = NULL
+        WHERE id = $3 AND status = $4
+        ",
+    )
+    .bind(GenerateMediaRequestStatus::Pending.value())
+    .bind(run_at)
+    .bind(id)
+    .bind(GenerateMediaRequestStatus::Processing.value())
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(GenerateMediaRequestsApiError::RequestNotProcessing.value()),
+        },
+        Err(e) => {
+            tracing::error!("requeue_generate_media_request_by_id_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_exhausted_generate_media_requests_as_admin(
+    pool: &PgPool,
+) -> Result<Vec<GenerateMediaRequest>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, GenerateMediaRequest>(
+        "
+        SELECT * FROM generate_media_requests
+        WHERE api_v = $1 AND status = $2 AND attempts >= $3 AND locked_until < $4
+        ORDER BY created_at ASC
+        ",
+    )
+    .bind(app::config::API_V)
+    .bind(GenerateMediaRequestStatus::Processing.value())
+    .bind(MAX_ATTEMPTS)
+    .bind(time::current_time_in_secs() as i64)
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(generate_media_requests) => Ok(generate_media_requests),
+        Err(e) => {
+            tracing::error!("get_exhausted_generate_media_requests_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
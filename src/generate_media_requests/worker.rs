@@ -0,0 +1,106 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{task, time::interval};
+
+use crate::AppState;
+
+use super::{
+    config::{CLAIM_BATCH_SIZE, POLL_INTERVAL},
+    enums::generate_media_request_event_type::GenerateMediaRequestEventType,
+    events,
+    models::generate_media_request::GenerateMediaRequest,
+    service,
+};
+
+pub fn spawn(state: Arc<AppState>) {
+    task::spawn(async move {
+        let mut interval = interval(Duration::from_secs(POLL_INTERVAL));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = state.generate_media_queue.notified() => {}
+            }
+
+            process_requests(&state).await;
+        }
+    });
+}
+
+async fn process_requests(state: &Arc<AppState>) {
+    // requests for generators with an open circuit stay queued until it closes,
+    // a half open circuit gets a single trial request
+    let (generators, half_open_generators): (Vec<&str>, Vec<&str>) = state
+        .media_generators
+        .available_generators()
+        .into_iter()
+        .partition(|generator| state.media_generators.is_closed(generator));
+
+    for generator in half_open_generators {
+        if !state.media_generators.begin_trial(generator) {
+            continue;
+        }
+
+        match service::claim_generate_media_requests_as_admin(&[generator], 1, &state.pool).await {
+            Ok(requests) if !requests.is_empty() => dispatch_requests(requests, state).await,
+            Ok(_) => state.media_generators.cancel_trial(generator),
+            Err(e) => {
+                tracing::error!("process_requests: {:?}", e);
+                state.media_generators.cancel_trial(generator);
+            }
+        }
+    }
+
+    if generators.is_empty() {
+        return;
+    }
+
+    loop {
+        let requests = match service::claim_generate_media_requests_as_admin(
+            &generators,
+            CLAIM_BATCH_SIZE,
+            &state.pool,
+        )
+        .await
+        {
+            Ok(requests) => requests,
+            Err(e) => {
+                tracing::error!("process_requests: {:?}", e);
+                return;
+            }
+        };
+
+        if requests.is_empty() {
+            return;
+        }
+
+        dispatch_requests(requests, state).await;
+    }
+}
+
+async fn dispatch_requests(requests: Vec<GenerateMediaRequest>, state: &Arc<AppState>) {
+    tracing::debug!("claimed {} request(s)", requests.len());
+
+    for request in requests {
+        let Some(provider) = state
+            .media_generators
+            .get(&request.generate_media_dto.generator)
+        else {
+            tracing::error!(
+                "process_requests: generator {} is not registered",
+                request.generate_media_dto.generator
+            );
+            continue;
+        };
+
+        events::publish(
+            &request.id,
+            &GenerateMediaRequestEventType::Processing,
+            None,
+            state,
+        )
+        .await;
+
+        provider.spawn_generate_media_task(request, state.clone());
+    }
+}
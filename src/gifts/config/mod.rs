@@ -0,0 +1,3 @@
+// most ink a user can gift away within GIFT_WINDOW
+pub static DAILY_GIFT_CAP: i64 = 10000;
+pub static GIFT_WINDOW: u64 = 86400;
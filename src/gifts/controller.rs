@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use validator::Validate;
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
+
+use super::{dtos::gift_ink_dto::GiftInkDto, service};
+
+pub async fn gift_ink(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<GiftInkDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            service::gift_ink(&id, &dto, &claims, &state.pool).await
+        }
+        Err(e) => Err(e),
+    }
+}
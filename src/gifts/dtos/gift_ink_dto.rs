@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct GiftInkDto {
+    #[validate(range(min = 1, message = "amount must be greater than 0."))]
+    pub amount: i64,
+}
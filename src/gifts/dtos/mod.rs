@@ -0,0 +1 @@
+pub mod gift_ink_dto;
@@ -0,0 +1,29 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum GiftsApiError {
+    CannotGiftSelf,
+    NotEnoughInk,
+    DailyCapExceeded,
+}
+
+impl GiftsApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::CannotGiftSelf => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "You cannot gift ink to yourself.".to_string(),
+            },
+            Self::NotEnoughInk => ApiError {
+                code: StatusCode::NOT_ACCEPTABLE,
+                message: "Not enough ink.".to_string(),
+            },
+            Self::DailyCapExceeded => ApiError {
+                code: StatusCode::TOO_MANY_REQUESTS,
+                message: "You have reached your daily gifting limit.".to_string(),
+            },
+        }
+    }
+}
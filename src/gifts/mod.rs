@@ -0,0 +1,5 @@
+pub mod config;
+pub mod controller;
+pub mod dtos;
+pub mod errors;
+pub mod service;
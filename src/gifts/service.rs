@@ -0,0 +1,188 @@
+use serde_json::json;
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError, util::time},
+    auth::jwt::models::claims::Claims,
+    transactions::{self, enums::transaction_type::TransactionType},
+    users::{
+        self,
+        errors::UsersApiError,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+};
+
+use super::{
+    config::{DAILY_GIFT_CAP, GIFT_WINDOW},
+    dtos::gift_ink_dto::GiftInkDto,
+    errors::GiftsApiError,
+};
+
+pub async fn gift_ink(
+    recipient_id: &str,
+    dto: &GiftInkDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    if recipient_id == claims.id {
+        return Err(GiftsApiError::CannotGiftSelf.value());
+    }
+
+    let Ok(mut tx) = pool.begin().await else {
+        tracing::warn!("gift_ink failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = check_can_gift_as_tx(recipient_id, dto.amount, claims, &mut tx).await {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    let transaction_id = Uuid::new_v4().to_string();
+    let data = json!({
+        "recipient_id": recipient_id,
+        "amount": dto.amount,
+    });
+
+    if let Err(e) = transactions::service::create_transaction_of_type(
+        &transaction_id,
+        &claims.id,
+        TransactionType::GIFT,
+        data,
+        &mut tx,
+    )
+    .await
+    {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    // the gifted ink moves its share of ink_sum along with it
+    let sender_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: Some(dto.amount),
+        ink_sum_increase: None,
+        ink_sum_decrease: Some(dto.amount),
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &sender_ink_dto,
+        InkLedgerReason::GIFT_SENT,
+        Some(&transaction_id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("gift_ink failed to roll back sender edit: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    let recipient_ink_dto = EditUserInkDto {
+        ink_increase: Some(dto.amount),
+        ink_decrease: None,
+        ink_sum_increase: Some(dto.amount),
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        recipient_id,
+        &recipient_ink_dto,
+        InkLedgerReason::GIFT_RECEIVED,
+        Some(&transaction_id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("gift_ink failed to roll back recipient edit: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("gift_ink failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// locks the sender and the recipient so concurrent gifts are checked one at a time,
+// always in id order so two users gifting each other can't deadlock
+async fn check_can_gift_as_tx(
+    recipient_id: &str,
+    amount: i64,
+    claims: &Claims,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (String, i64, i64)>(
+        "
+        SELECT id, ink::BIGINT, ink_pending::BIGINT FROM users
+        WHERE id = ANY($1)
+        ORDER BY id
+        FOR UPDATE
+        ",
+    )
+    .bind(vec![claims.id.to_string(), recipient_id.to_string()])
+    .fetch_all(&mut *tx)
+    .await;
+
+    let rows = match sqlx_result {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("check_can_gift_as_tx: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    if rows.len() != 2 {
+        return Err(UsersApiError::UserNotFound.value());
+    }
+
+    let Some((_, ink, ink_pending)) = rows.into_iter().find(|row| row.0 == claims.id) else {
+        return Err(UsersApiError::UserNotFound.value());
+    };
+
+    if ink - ink_pending < amount {
+        return Err(GiftsApiError::NotEnoughInk.value());
+    }
+
+    let sqlx_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT COALESCE(SUM((data->>'amount')::BIGINT), 0)::BIGINT FROM transactions
+        WHERE user_id = $1 AND event_type = $2 AND created_at > $3
+        ",
+    )
+    .bind(&claims.id)
+    .bind(TransactionType::GIFT)
+    .bind((time::current_time_in_secs() - GIFT_WINDOW) as i64)
+    .fetch_one(&mut *tx)
+    .await;
+
+    let gifted = match sqlx_result {
+        Ok(row) => row.0,
+        Err(e) => {
+            tracing::error!("check_can_gift_as_tx: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    if gifted + amount > DAILY_GIFT_CAP {
+        return Err(GiftsApiError::DailyCapExceeded.value());
+    }
+
+    Ok(())
+}
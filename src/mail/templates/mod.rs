@@ -1,2 +1,4 @@
 pub mod request_email_update_template;
 pub mod request_password_update_template;
+pub mod unlock_account_template;
+pub mod verify_email_template;
@@ -0,0 +1,29 @@
+use crate::{app, users::models::user::User};
+
+pub fn unlock_account_template(
+    user: &User,
+    access_token: &str,
+    frontend_url: &str,
+) -> (String, String) {
+    let url = format!("{}/auth/unlock/{}", frontend_url, access_token);
+
+    (
+        format!("Your {} account was locked", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello {},</p>
+            <p>We noticed too many failed attempts to log in to your {} account, so we locked it for 15 minutes.</p>
+            <p>If this was you, you can use the following link to unlock it now:</p>
+            <a href={}>{}</a>
+            <p>This link will expire in 1 hour.</p>
+            <p>If this was not you, consider updating your password.</p>
+            <p>Your friends at {}</p>
+            ",
+            user.displayname,
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
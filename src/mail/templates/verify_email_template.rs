@@ -0,0 +1,29 @@
+use crate::{app, users::models::user::User};
+
+pub fn verify_email_template(
+    user: &User,
+    access_token: &str,
+    frontend_url: &str,
+) -> (String, String) {
+    let url = format!("{}/auth/verify/{}", frontend_url, access_token);
+
+    (
+        format!("Verify your {} email", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello {},</p>
+            <p>Welcome to {}!</p>
+            <p>To verify your email, visit the following link:</p>
+            <a href={}>{}</a>
+            <p>This link will expire in 1 hour.</p>
+            <p>If you did not create an account, ignore this email.</p>
+            <p>Your friends at {}</p>
+            ",
+            user.displayname,
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
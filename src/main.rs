@@ -1,21 +1,20 @@
 #![allow(dead_code)]
 // #![allow(unused_variables)]
 
-use std::{env, net::SocketAddr, sync::Arc, time::Duration};
+use std::{env, net::SocketAddr, sync::Arc};
 
 #[macro_use]
 extern crate lazy_static;
 
 use app::util::fcm::client::fcm_client::FcmClient;
 use axum::{
-    error_handling::HandleErrorLayer,
     http::Method,
+    middleware,
     routing::{delete, get, patch, post},
-    BoxError, Router,
+    Router,
 };
 use sqlx::{postgres::PgPoolOptions, PgPool};
-use tokio::sync::RwLock;
-use tower::{buffer::BufferLayer, limit::RateLimitLayer, ServiceBuilder};
+use tokio::sync::{broadcast, Notify, RwLock};
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
 
@@ -23,22 +22,43 @@ use crate::{
     app::{
         enums::api_status::ApiStatus,
         envy::Envy,
-        errors::DefaultApiError,
         util::{fcm, janitor},
     },
-    media::util::backblaze::b2::{self, b2::B2},
+    auth::{identity::structs::jwks_cache::JwksCache, jwt::structs::jwt_keys::JwtKeys},
+    generate_media_requests::{
+        config::EVENTS_BUFFER_SIZE, models::generate_media_request_event::GenerateMediaRequestEvent,
+    },
+    media::{
+        apis::{
+            dalle::provider::DalleProvider, mist::provider::MistProvider,
+            stable_horde::provider::StableHordeProvider,
+        },
+        providers::media_generator_registry::MediaGeneratorRegistry,
+        util::backblaze::b2::{self, b2::B2},
+    },
+    pricing::structs::ink_pricing::InkPricing,
+    rate_limits::structs::rate_limiter::RateLimiter,
 };
 
+mod api_keys;
 mod app;
 mod auth;
 mod blocks;
 mod devices;
 mod follows;
 mod generate_media_requests;
+mod gifts;
 mod mail;
 mod media;
 mod posts;
+mod pricing;
+mod products;
+mod promo_codes;
+mod rate_limits;
+mod roles;
+mod subscriptions;
 mod transactions;
+mod upscales;
 mod users;
 mod webhooks;
 
@@ -48,6 +68,13 @@ pub struct AppState {
     pub client: reqwest::Client,
     pub fcm_client: Arc<RwLock<FcmClient>>,
     pub b2: Arc<RwLock<B2>>,
+    pub media_generators: Arc<MediaGeneratorRegistry>,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub jwks_cache: Arc<JwksCache>,
+    pub ink_pricing: Arc<InkPricing>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub generate_media_queue: Arc<Notify>,
+    pub generate_media_events: broadcast::Sender<GenerateMediaRequestEvent>,
     pub api_state: Arc<ApiState>,
     pub envy: Envy,
 }
@@ -126,17 +153,45 @@ async fn main() {
 
     tracing::info!("logged in to backblaze");
 
+    let jwt_keys = match JwtKeys::from_envy(&envy) {
+        Ok(jwt_keys) => jwt_keys,
+        Err(e) => panic!("{:#?}", e),
+    };
+
+    tracing::info!("loaded jwt keys");
+
+    let mut media_generators = MediaGeneratorRegistry::new();
+    media_generators.register(Box::new(MistProvider));
+    media_generators.register(Box::new(StableHordeProvider));
+    media_generators.register(Box::new(DalleProvider));
+
     let state = Arc::new(AppState {
         pool,
         client,
         fcm_client: Arc::new(RwLock::new(fcm_client)),
         b2: Arc::new(RwLock::new(b2)),
+        media_generators: Arc::new(media_generators),
+        jwt_keys: Arc::new(jwt_keys),
+        jwks_cache: Arc::new(JwksCache::new()),
+        ink_pricing: Arc::new(InkPricing::new()),
+        rate_limiter: Arc::new(RateLimiter::new()),
+        generate_media_queue: Arc::new(Notify::new()),
+        generate_media_events: broadcast::channel(EVENTS_BUFFER_SIZE).0,
         api_state: Arc::new(ApiState {
             api_status: Arc::new(RwLock::new(ApiStatus::Online.value())),
         }),
         envy,
     });
 
+    pricing::refresher::spawn(state.clone());
+    tracing::info!("pricing refresher spawned");
+
+    generate_media_requests::worker::spawn(state.clone());
+    tracing::info!("generate media worker spawned");
+
+    generate_media_requests::events::spawn_listener(state.clone());
+    tracing::info!("generate media events listener spawned");
+
     if role == "main" {
         janitor::spawn(state.clone());
         tracing::info!("janitor spawned");
@@ -147,19 +202,40 @@ async fn main() {
         .route("/", get(app::controller::get_root))
         .route("/status", get(app::controller::get_api_state))
         .route("/status", patch(app::controller::edit_api_state))
+        .route("/.well-known/jwks.json", get(auth::controller::get_jwks))
         // TRANSACTIONS
         .route(
             "/transactions",
             post(transactions::controller::handle_webhook),
         )
+        .route(
+            "/transactions/stripe",
+            post(transactions::controller::handle_stripe_webhook),
+        )
         // AUTH
         .route("/auth/register", post(auth::controller::register))
         .route("/auth/login", post(auth::controller::login))
+        .route(
+            "/auth/login/2fa",
+            post(auth::controller::login_with_second_factor),
+        )
+        .route("/auth/apple", post(auth::controller::login_with_apple))
+        .route("/auth/google", post(auth::controller::login_with_google))
+        .route("/auth/apple/link", post(auth::controller::link_apple))
+        .route("/auth/google/link", post(auth::controller::link_google))
         .route(
             "/auth/email",
             post(auth::controller::request_email_update_mail),
         )
         .route("/auth/email", patch(auth::controller::process_email_edit))
+        .route(
+            "/auth/verify",
+            post(auth::controller::request_email_verification_mail),
+        )
+        .route(
+            "/auth/verify",
+            patch(auth::controller::process_email_verification),
+        )
         .route(
             "/auth/password",
             post(auth::controller::request_password_update_mail),
@@ -168,10 +244,31 @@ async fn main() {
             "/auth/password",
             patch(auth::controller::process_password_edit),
         )
+        .route("/auth/password/set", post(auth::controller::set_password))
+        .route(
+            "/auth/2fa/totp",
+            post(auth::controller::begin_totp_enrollment),
+        )
+        .route("/auth/2fa/totp", delete(auth::controller::disable_totp))
+        .route(
+            "/auth/2fa/totp/confirm",
+            post(auth::controller::confirm_totp_enrollment),
+        )
+        .route(
+            "/auth/unlock",
+            patch(auth::controller::process_account_unlock),
+        )
         .route("/auth/refresh", post(auth::controller::refresh))
         .route("/auth/devices", get(auth::controller::get_devices))
         .route("/auth/logout", post(auth::controller::logout))
         .route("/auth/delete", post(auth::controller::delete_account))
+        // API_KEYS
+        .route("/api-keys", post(api_keys::controller::create_api_key))
+        .route("/api-keys", get(api_keys::controller::get_api_keys))
+        .route(
+            "/api-keys/:id",
+            delete(api_keys::controller::revoke_api_key_by_id),
+        )
         // DEVICES
         .route(
             "/devices/:id",
@@ -180,8 +277,39 @@ async fn main() {
         // USERS
         .route("/users", get(users::controller::get_users))
         .route("/users/me", get(users::controller::get_user_from_request))
+        .route(
+            "/users/me/ink/history",
+            get(users::controller::get_ink_history),
+        )
+        .route(
+            "/users/ink/reconcile",
+            post(users::controller::reconcile_ink),
+        )
         .route("/users/:id", get(users::controller::get_user_by_id))
         .route("/users/:id", patch(users::controller::edit_user_by_id))
+        .route("/users/:id/gift", post(gifts::controller::gift_ink))
+        .route(
+            "/users/:id/ink",
+            post(users::controller::adjust_user_ink_by_id),
+        )
+        // ROLES
+        .route("/users/:id/roles", post(roles::controller::grant_role))
+        .route(
+            "/users/:id/roles/:role",
+            delete(roles::controller::revoke_role),
+        )
+        .route(
+            "/users/:id/permissions",
+            post(roles::controller::grant_permission),
+        )
+        .route(
+            "/users/:id/permissions/:permission",
+            delete(roles::controller::revoke_permission),
+        )
+        .route(
+            "/users/:id/role-changes",
+            get(roles::controller::get_role_changes),
+        )
         // POSTS
         .route("/posts", get(posts::controller::get_posts))
         .route("/posts/:id", get(posts::controller::get_post_by_id))
@@ -198,15 +326,76 @@ async fn main() {
             "/webhooks/modal",
             post(webhooks::modal::webhook::receive_webhook),
         )
-        // .route("/media/import", post(media::controller::import_media))
+        .route("/media/import", post(media::controller::import_media))
         .route("/media", get(media::controller::get_media))
         .route("/media/:id", get(media::controller::get_media_by_id))
         .route("/media/:id", delete(media::controller::delete_media_by_id))
+        .route(
+            "/media/:id/upscale",
+            post(upscales::controller::upscale_media),
+        )
+        // UPSCALES
+        .route("/upscales", get(upscales::controller::get_upscales))
         // GENERATE_MEDIA_REQUESTS
         .route(
             "/generate-media-requests",
             get(generate_media_requests::controller::get_generate_media_requests),
         )
+        .route(
+            "/generate-media-requests/:id",
+            delete(generate_media_requests::controller::cancel_generate_media_request_by_id),
+        )
+        .route(
+            "/generate-media-requests/:id/events",
+            get(generate_media_requests::controller::get_generate_media_request_events),
+        )
+        // PRICING
+        .route("/pricing", get(pricing::controller::get_ink_quote))
+        .route(
+            "/pricing/prices",
+            post(pricing::controller::create_ink_price),
+        )
+        .route("/pricing/prices", get(pricing::controller::get_ink_prices))
+        .route(
+            "/pricing/prices/:id",
+            patch(pricing::controller::edit_ink_price_by_id),
+        )
+        .route(
+            "/pricing/prices/:id",
+            delete(pricing::controller::delete_ink_price_by_id),
+        )
+        // PRODUCTS
+        .route("/products", post(products::controller::create_product))
+        .route("/products", get(products::controller::get_products))
+        .route(
+            "/products/:id",
+            get(products::controller::get_product_by_id),
+        )
+        .route(
+            "/products/:id",
+            patch(products::controller::edit_product_by_id),
+        )
+        .route(
+            "/products/:id",
+            delete(products::controller::delete_product_by_id),
+        )
+        // PROMO_CODES
+        .route(
+            "/promo-codes",
+            post(promo_codes::controller::create_promo_code),
+        )
+        .route(
+            "/promo-codes",
+            get(promo_codes::controller::get_promo_codes),
+        )
+        .route(
+            "/promo-codes/redeem",
+            post(promo_codes::controller::redeem_promo_code),
+        )
+        .route(
+            "/promo-codes/:id",
+            delete(promo_codes::controller::delete_promo_code_by_id),
+        )
         // FOLLOWS
         .route("/follow/:id", post(follows::controller::follow))
         .route("/follows", get(follows::controller::get_follows))
@@ -216,23 +405,19 @@ async fn main() {
         .route("/blocks", get(blocks::controller::get_blocks))
         .route("/block/:id", delete(blocks::controller::unblock))
         // LAYERS
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limits::middleware::rate_limit,
+        ))
         .layer(cors)
         .layer(tower_http::limit::RequestBodyLimitLayer::new(2097152))
-        .layer(
-            ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(|_err: BoxError| async move {
-                    DefaultApiError::InternalServerError.value();
-                }))
-                .layer(BufferLayer::new(1024))
-                .layer(RateLimitLayer::new(5, Duration::from_secs(1))),
-        )
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     tracing::info!("listening on {}", addr);
 
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
@@ -1 +1,4 @@
+use crate::media::enums::media_model::MediaModel;
+
 pub static API_URL: &str = "https://api.openai.com/v1";
+pub static DEFAULT_MODEL: &str = MediaModel::DALLE;
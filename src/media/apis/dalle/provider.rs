@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use crate::{
+    generate_media_requests::models::generate_media_request::GenerateMediaRequest,
+    media::{
+        enums::media_generator::MediaGenerator,
+        providers::media_generator_provider::MediaGeneratorProvider,
+    },
+    AppState,
+};
+
+use super::{config::DEFAULT_MODEL, service};
+
+pub struct DalleProvider;
+
+impl MediaGeneratorProvider for DalleProvider {
+    fn generator(&self) -> &'static str {
+        return MediaGenerator::DALLE;
+    }
+
+    fn default_model(&self) -> &'static str {
+        return DEFAULT_MODEL;
+    }
+
+    fn is_valid_model(&self, model: &str) -> bool {
+        return service::is_valid_model(model);
+    }
+
+    fn is_valid_size(&self, width: &u16, height: &u16, _model: &str) -> bool {
+        return service::is_valid_size(width, height);
+    }
+
+    fn is_valid_number(&self, number: u8, _model: &str) -> bool {
+        return service::is_valid_number(number);
+    }
+
+    fn is_valid_mode(&self, mode: &str, _model: &str) -> bool {
+        return service::is_valid_mode(mode);
+    }
+
+    fn base_ink(&self) -> f64 {
+        return 40.0;
+    }
+
+    fn spawn_generate_media_task(&self, request: GenerateMediaRequest, state: Arc<AppState>) {
+        service::spawn_generate_media_task(request, state);
+    }
+}
@@ -14,12 +14,19 @@ use crate::{
         util::multipart::models::file_properties::FileProperties,
     },
     generate_media_requests::{
-        enums::generate_media_request_status::GenerateMediaRequestStatus,
+        self,
+        enums::{
+            generate_media_request_event_type::GenerateMediaRequestEventType,
+            generate_media_request_status::GenerateMediaRequestStatus,
+        },
         models::generate_media_request::GenerateMediaRequest,
     },
     media::{
-        self, dtos::generate_media_dto::GenerateMediaDto, enums::media_model::MediaModel,
-        models::media::Media, util::backblaze,
+        self,
+        dtos::generate_media_dto::GenerateMediaDto,
+        enums::{media_generate_mode::MediaGenerateMode, media_model::MediaModel},
+        models::media::Media,
+        util::backblaze,
     },
     AppState,
 };
@@ -136,13 +143,24 @@ async fn upload_image_and_create_media(
         Ok(response) => {
             let b2_download_url = &state.b2.read().await.download_url;
 
-            Ok(Media::from_request(
+            let media = Media::from_request(
                 &file_properties.id,
                 request,
                 None,
                 &response,
                 b2_download_url,
-            ))
+            );
+
+            // progress only, the ids are sent with completed once the media is committed
+            generate_media_requests::events::publish(
+                &request.id,
+                &GenerateMediaRequestEventType::MediaUploaded,
+                None,
+                state,
+            )
+            .await;
+
+            Ok(media)
         }
         Err(e) => {
             tracing::error!("upload_image_and_create_media failed upload_file_with_retry");
@@ -218,6 +236,10 @@ fn provide_input_spec(dto: &GenerateMediaDto) -> InputSpec {
     }
 }
 
+pub fn is_valid_mode(mode: &str) -> bool {
+    return matches!(mode, MediaGenerateMode::TXT2IMG);
+}
+
 pub fn is_valid_model(model: &str) -> bool {
     let valid_models: [&str; 1] = [MediaModel::DALLE];
 
@@ -0,0 +1,3 @@
+use crate::media::enums::media_model::MediaModel;
+
+pub static DEFAULT_MODEL: &str = MediaModel::STABLE_DIFFUSION_1_5;
@@ -1 +1,3 @@
+pub mod config;
+pub mod provider;
 pub mod service;
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use crate::{
+    generate_media_requests::models::generate_media_request::GenerateMediaRequest,
+    media::{
+        apis::modal, enums::media_generator::MediaGenerator,
+        providers::media_generator_provider::MediaGeneratorProvider,
+    },
+    AppState,
+};
+
+use super::{config::DEFAULT_MODEL, service};
+
+pub struct MistProvider;
+
+impl MediaGeneratorProvider for MistProvider {
+    fn generator(&self) -> &'static str {
+        return MediaGenerator::MIST;
+    }
+
+    fn default_model(&self) -> &'static str {
+        return DEFAULT_MODEL;
+    }
+
+    fn is_valid_model(&self, model: &str) -> bool {
+        return service::is_valid_model(model);
+    }
+
+    fn is_valid_size(&self, width: &u16, height: &u16, model: &str) -> bool {
+        return service::is_valid_size(width, height, model);
+    }
+
+    fn is_valid_number(&self, number: u8, model: &str) -> bool {
+        return service::is_valid_number(number, model);
+    }
+
+    fn is_valid_mode(&self, mode: &str, model: &str) -> bool {
+        return service::is_valid_mode(mode, model);
+    }
+
+    fn fallback_generator(&self) -> Option<&'static str> {
+        return Some(MediaGenerator::STABLE_HORDE);
+    }
+
+    fn base_ink(&self) -> f64 {
+        return 10.0;
+    }
+
+    fn spawn_generate_media_task(&self, request: GenerateMediaRequest, state: Arc<AppState>) {
+        modal::service::spawn_generate_media_task(request, state);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::media::enums::media_model::MediaModel;
+use crate::media::enums::{media_generate_mode::MediaGenerateMode, media_model::MediaModel};
 
 pub fn is_valid_model(model: &str) -> bool {
     let valid_models: [&str; 7] = [
@@ -37,3 +37,14 @@ pub fn is_valid_size(width: &u16, height: &u16, _model: &str) -> bool {
 pub fn is_valid_number(number: u8, _model: &str) -> bool {
     return number > 0 && number < 7;
 }
+
+pub fn is_valid_mode(mode: &str, model: &str) -> bool {
+    match mode {
+        MediaGenerateMode::TXT2IMG | MediaGenerateMode::IMG2IMG => true,
+        // only these models have an inpainting checkpoint deployed
+        MediaGenerateMode::INPAINT | MediaGenerateMode::OUTPAINT => {
+            model == MediaModel::STABLE_DIFFUSION_1_5 || model == MediaModel::STABLE_DIFFUSION_2_1
+        }
+        _ => false,
+    }
+}
@@ -1,9 +1,13 @@
 // pub static API_URL: &str = "https://ydubuc--mist-modal-entrypoint.modal.run";
 // pub static API_URL: &str = "https://ydubuc--mist-dreamlike-art-dreamlike-diffusion-1-0-entrypoint.modal.run"
 
-use crate::media::enums::media_model::MediaModel;
+use crate::media::enums::{media_generate_mode::MediaGenerateMode, media_model::MediaModel};
+
+pub fn api_url<'a>(model: &'a str, mode: &'a str) -> &'a str {
+    if mode == MediaGenerateMode::INPAINT || mode == MediaGenerateMode::OUTPAINT {
+        return inpaint_api_url(model);
+    }
 
-pub fn api_url(model: &str) -> &str {
     match model {
         MediaModel::STABLE_DIFFUSION_1_5 => {
             "https://ydubuc--mist-runwayml-stable-diffusion-v1-5-entrypoint.modal.run"
@@ -27,3 +31,22 @@ pub fn api_url(model: &str) -> &str {
         _ => "",
     }
 }
+
+fn inpaint_api_url(model: &str) -> &str {
+    match model {
+        MediaModel::STABLE_DIFFUSION_1_5 => {
+            "https://ydubuc--mist-runwayml-stable-diffusion-inpainting-entrypoint.modal.run"
+        }
+        MediaModel::STABLE_DIFFUSION_2_1 => {
+            "https://ydubuc--mist-stabilityai-stable-diffusion-2-inpainting-entrypoint.modal.run"
+        }
+        _ => "",
+    }
+}
+
+pub fn upscale_api_url(model: &str) -> &str {
+    match model {
+        MediaModel::REAL_ESRGAN_X4 => "https://ydubuc--mist-real-esrgan-x4-entrypoint.modal.run",
+        _ => "",
+    }
+}
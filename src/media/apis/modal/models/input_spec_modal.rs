@@ -10,5 +10,12 @@ pub struct InputModal {
     pub number: u8,
     pub steps: u16,
     pub cfg_scale: u8,
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask_image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strength: Option<f32>,
     pub callback_url: String,
 }
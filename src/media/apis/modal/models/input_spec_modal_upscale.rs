@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct InputModalUpscale {
+    pub image: String,
+    pub scale: u8,
+}
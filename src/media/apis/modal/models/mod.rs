@@ -1 +1,2 @@
 pub mod input_spec_modal;
+pub mod input_spec_modal_upscale;
@@ -12,17 +12,25 @@ use crate::{
         util::multipart::models::file_properties::FileProperties,
     },
     generate_media_requests::{
-        enums::generate_media_request_status::GenerateMediaRequestStatus,
+        self,
+        enums::{
+            generate_media_request_event_type::GenerateMediaRequestEventType,
+            generate_media_request_status::GenerateMediaRequestStatus,
+        },
         models::generate_media_request::GenerateMediaRequest,
     },
-    media::{self, models::media::Media, util::backblaze},
+    media::{self, apis::mist::config::DEFAULT_MODEL, models::media::Media, util::backblaze},
     webhooks::modal::dtos::receive_webhook_dto::{ReceiveWebhookDto, ReceiveWebhookDtoOutput},
     AppState,
 };
 
 use super::{
-    config::api_url, models::input_spec_modal::InputModal,
-    structs::modal_entrypoint_response::ModalEntrypointResponse,
+    config::{api_url, upscale_api_url},
+    models::{input_spec_modal::InputModal, input_spec_modal_upscale::InputModalUpscale},
+    structs::{
+        modal_entrypoint_response::ModalEntrypointResponse,
+        modal_upscale_response::ModalUpscaleResponse,
+    },
 };
 
 pub fn spawn_generate_media_task(
@@ -36,6 +44,15 @@ pub fn spawn_generate_media_task(
                     "spawn_generate_media_task failed call_modal_entrypoint_with_retry: {:?}",
                     e
                 );
+
+                media::service::on_generate_media_completion_with_retry(
+                    &generate_media_request,
+                    &GenerateMediaRequestStatus::Error,
+                    &None,
+                    &state,
+                )
+                .await
+                .ok();
             }
             _ => {}
         }
@@ -149,13 +166,24 @@ async fn upload_image_and_create_media(
         Ok(response) => {
             let b2_download_url = &state.b2.read().await.download_url;
 
-            Ok(Media::from_request(
+            let media = Media::from_request(
                 &file_properties.id,
                 request,
                 Some(&output.seed),
                 &response,
                 b2_download_url,
-            ))
+            );
+
+            // progress only, the ids are sent with completed once the media is committed
+            generate_media_requests::events::publish(
+                &request.id,
+                &GenerateMediaRequestEventType::MediaUploaded,
+                None,
+                state,
+            )
+            .await;
+
+            Ok(media)
         }
         Err(e) => {
             tracing::error!("upload_image_and_create_media failed upload_file_with_retry");
@@ -194,6 +222,65 @@ async fn get_bytes(url: &str, client: &reqwest::Client) -> Result<Bytes, ApiErro
     }
 }
 
+// unlike generation, upscaling is quick enough for modal to respond with the result directly
+pub async fn upscale_media(
+    media: &Media,
+    model: &str,
+    scale: u8,
+    state: &Arc<AppState>,
+) -> Result<Bytes, ApiError> {
+    let Ok(bytes) = get_bytes_with_retry(&media.url, &state.client).await else {
+        return Err(ApiError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Failed to get bytes".to_string(),
+        });
+    };
+
+    let input_spec = InputModalUpscale {
+        image: base64::encode(bytes),
+        scale,
+    };
+
+    let mut headers = header::HeaderMap::new();
+    headers.insert("Content-Type", "application/json".parse().unwrap());
+    headers.insert(
+        "Authorization",
+        format!("Bearer {}", state.envy.modal_webhook_secret)
+            .parse()
+            .unwrap(),
+    );
+
+    let result = state
+        .client
+        .post(upscale_api_url(model))
+        .headers(headers)
+        .json(&input_spec)
+        .send()
+        .await;
+
+    let modal_upscale_response: ModalUpscaleResponse = match result {
+        Ok(res) => match res.text().await {
+            Ok(text) => match serde_json::from_str(&text) {
+                Ok(modal_upscale_response) => modal_upscale_response,
+                Err(_) => {
+                    tracing::warn!("upscale_media (1): {:?}", text);
+                    return Err(DefaultApiError::InternalServerError.value());
+                }
+            },
+            Err(e) => {
+                tracing::warn!("upscale_media (2): {:?}", e);
+                return Err(DefaultApiError::InternalServerError.value());
+            }
+        },
+        Err(e) => {
+            tracing::warn!("upscale_media (3): {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    get_bytes_with_retry(&modal_upscale_response.url, &state.client).await
+}
+
 async fn call_modal_entrypoint_with_retry(
     request: &GenerateMediaRequest,
     state: &Arc<AppState>,
@@ -211,9 +298,23 @@ async fn call_modal_entrypoint(
     state: &Arc<AppState>,
 ) -> Result<ModalEntrypointResponse, ApiError> {
     let modal_webhook_secret = &state.envy.modal_webhook_secret;
-    let input_spec = provide_input_spec(request, state);
     let dto = &request.generate_media_dto;
-    let model = dto.model.clone().unwrap_or(dto.default_model().to_string());
+
+    let get_input_image_result =
+        media::service::get_image_base64_if_any(&dto.input_media_id, &state.pool).await;
+    let Ok(input_image) = get_input_image_result else {
+        return Err(get_input_image_result.unwrap_err());
+    };
+
+    let get_mask_image_result =
+        media::service::get_image_base64_if_any(&dto.mask_media_id, &state.pool).await;
+    let Ok(mask_image) = get_mask_image_result else {
+        return Err(get_mask_image_result.unwrap_err());
+    };
+
+    let input_spec = provide_input_spec(request, input_image, mask_image, state);
+    let model = dto.model.clone().unwrap_or(DEFAULT_MODEL.to_string());
+    let mode = dto.mode();
 
     let mut headers = header::HeaderMap::new();
     headers.insert("Content-Type", "application/json".parse().unwrap());
@@ -224,7 +325,7 @@ async fn call_modal_entrypoint(
 
     let result = state
         .client
-        .post(api_url(&model))
+        .post(api_url(&model, &mode))
         .headers(headers)
         .json(&input_spec)
         .send()
@@ -251,7 +352,12 @@ async fn call_modal_entrypoint(
     }
 }
 
-fn provide_input_spec(request: &GenerateMediaRequest, state: &Arc<AppState>) -> Value {
+fn provide_input_spec(
+    request: &GenerateMediaRequest,
+    input_image: Option<String>,
+    mask_image: Option<String>,
+    state: &Arc<AppState>,
+) -> Value {
     let dto = &request.generate_media_dto;
 
     let input: Value = serde_json::to_value(InputModal {
@@ -261,8 +367,12 @@ fn provide_input_spec(request: &GenerateMediaRequest, state: &Arc<AppState>) ->
         width: dto.width,
         height: dto.height,
         number: dto.number,
-        steps: 50,
+        steps: dto.steps(),
         cfg_scale: dto.cfg_scale.unwrap_or(8),
+        mode: dto.mode(),
+        init_image: input_image,
+        mask_image,
+        strength: dto.strength,
         callback_url: format!("https://{}/webhooks/modal", state.envy.railway_static_url),
     })
     .unwrap();
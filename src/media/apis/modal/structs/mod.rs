@@ -1 +1,2 @@
 pub mod modal_entrypoint_response;
+pub mod modal_upscale_response;
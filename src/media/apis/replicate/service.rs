@@ -25,7 +25,7 @@ use crate::{
 };
 
 use super::{
-    config::API_URL,
+    config::{API_URL, DEFAULT_MODEL},
     enums::replicate_model_version::ReplicateModelVersion,
     models::{
         input_spec::InputSpec, input_spec_openjourney::InputSpecOpenjourney,
@@ -424,7 +424,7 @@ async fn get_prediction_by_id(
 }
 
 fn provide_input_spec(dto: &GenerateMediaDto) -> InputSpec {
-    let model = dto.model.clone().unwrap_or(dto.default_model().to_string());
+    let model = dto.model.clone().unwrap_or(DEFAULT_MODEL.to_string());
     let version: String;
 
     let input: Value = match model.as_ref() {
@@ -1 +1,4 @@
+use crate::media::enums::media_model::MediaModel;
+
 pub static API_URL: &str = "https://stablehorde.net/api/v2";
+pub static DEFAULT_MODEL: &str = MediaModel::STABLE_DIFFUSION_1_5;
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod enums;
 pub mod models;
+pub mod provider;
 pub mod service;
 pub mod structs;
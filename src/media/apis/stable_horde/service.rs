@@ -12,18 +12,26 @@ use crate::{
         util::multipart::models::file_properties::FileProperties,
     },
     generate_media_requests::{
-        enums::generate_media_request_status::GenerateMediaRequestStatus,
+        self,
+        enums::{
+            generate_media_request_event_type::GenerateMediaRequestEventType,
+            generate_media_request_status::GenerateMediaRequestStatus,
+        },
+        errors::GenerateMediaRequestsApiError,
         models::generate_media_request::GenerateMediaRequest,
     },
     media::{
-        self, dtos::generate_media_dto::GenerateMediaDto, enums::media_model::MediaModel,
-        models::media::Media, util::backblaze,
+        self,
+        dtos::generate_media_dto::GenerateMediaDto,
+        enums::{media_generate_mode::MediaGenerateMode, media_model::MediaModel},
+        models::media::Media,
+        util::backblaze,
     },
     AppState,
 };
 
 use super::{
-    config::API_URL,
+    config::{API_URL, DEFAULT_MODEL},
     enums::stable_horde_model_version::StableHordeModelVersion,
     models::input_spec::{InputSpec, InputSpecParams},
     structs::{
@@ -61,15 +69,34 @@ pub fn spawn_generate_media_task(
     });
 }
 
+pub fn spawn_cancel_generate_media_task(
+    generate_media_request: GenerateMediaRequest,
+    state: Arc<AppState>,
+) {
+    let Some(id) = generate_media_request.provider_request_id else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let stable_horde_api_key = &state.envy.stable_horde_api_key;
+
+        if let Err(e) = cancel_request_by_id(&id, stable_horde_api_key, &state.client).await {
+            tracing::error!(
+                "spawn_cancel_generate_media_task failed cancel_request_by_id: {:?}",
+                e
+            );
+        }
+    });
+}
+
 async fn generate_media(
     request: &GenerateMediaRequest,
     state: &Arc<AppState>,
 ) -> Result<Vec<Media>, ApiError> {
     let stable_horde_api_key = &state.envy.stable_horde_api_key;
-    let dto = &request.generate_media_dto;
 
     let stable_horde_request_response_result =
-        await_request_completion(dto, stable_horde_api_key, &state.client).await;
+        await_request_completion(request, stable_horde_api_key, state).await;
     let Ok(stable_horde_request_response) = stable_horde_request_response_result else {
         return Err(stable_horde_request_response_result.unwrap_err());
     };
@@ -87,6 +114,23 @@ async fn generate_media(
         generations.first().unwrap().worker_id
     );
 
+    // the request may have been canceled while stable horde was generating
+    match generate_media_requests::service::get_generate_media_request_by_id_as_admin(
+        &request.id,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(request) if !request.is_processing() => {
+            tracing::warn!(
+                "generate_media discarded results for request {}",
+                request.id
+            );
+            return Err(GenerateMediaRequestsApiError::RequestNotProcessing.value());
+        }
+        _ => {}
+    }
+
     generations.retain(|gen| gen.censored != true);
     generations.truncate(request.generate_media_dto.number.into());
 
@@ -154,13 +198,24 @@ async fn upload_image_and_create_media(
         Ok(response) => {
             let b2_download_url = &state.b2.read().await.download_url;
 
-            Ok(Media::from_request(
+            let media = Media::from_request(
                 &file_properties.id,
                 request,
                 Some(&stable_horde_generation.seed),
                 &response,
                 b2_download_url,
-            ))
+            );
+
+            // progress only, the ids are sent with completed once the media is committed
+            generate_media_requests::events::publish(
+                &request.id,
+                &GenerateMediaRequestEventType::MediaUploaded,
+                None,
+                state,
+            )
+            .await;
+
+            Ok(media)
         }
         Err(e) => {
             tracing::error!("upload_image_and_create_media failed upload_file_with_retry");
@@ -200,11 +255,22 @@ async fn get_bytes(url: &str, client: &reqwest::Client) -> Result<Bytes, ApiErro
 }
 
 async fn await_request_completion(
-    dto: &GenerateMediaDto,
+    request: &GenerateMediaRequest,
     stable_horde_api_key: &str,
-    client: &reqwest::Client,
+    state: &Arc<AppState>,
 ) -> Result<StableHordeGetRequestResponse, ApiError> {
-    let generate_async_result = generate_async_with_retry(dto, stable_horde_api_key, client).await;
+    let dto = &request.generate_media_dto;
+    let client = &state.client;
+
+    let get_source_image_result =
+        media::service::get_image_base64_if_any(&dto.input_media_id, &state.pool).await;
+    let Ok(source_image) = get_source_image_result else {
+        tracing::error!("await_request_completion failed get_image_base64_if_any");
+        return Err(get_source_image_result.unwrap_err());
+    };
+
+    let generate_async_result =
+        generate_async_with_retry(dto, &source_image, stable_horde_api_key, client).await;
     let Ok(generate_async_response) = generate_async_result else {
         tracing::error!("await_request_completion failed generate_async_with_retry");
         return Err(generate_async_result.unwrap_err());
@@ -212,6 +278,22 @@ async fn await_request_completion(
 
     let id = generate_async_response.id;
 
+    if let Err(e) =
+        generate_media_requests::service::edit_generate_media_request_provider_request_id_by_id_as_admin(
+            &request.id,
+            &id,
+            &state.pool,
+        )
+        .await
+    {
+        if e.code == StatusCode::CONFLICT {
+            // canceled before the id could be recorded
+            tracing::warn!("await_request_completion canceling orphaned request {}", id);
+            cancel_request_by_id(&id, stable_horde_api_key, client).await.ok();
+        }
+        return Err(e);
+    }
+
     sleep(Duration::from_millis(5000)).await;
 
     let Ok(initial_check_response) =
@@ -300,23 +382,25 @@ async fn await_request_completion(
 
 async fn generate_async_with_retry(
     dto: &GenerateMediaDto,
+    source_image: &Option<String>,
     stable_horde_api_key: &str,
     client: &reqwest::Client,
 ) -> Result<StableHordeGenerateAsyncResponse, ApiError> {
     let retry_strategy = FixedInterval::from_millis(10000).take(3);
 
     Retry::spawn(retry_strategy, || async {
-        generate_async(dto, stable_horde_api_key, client).await
+        generate_async(dto, source_image, stable_horde_api_key, client).await
     })
     .await
 }
 
 async fn generate_async(
     dto: &GenerateMediaDto,
+    source_image: &Option<String>,
     stable_horde_api_key: &str,
     client: &reqwest::Client,
 ) -> Result<StableHordeGenerateAsyncResponse, ApiError> {
-    let input_spec = provide_input_spec(dto);
+    let input_spec = provide_input_spec(dto, source_image);
 
     let mut headers = header::HeaderMap::new();
     headers.insert("Content-Type", "application/json".parse().unwrap());
@@ -406,8 +490,8 @@ async fn get_request_by_id(
     }
 }
 
-fn provide_input_spec(dto: &GenerateMediaDto) -> InputSpec {
-    let model = dto.model.clone().unwrap_or(dto.default_model().to_string());
+fn provide_input_spec(dto: &GenerateMediaDto, source_image: &Option<String>) -> InputSpec {
+    let model = dto.model.clone().unwrap_or(DEFAULT_MODEL.to_string());
     let version = match model.as_ref() {
         MediaModel::STABLE_DIFFUSION_1_5 => StableHordeModelVersion::STABLE_DIFFUSION,
         MediaModel::STABLE_DIFFUSION_2_1 => StableHordeModelVersion::STABLE_DIFFUSION_2_1,
@@ -426,14 +510,14 @@ fn provide_input_spec(dto: &GenerateMediaDto) -> InputSpec {
                 Some(cfg_scale) => Some(cfg_scale as i8),
                 None => None,
             },
-            denoising_strength: None,
+            denoising_strength: dto.strength,
             seed: None,
             height: Some(dto.height),
             width: Some(dto.width),
             seed_variation: None,
             post_processing: Some(vec!["GFPGAN".to_string()]),
             karras: None,
-            steps: Some(50),
+            steps: Some(dto.steps()),
             n: Some(dto.number),
         }),
         nsfw: Some(false),
@@ -441,13 +525,20 @@ fn provide_input_spec(dto: &GenerateMediaDto) -> InputSpec {
         censor_nsfw: Some(true),
         workers: None,
         models: Some(vec![version.to_string()]),
-        source_image: None,
-        source_processing: None,
+        source_image: source_image.clone(),
+        source_processing: source_image.as_ref().map(|_| "img2img".to_string()),
         source_mask: None,
         r2: Some(true),
     }
 }
 
+pub fn is_valid_mode(mode: &str) -> bool {
+    return matches!(
+        mode,
+        MediaGenerateMode::TXT2IMG | MediaGenerateMode::IMG2IMG
+    );
+}
+
 pub fn is_valid_model(model: &str) -> bool {
     let valid_models: [&str; 6] = [
         MediaModel::STABLE_DIFFUSION_1_5,
@@ -480,3 +571,30 @@ pub fn is_valid_size(width: &u16, height: &u16) -> bool {
 pub fn is_valid_number(number: u8) -> bool {
     return (number > 0) && (number < 9);
 }
+
+async fn cancel_request_by_id(
+    id: &str,
+    stable_horde_api_key: &str,
+    client: &reqwest::Client,
+) -> Result<(), ApiError> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert("Content-Type", "application/json".parse().unwrap());
+    headers.insert("apikey", stable_horde_api_key.parse().unwrap());
+
+    let url = format!("{}/generate/status/{}", API_URL, id);
+    let result = client.delete(url).headers(headers).send().await;
+
+    match result {
+        Ok(res) => match res.status().is_success() {
+            true => Ok(()),
+            false => {
+                tracing::warn!("cancel_request_by_id (1): {:?}", res.status());
+                Err(DefaultApiError::InternalServerError.value())
+            }
+        },
+        Err(e) => {
+            tracing::warn!("cancel_request_by_id (2): {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
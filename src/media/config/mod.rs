@@ -0,0 +1,13 @@
+pub static MAX_IMPORT_FILES: usize = 4;
+pub static MAX_IMPORT_FILE_SIZE: usize = 1048576;
+pub static MIN_IMPORT_DIMENSION: u32 = 64;
+pub static MAX_IMPORT_DIMENSION: u32 = 2048;
+// bytes a decoder may allocate, twice a full size rgba canvas
+pub static MAX_IMPORT_DECODE_ALLOC: u64 = 2 * 2048 * 2048 * 4;
+// total bytes of imported media a user may keep
+pub static IMPORT_STORAGE_QUOTA: i64 = 104857600;
+// circuit breaker, evaluated over the most recent generation attempts of a provider
+pub static CIRCUIT_WINDOW_SIZE: usize = 20;
+pub static CIRCUIT_MIN_SAMPLES: usize = 5;
+pub static CIRCUIT_ERROR_RATE: f64 = 0.5;
+pub static CIRCUIT_OPEN_DURATION: u64 = 120;
@@ -2,16 +2,16 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Multipart, Path, Query, State},
-    headers::{authorization::Bearer, Authorization},
     http::StatusCode,
-    Json, TypedHeader,
+    Json,
 };
 use axum_macros::debug_handler;
 use validator::Validate;
 
 use crate::{
+    api_keys::enums::api_key_scope::ApiKeyScope,
     app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
-    auth::jwt::models::claims::Claims,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
     generate_media_requests::models::generate_media_request::GenerateMediaRequest,
     AppState,
 };
@@ -25,10 +25,10 @@ use super::{
 #[debug_handler]
 pub async fn generate_media(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<GenerateMediaDto>,
 ) -> Result<Json<GenerateMediaRequest>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header_or_api_key(authorization, ApiKeyScope::MEDIA_GENERATE, &state).await {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -49,10 +49,10 @@ pub async fn generate_media(
 #[debug_handler]
 pub async fn import_media(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     multipart: Multipart,
 ) -> Result<Json<Vec<Media>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => match service::import_media(multipart, &claims, &state).await {
             Ok(media) => Ok(Json(media)),
             Err(e) => Err(e),
@@ -64,10 +64,10 @@ pub async fn import_media(
 #[debug_handler]
 pub async fn get_media(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetMediaFilterDto>,
 ) -> Result<Json<Vec<Media>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header_or_api_key(authorization, ApiKeyScope::MEDIA_READ, &state).await {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -100,9 +100,9 @@ pub async fn get_media_by_id(
 pub async fn delete_media_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::delete_media_by_id(&id, &claims, &state).await,
         Err(e) => Err(e),
     }
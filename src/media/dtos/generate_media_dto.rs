@@ -5,9 +5,10 @@ use validator::Validate;
 use crate::{
     app::models::api_error::ApiError,
     media::{
-        apis::{dalle, mist, stable_horde},
-        enums::{media_generator::MediaGenerator, media_model::MediaModel},
+        enums::{media_generate_mode::MediaGenerateMode, media_model::MediaModel},
+        providers::media_generator_provider::MediaGeneratorProvider,
     },
+    pricing::structs::ink_pricing::InkPricing,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -39,28 +40,51 @@ pub struct GenerateMediaDto {
     #[validate(length(equal = 36, message = "input_media_id must be 36 characters."))]
     pub input_media_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(equal = 36, message = "mask_media_id must be 36 characters."))]
+    pub mask_media_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0.0, max = 1.0, message = "strength must be between 0 and 1."))]
+    pub strength: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 10, max = 100, message = "steps must be between 10 and 100."))]
+    pub steps: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub publish: Option<bool>,
 }
 
 impl GenerateMediaDto {
-    pub fn default_model(&self) -> &str {
-        match self.generator.as_ref() {
-            MediaGenerator::MIST => MediaModel::STABLE_DIFFUSION_1_5,
-            MediaGenerator::STABLE_HORDE => MediaModel::STABLE_DIFFUSION_1_5,
-            MediaGenerator::DALLE => MediaModel::DALLE,
-            _ => panic!("default_model for generator not implemented."),
-        }
-    }
-
     pub fn default_cfg_scale() -> u8 {
         return 8;
     }
 
+    pub fn default_strength() -> f32 {
+        return 0.75;
+    }
+
+    pub fn default_steps() -> u16 {
+        return 50;
+    }
+
+    pub fn steps(&self) -> u16 {
+        return self.steps.unwrap_or(Self::default_steps());
+    }
+
+    // requests made before modes existed are img2img when they have an input image
+    pub fn mode(&self) -> String {
+        if let Some(mode) = &self.mode {
+            return mode.to_string();
+        }
+
+        return match self.input_media_id {
+            Some(_) => MediaGenerateMode::IMG2IMG.to_string(),
+            None => MediaGenerateMode::TXT2IMG.to_string(),
+        };
+    }
+
     pub fn formatted_prompt(&self) -> String {
-        let model = self
-            .model
-            .clone()
-            .unwrap_or(self.default_model().to_string());
+        let model = self.model.clone().unwrap_or_default();
         let prefix = match model.as_ref() {
             MediaModel::OPENJOURNEY => "mdjrny-v4 style",
             MediaModel::DREAMLIKE_DIFFUSION_1 => "dreamlikeart",
@@ -84,17 +108,17 @@ impl GenerateMediaDto {
         }
     }
 
-    pub fn sanitized(&self) -> Self {
+    pub fn sanitized(&self, provider: &dyn MediaGeneratorProvider) -> Self {
         return Self {
             prompt: self.prompt.trim().replace("\n", " ").replace("\r", " "),
             number: self.number,
             width: self.width,
             height: self.height,
-            generator: self.generator.to_string(),
+            generator: provider.generator().to_string(),
             model: Some(
                 self.model
                     .clone()
-                    .unwrap_or(self.default_model().to_string()),
+                    .unwrap_or(provider.default_model().to_string()),
             ),
             negative_prompt: match &self.negative_prompt {
                 Some(negative_prompt) => {
@@ -104,73 +128,100 @@ impl GenerateMediaDto {
             },
             cfg_scale: self.cfg_scale,
             input_media_id: self.input_media_id.clone(),
+            mode: Some(self.mode()),
+            mask_media_id: self.mask_media_id.clone(),
+            strength: self
+                .input_media_id
+                .as_ref()
+                .map(|_| self.strength.unwrap_or(Self::default_strength())),
+            steps: Some(self.steps()),
             publish: self.publish,
         };
     }
 
-    pub fn is_valid(&self) -> Result<(), ApiError> {
+    pub fn is_valid(
+        &self,
+        provider: &dyn MediaGeneratorProvider,
+        ink_pricing: &InkPricing,
+        is_subscriber: bool,
+    ) -> Result<(), ApiError> {
         let model = self
             .model
             .clone()
-            .unwrap_or(self.default_model().to_string());
-
-        let is_valid_generator: bool;
-        let is_valid_model: bool;
-        let is_valid_size: bool;
-        let is_valid_number: bool;
-
-        match self.generator.as_ref() {
-            MediaGenerator::MIST => {
-                is_valid_generator = true;
-                is_valid_model = mist::service::is_valid_model(&model);
-                is_valid_size = mist::service::is_valid_size(&self.width, &self.height, &model);
-                is_valid_number = mist::service::is_valid_number(self.number, &model);
-            }
-            MediaGenerator::STABLE_HORDE => {
-                is_valid_generator = true;
-                is_valid_model = stable_horde::service::is_valid_model(&model);
-                is_valid_size = stable_horde::service::is_valid_size(&self.width, &self.height);
-                is_valid_number = stable_horde::service::is_valid_number(self.number);
-            }
-            MediaGenerator::DALLE => {
-                is_valid_generator = true;
-                is_valid_model = dalle::service::is_valid_model(&model);
-                is_valid_size = dalle::service::is_valid_size(&self.width, &self.height);
-                is_valid_number = dalle::service::is_valid_number(self.number);
-            }
-            _ => {
-                is_valid_generator = false;
-                is_valid_model = false;
-                is_valid_size = false;
-                is_valid_number = false;
-            }
-        }
+            .unwrap_or(provider.default_model().to_string());
 
-        if !is_valid_generator {
+        if !provider.is_valid_model(&model) {
             return Err(ApiError {
                 code: StatusCode::BAD_REQUEST,
-                message: "This generator is not supported.".to_string(),
+                message: "This generator does not support this model.".to_string(),
             });
         }
-        if !is_valid_model {
+        if !is_subscriber && ink_pricing.requires_subscription(provider.generator(), &model) {
             return Err(ApiError {
-                code: StatusCode::BAD_REQUEST,
-                message: "This generator does not support this model.".to_string(),
+                code: StatusCode::FORBIDDEN,
+                message: "This model requires a subscription.".to_string(),
             });
         }
-        if !is_valid_size {
+        if !provider.is_valid_size(&self.width, &self.height, &model) {
             return Err(ApiError {
                 code: StatusCode::BAD_REQUEST,
                 message: "This generator does not support this size.".to_string(),
             });
         }
-        if !is_valid_number {
+        if !provider.is_valid_number(self.number, &model) {
             return Err(ApiError {
                 code: StatusCode::BAD_REQUEST,
                 message: "This generator does not support this number.".to_string(),
             });
         }
 
+        let mode = self.mode();
+
+        if !provider.is_valid_mode(&mode, &model) {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "This generator does not support this mode.".to_string(),
+            });
+        }
+
+        let (requires_input_media, requires_mask_media) = match mode.as_str() {
+            MediaGenerateMode::TXT2IMG => (false, false),
+            MediaGenerateMode::IMG2IMG => (true, false),
+            MediaGenerateMode::INPAINT => (true, true),
+            MediaGenerateMode::OUTPAINT => (true, false),
+            _ => {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: "mode must be txt2img, img2img, inpaint or outpaint.".to_string(),
+                })
+            }
+        };
+
+        if requires_input_media != self.input_media_id.is_some() {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: match requires_input_media {
+                    true => format!("{} requires input_media_id.", mode),
+                    false => format!("{} does not take input_media_id.", mode),
+                },
+            });
+        }
+        if requires_mask_media != self.mask_media_id.is_some() {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: match requires_mask_media {
+                    true => format!("{} requires mask_media_id.", mode),
+                    false => format!("{} does not take mask_media_id.", mode),
+                },
+            });
+        }
+        if self.strength.is_some() && self.input_media_id.is_none() {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "strength requires input_media_id.".to_string(),
+            });
+        }
+
         Ok(())
     }
 }
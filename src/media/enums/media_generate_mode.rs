@@ -0,0 +1,13 @@
+#[non_exhaustive]
+pub struct MediaGenerateMode;
+
+impl MediaGenerateMode {
+    pub const TXT2IMG: &'static str = "txt2img";
+    pub const IMG2IMG: &'static str = "img2img";
+    pub const INPAINT: &'static str = "inpaint";
+    pub const OUTPAINT: &'static str = "outpaint";
+
+    pub fn all() -> [&'static str; 4] {
+        return [Self::TXT2IMG, Self::IMG2IMG, Self::INPAINT, Self::OUTPAINT];
+    }
+}
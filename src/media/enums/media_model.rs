@@ -10,4 +10,5 @@ impl MediaModel {
     pub const DREAMLIKE_DIFFUSION_1: &'static str = "dreamlike_diffusion_1";
     pub const ARCANE_DIFFUSION: &'static str = "arcane_diffusion";
     pub const DALLE: &'static str = "dalle";
+    pub const REAL_ESRGAN_X4: &'static str = "real_esrgan_x4";
 }
@@ -1,3 +1,4 @@
+pub mod media_generate_mode;
 pub mod media_generator;
 pub mod media_model;
 pub mod media_source;
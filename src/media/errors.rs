@@ -5,6 +5,8 @@ use crate::app::models::api_error::ApiError;
 #[derive(Debug)]
 pub enum MediaApiError {
     MediaNotFound,
+    GeneratorNotSupported,
+    GeneratorUnavailable,
 }
 
 impl MediaApiError {
@@ -14,6 +16,14 @@ impl MediaApiError {
                 code: StatusCode::NOT_FOUND,
                 message: "Media not found.".to_string(),
             },
+            Self::GeneratorNotSupported => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "This generator is not supported.".to_string(),
+            },
+            Self::GeneratorUnavailable => ApiError {
+                code: StatusCode::SERVICE_UNAVAILABLE,
+                message: "This generator is temporarily unavailable, try again later.".to_string(),
+            },
         }
     }
 }
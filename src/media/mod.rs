@@ -1,8 +1,10 @@
 pub mod apis;
+pub mod config;
 pub mod controller;
 pub mod dtos;
 pub mod enums;
 pub mod errors;
 pub mod models;
+pub mod providers;
 pub mod service;
 pub mod util;
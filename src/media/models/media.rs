@@ -19,6 +19,8 @@ pub struct Media {
     pub id: String,
     pub user_id: String,
     pub file_id: String,
+    #[serde(skip_serializing)]
+    pub file_size: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_id: Option<String>,
     pub url: String,
@@ -32,6 +34,11 @@ pub struct Media {
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_media_id: Option<String>,
+    // ink charged for this media, refunded from when it is deleted
+    #[serde(skip_serializing)]
+    pub ink_cost: Option<i64>,
     pub created_at: i64,
 }
 
@@ -55,6 +62,7 @@ impl Media {
             id: id.to_string(),
             user_id: request.user_id.to_string(),
             file_id: b2_upload_responses.file_id.to_string(),
+            file_size: b2_upload_responses.content_length as i64,
             post_id: Some(request.id.to_string()),
             url: download_url,
             width: dto.width as i16,
@@ -66,7 +74,9 @@ impl Media {
                 None => None,
             },
             source: dto.generator.to_string(),
-            model: Some(dto.model.clone().unwrap_or(dto.default_model().to_string())),
+            model: dto.model.clone(),
+            input_media_id: dto.input_media_id.clone(),
+            ink_cost: request.quoted_ink_cost(Some(1)),
             created_at: time::current_time_in_secs() as i64,
         };
     }
@@ -89,6 +99,7 @@ impl Media {
             id: id.to_string(),
             user_id: claims.id.to_string(),
             file_id: b2_upload_responses.file_id.to_string(),
+            file_size: b2_upload_responses.content_length as i64,
             post_id: None,
             url: download_url,
             width: image_size.width.to_owned() as i16,
@@ -98,6 +109,8 @@ impl Media {
             seed: None,
             source: MediaSource::Import.value(),
             model: None,
+            input_media_id: None,
+            ink_cost: None,
             created_at: time::current_time_in_secs() as i64,
         };
     }
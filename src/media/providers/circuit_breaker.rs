@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use crate::{
+    app::util::time,
+    generate_media_requests::config::LEASE_DURATION,
+    media::config::{
+        CIRCUIT_ERROR_RATE, CIRCUIT_MIN_SAMPLES, CIRCUIT_OPEN_DURATION, CIRCUIT_WINDOW_SIZE,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct ProviderHealth {
+    pub generator: String,
+    pub state: String,
+    pub error_rate: f64,
+    pub average_latency: u64,
+    pub samples: usize,
+}
+
+struct Outcome {
+    success: bool,
+    latency: u64,
+}
+
+pub struct CircuitBreaker {
+    outcomes: VecDeque<Outcome>,
+    opened_at: Option<u64>,
+    trial_started_at: Option<u64>,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        return Self {
+            outcomes: VecDeque::with_capacity(CIRCUIT_WINDOW_SIZE),
+            opened_at: None,
+            trial_started_at: None,
+        };
+    }
+
+    pub fn record(&mut self, generator: &str, success: bool, latency: u64) {
+        if self.outcomes.len() == CIRCUIT_WINDOW_SIZE {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(Outcome { success, latency });
+
+        let current_time = time::current_time_in_secs();
+
+        if self.opened_at.is_some() {
+            // only the trial decides, not requests dispatched before the circuit opened
+            if self.trial_started_at.take().is_none() {
+                return;
+            }
+
+            if success {
+                tracing::info!("circuit closed for {}", generator);
+                self.opened_at = None;
+                self.outcomes.clear();
+            } else {
+                tracing::warn!("circuit reopened for {}", generator);
+                self.opened_at = Some(current_time);
+            }
+            return;
+        }
+
+        if self.outcomes.len() >= CIRCUIT_MIN_SAMPLES && self.error_rate() >= CIRCUIT_ERROR_RATE {
+            tracing::warn!(
+                "circuit opened for {} (error rate {:.2})",
+                generator,
+                self.error_rate()
+            );
+            self.opened_at = Some(current_time);
+        }
+    }
+
+    // once the open duration elapses the circuit is half open and lets a single trial through
+    pub fn is_available(&self) -> bool {
+        return match self.opened_at {
+            Some(_) => self.is_half_open() && !self.is_trial_in_flight(),
+            None => true,
+        };
+    }
+
+    pub fn is_closed(&self) -> bool {
+        return self.opened_at.is_none();
+    }
+
+    // called when the trial request is dispatched, only one caller gets it
+    pub fn begin_trial(&mut self) -> bool {
+        if !self.is_half_open() || self.is_trial_in_flight() {
+            return false;
+        }
+
+        self.trial_started_at = Some(time::current_time_in_secs());
+
+        return true;
+    }
+
+    // the trial was taken but nothing was dispatched
+    pub fn cancel_trial(&mut self) {
+        self.trial_started_at = None;
+    }
+
+    pub fn state(&self) -> &str {
+        return match self.opened_at {
+            Some(_) if self.is_half_open() => "half_open",
+            Some(_) => "open",
+            None => "closed",
+        };
+    }
+
+    fn is_half_open(&self) -> bool {
+        return self.opened_at.is_some_and(|opened_at| {
+            time::current_time_in_secs() >= opened_at + CIRCUIT_OPEN_DURATION
+        });
+    }
+
+    // a trial that never reported back, e.g. its task died, is given up once its lease expires
+    fn is_trial_in_flight(&self) -> bool {
+        return self.trial_started_at.is_some_and(|trial_started_at| {
+            time::current_time_in_secs() < trial_started_at + LEASE_DURATION as u64
+        });
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+
+        let errors = self.outcomes.iter().filter(|o| !o.success).count();
+
+        return errors as f64 / self.outcomes.len() as f64;
+    }
+
+    pub fn average_latency(&self) -> u64 {
+        if self.outcomes.is_empty() {
+            return 0;
+        }
+
+        let total: u64 = self.outcomes.iter().map(|o| o.latency).sum();
+
+        return total / self.outcomes.len() as u64;
+    }
+
+    pub fn health(&self, generator: &str) -> ProviderHealth {
+        return ProviderHealth {
+            generator: generator.to_string(),
+            state: self.state().to_string(),
+            error_rate: self.error_rate(),
+            average_latency: self.average_latency(),
+            samples: self.outcomes.len(),
+        };
+    }
+}
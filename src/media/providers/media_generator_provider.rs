@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{
+    generate_media_requests::models::generate_media_request::GenerateMediaRequest, AppState,
+};
+
+pub trait MediaGeneratorProvider: Send + Sync {
+    fn generator(&self) -> &'static str;
+
+    fn default_model(&self) -> &'static str;
+
+    fn is_valid_model(&self, model: &str) -> bool;
+
+    fn is_valid_size(&self, width: &u16, height: &u16, model: &str) -> bool;
+
+    fn is_valid_number(&self, number: u8, model: &str) -> bool;
+
+    fn is_valid_mode(&self, mode: &str, model: &str) -> bool;
+
+    // generator serving equivalent models, used while this one is unavailable
+    fn fallback_generator(&self) -> Option<&'static str> {
+        return None;
+    }
+
+    // ink charged for a single 512x512 image when no ink price matches
+    fn base_ink(&self) -> f64;
+
+    // whether deleting generated media gives the ink back
+    fn refunds_on_delete(&self) -> bool {
+        return false;
+    }
+
+    fn spawn_generate_media_task(&self, request: GenerateMediaRequest, state: Arc<AppState>);
+
+    // aborts the provider call for a canceled request, if the provider supports it
+    fn cancel_generate_media_task(&self, _request: GenerateMediaRequest, _state: Arc<AppState>) {}
+}
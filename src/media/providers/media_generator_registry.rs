@@ -0,0 +1,123 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    media::dtos::generate_media_dto::GenerateMediaDto, pricing::structs::ink_pricing::InkPricing,
+};
+
+use super::{
+    circuit_breaker::{CircuitBreaker, ProviderHealth},
+    media_generator_provider::MediaGeneratorProvider,
+};
+
+pub struct MediaGeneratorRegistry {
+    providers: HashMap<&'static str, Box<dyn MediaGeneratorProvider>>,
+    circuit_breakers: HashMap<&'static str, Mutex<CircuitBreaker>>,
+}
+
+impl MediaGeneratorRegistry {
+    pub fn new() -> Self {
+        return Self {
+            providers: HashMap::new(),
+            circuit_breakers: HashMap::new(),
+        };
+    }
+
+    pub fn register(&mut self, provider: Box<dyn MediaGeneratorProvider>) {
+        self.circuit_breakers
+            .insert(provider.generator(), Mutex::new(CircuitBreaker::new()));
+        self.providers.insert(provider.generator(), provider);
+    }
+
+    pub fn get(&self, generator: &str) -> Option<&dyn MediaGeneratorProvider> {
+        return self
+            .providers
+            .get(generator)
+            .map(|provider| provider.as_ref());
+    }
+
+    pub fn generators(&self) -> Vec<&'static str> {
+        return self.providers.keys().copied().collect();
+    }
+
+    pub fn available_generators(&self) -> Vec<&'static str> {
+        return self
+            .providers
+            .keys()
+            .copied()
+            .filter(|generator| self.is_available(generator))
+            .collect();
+    }
+
+    pub fn is_available(&self, generator: &str) -> bool {
+        return match self.circuit_breakers.get(generator) {
+            Some(circuit_breaker) => circuit_breaker.lock().unwrap().is_available(),
+            None => false,
+        };
+    }
+
+    pub fn is_closed(&self, generator: &str) -> bool {
+        return match self.circuit_breakers.get(generator) {
+            Some(circuit_breaker) => circuit_breaker.lock().unwrap().is_closed(),
+            None => false,
+        };
+    }
+
+    pub fn begin_trial(&self, generator: &str) -> bool {
+        return match self.circuit_breakers.get(generator) {
+            Some(circuit_breaker) => circuit_breaker.lock().unwrap().begin_trial(),
+            None => false,
+        };
+    }
+
+    pub fn cancel_trial(&self, generator: &str) {
+        if let Some(circuit_breaker) = self.circuit_breakers.get(generator) {
+            circuit_breaker.lock().unwrap().cancel_trial();
+        }
+    }
+
+    pub fn record_outcome(&self, generator: &str, success: bool, latency: u64) {
+        if let Some(circuit_breaker) = self.circuit_breakers.get(generator) {
+            circuit_breaker
+                .lock()
+                .unwrap()
+                .record(generator, success, latency);
+        }
+    }
+
+    // the requested provider if it is healthy, otherwise its fallback if
+    // that one is healthy and can serve the same request
+    pub fn get_available(
+        &self,
+        dto: &GenerateMediaDto,
+        ink_pricing: &InkPricing,
+        is_subscriber: bool,
+    ) -> Option<&dyn MediaGeneratorProvider> {
+        let provider = self.get(&dto.generator)?;
+
+        if self.is_available(provider.generator()) {
+            return Some(provider);
+        }
+
+        let fallback = self.get(provider.fallback_generator()?)?;
+
+        if !self.is_available(fallback.generator())
+            || dto.is_valid(fallback, ink_pricing, is_subscriber).is_err()
+        {
+            return None;
+        }
+
+        return Some(fallback);
+    }
+
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        let mut health: Vec<ProviderHealth> = self
+            .circuit_breakers
+            .iter()
+            .map(|(generator, circuit_breaker)| circuit_breaker.lock().unwrap().health(generator))
+            .collect();
+
+        health.sort_by(|a, b| a.generator.cmp(&b.generator));
+
+        return health;
+    }
+}
@@ -0,0 +1,3 @@
+pub mod circuit_breaker;
+pub mod media_generator_provider;
+pub mod media_generator_registry;
@@ -1,36 +1,55 @@
 use std::sync::Arc;
 
 use axum::{extract::Multipart, http::StatusCode};
-use sqlx::{PgPool, Postgres};
+use sqlx::{postgres::PgExecutor, PgPool, Postgres};
+use tokio::task;
 use tokio_retry::{strategy::FixedInterval, Retry};
 
 use crate::{
+    api_keys,
     app::{
+        self,
         enums::api_status::ApiStatus,
         errors::DefaultApiError,
         models::api_error::ApiError,
-        util::multipart::{
-            models::file_properties::FileProperties, multipart::get_files_properties,
+        util::{
+            multipart::{models::file_properties::FileProperties, multipart::get_files_properties},
+            time,
         },
     },
-    auth::jwt::models::claims::Claims,
+    auth::{
+        enums::email_verification_policy::EmailVerificationPolicy, errors::AuthApiError,
+        jwt::models::claims::Claims,
+    },
     devices,
     generate_media_requests::{
-        self, enums::generate_media_request_status::GenerateMediaRequestStatus,
+        self,
+        config::LEASE_DURATION,
+        enums::{
+            generate_media_request_event_type::GenerateMediaRequestEventType,
+            generate_media_request_status::GenerateMediaRequestStatus,
+        },
         models::generate_media_request::GenerateMediaRequest,
     },
     posts::{self, models::post::Post},
-    users::{self, util::ink::dtos::edit_user_ink_dto::EditUserInkDto},
+    subscriptions,
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
     AppState,
 };
 
 use super::{
-    apis::{dalle, modal, stable_horde},
+    config::{IMPORT_STORAGE_QUOTA, MAX_IMPORT_FILES},
     dtos::{generate_media_dto::GenerateMediaDto, get_media_filter_dto::GetMediaFilterDto},
-    enums::media_generator::MediaGenerator,
+    enums::{media_generate_mode::MediaGenerateMode, media_source::MediaSource},
     errors::MediaApiError,
     models::media::Media,
-    util::{backblaze, openai},
+    providers::media_generator_provider::MediaGeneratorProvider,
+    util::{backblaze, import::sanitizer, openai},
 };
 
 pub async fn generate_media(
@@ -38,10 +57,39 @@ pub async fn generate_media(
     claims: &Claims,
     state: &Arc<AppState>,
 ) -> Result<GenerateMediaRequest, ApiError> {
-    if let Err(e) = dto.is_valid() {
+    let Some(provider) = state.media_generators.get(&dto.generator) else {
+        return Err(MediaApiError::GeneratorNotSupported.value());
+    };
+
+    let entitlement_result =
+        subscriptions::service::get_entitlement_by_user_id(&claims.id, &state.pool).await;
+    let Ok(entitlement) = entitlement_result else {
+        return Err(entitlement_result.unwrap_err());
+    };
+    let is_subscriber = entitlement.is_some();
+
+    if let Err(e) = dto.is_valid(provider, &state.ink_pricing, is_subscriber) {
         return Err(e);
     }
 
+    let Some(provider) =
+        state
+            .media_generators
+            .get_available(dto, &state.ink_pricing, is_subscriber)
+    else {
+        return Err(MediaApiError::GeneratorUnavailable.value());
+    };
+
+    if provider.generator() != dto.generator {
+        tracing::info!(
+            "generate_media rerouted from {} to {}",
+            dto.generator,
+            provider.generator()
+        );
+    }
+
+    let dto = &dto.sanitized(provider);
+
     let api_status = state.api_state.api_status.read().await;
     if *api_status == ApiStatus::Maintenance.value() {
         return Err(ApiError {
@@ -50,28 +98,17 @@ pub async fn generate_media(
         });
     }
 
-    // let get_input_media_if_any_result = get_input_media_if_any(dto, claims, state).await;
-    // let Ok(input_media) = get_input_media_if_any_result
-    // else {
-    //     return Err(get_input_media_if_any_result.unwrap_err());
-    // };
+    if let Err(e) = get_input_media_if_any(dto, claims, state).await {
+        return Err(e);
+    }
 
-    let get_generate_media_request_result = get_generate_media_request(dto, claims, state).await;
+    let get_generate_media_request_result =
+        get_generate_media_request(dto, provider, claims, state).await;
     let Ok(generate_media_request) = get_generate_media_request_result else {
         return Err(get_generate_media_request_result.unwrap_err());
     };
 
-    let req = generate_media_request.clone();
-    let state = state.clone();
-
-    match dto.generator.as_ref() {
-        MediaGenerator::MIST => modal::service::spawn_generate_media_task(req, state),
-        MediaGenerator::STABLE_HORDE => {
-            stable_horde::service::spawn_generate_media_task(req, state)
-        }
-        MediaGenerator::DALLE => dalle::service::spawn_generate_media_task(req, state),
-        _ => return Err(DefaultApiError::InternalServerError.value()),
-    }
+    state.generate_media_queue.notify_one();
 
     Ok(generate_media_request)
 }
@@ -85,23 +122,73 @@ async fn get_input_media_if_any(
         return Ok(None);
     };
 
-    match get_media_by_id(id, claims, &state.pool).await {
-        Ok(media) => {
-            if media.width as u16 != dto.width || media.height as u16 != dto.height {
-                return Err(ApiError {
-                    code: StatusCode::BAD_REQUEST,
-                    message: "Input image must have the same dimensions as request.".to_string(),
-                });
-            } else {
-                Ok(Some(media))
-            }
-        }
+    let media = match get_media_by_id(id, claims, &state.pool).await {
+        Ok(media) => media,
         Err(e) => return Err(e),
+    };
+
+    let width = media.width as u16;
+    let height = media.height as u16;
+
+    if dto.mode() == MediaGenerateMode::OUTPAINT {
+        // outpainting extends the input image out to the requested size
+        if width > dto.width || height > dto.height || (width == dto.width && height == dto.height)
+        {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Input image must be smaller than request to outpaint.".to_string(),
+            });
+        }
+    } else if width != dto.width || height != dto.height {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Input image must have the same dimensions as request.".to_string(),
+        });
+    }
+
+    if let Some(mask_id) = &dto.mask_media_id {
+        let mask = match get_media_by_id(mask_id, claims, &state.pool).await {
+            Ok(mask) => mask,
+            Err(e) => return Err(e),
+        };
+
+        if mask.width != media.width || mask.height != media.height {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Mask image must have the same dimensions as input image.".to_string(),
+            });
+        }
+    }
+
+    Ok(Some(media))
+}
+
+// loads an input or mask image for providers that take it inline
+pub async fn get_image_base64_if_any(
+    media_id: &Option<String>,
+    pool: &PgPool,
+) -> Result<Option<String>, ApiError> {
+    let Some(id) = media_id else {
+        return Ok(None);
+    };
+
+    let get_media_by_id_result = get_media_by_id_as_anonymous(id, pool).await;
+    let Ok(media) = get_media_by_id_result else {
+        return Err(get_media_by_id_result.unwrap_err());
+    };
+
+    match app::util::reqwest::get_bytes(&media.url).await {
+        Ok(bytes) => Ok(Some(base64::encode(bytes))),
+        Err(e) => {
+            tracing::error!("get_image_base64_if_any failed get_bytes");
+            Err(e)
+        }
     }
 }
 
 async fn get_generate_media_request(
     dto: &GenerateMediaDto,
+    provider: &dyn MediaGeneratorProvider,
     claims: &Claims,
     state: &Arc<AppState>,
 ) -> Result<GenerateMediaRequest, ApiError> {
@@ -110,7 +197,14 @@ async fn get_generate_media_request(
         Err(e) => return Err(e),
     };
 
-    let ink_cost = users::util::ink::ink::calculate_ink_cost(&dto, None);
+    if user.email_verified_at.is_none()
+        && EmailVerificationPolicy::from_envy(&state.envy)
+            == EmailVerificationPolicy::BLOCK_GENERATION
+    {
+        return Err(AuthApiError::EmailNotVerified.value());
+    }
+
+    let ink_cost = state.ink_pricing.calculate_ink_cost(provider, dto, None);
 
     if (user.ink - user.ink_pending) < ink_cost {
         return Err(ApiError {
@@ -148,25 +242,41 @@ async fn get_generate_media_request(
         });
     };
 
-    let edit_user_ink_dto = EditUserInkDto {
-        ink_increase: None,
-        ink_decrease: None,
-        ink_sum_increase: None,
-        ink_sum_decrease: None,
-        ink_pending_increase: Some(ink_cost),
-        ink_pending_decrease: None,
-    };
+    // checked again with the user locked, the check above only spares moderation
+    if let Err(e) =
+        users::util::ink::ink::check_ink_available_as_tx(&claims.id, ink_cost, &mut tx).await
+    {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
 
-    let edit_user_ink_by_id_result =
-        users::util::ink::ink::edit_user_ink_by_id(&claims.id, &edit_user_ink_dto, &mut tx).await;
+    if let Some(api_key_id) = &claims.api_key_id {
+        let check_ink_spending_cap_result =
+            api_keys::service::check_ink_spending_cap_as_tx(api_key_id, ink_cost, &mut tx).await;
 
-    if edit_user_ink_by_id_result.is_err() {
+        if let Err(e) = check_ink_spending_cap_result {
+            let rollback_result = tx.rollback().await;
+
+            if let Some(e) = rollback_result.err() {
+                tracing::error!(%e);
+            } else {
+                tracing::warn!("rolled back check_ink_spending_cap_result");
+            }
+
+            return Err(e);
+        }
+    }
+
+    let create_request_result =
+        generate_media_requests::service::create_request(dto, ink_cost, claims, &mut tx).await;
+
+    if create_request_result.is_err() {
         let rollback_result = tx.rollback().await;
 
         if let Some(e) = rollback_result.err() {
             tracing::error!(%e);
         } else {
-            tracing::warn!("rolled back edit_user_ink_by_id_result");
+            tracing::warn!("rolled back create_reqest_result");
         }
 
         return Err(ApiError {
@@ -175,16 +285,31 @@ async fn get_generate_media_request(
         });
     }
 
-    let create_request_result =
-        generate_media_requests::service::create_request(dto, claims, &mut tx).await;
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: None,
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: Some(ink_cost),
+        ink_pending_decrease: None,
+    };
 
-    if create_request_result.is_err() {
+    let edit_user_ink_by_id_result = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &edit_user_ink_dto,
+        InkLedgerReason::GENERATION_HOLD,
+        Some(&create_request_result.as_ref().unwrap().id),
+        &mut tx,
+    )
+    .await;
+
+    if edit_user_ink_by_id_result.is_err() {
         let rollback_result = tx.rollback().await;
 
         if let Some(e) = rollback_result.err() {
             tracing::error!(%e);
         } else {
-            tracing::warn!("rolled back create_reqest_result");
+            tracing::warn!("rolled back edit_user_ink_by_id_result");
         }
 
         return Err(ApiError {
@@ -211,6 +336,20 @@ pub async fn on_generate_media_completion_with_retry(
     media: &Option<Vec<Media>>,
     state: &Arc<AppState>,
 ) -> Result<(), ApiError> {
+    // latency is measured from when a worker claimed the request
+    let latency = match generate_media_request.locked_until {
+        Some(locked_until) => {
+            (time::current_time_in_secs() as i64 - (locked_until - LEASE_DURATION)).max(0) as u64
+        }
+        None => 0,
+    };
+
+    state.media_generators.record_outcome(
+        &generate_media_request.generate_media_dto.generator,
+        matches!(status, GenerateMediaRequestStatus::Completed),
+        latency,
+    );
+
     let retry_strategy = FixedInterval::from_millis(10000).take(6);
 
     Retry::spawn(retry_strategy, || async {
@@ -226,6 +365,53 @@ async fn on_generate_media_completion(
     state: &Arc<AppState>,
 ) -> Result<(), ApiError> {
     let uid = &generate_media_request.user_id;
+    let dto = &generate_media_request.generate_media_dto;
+
+    let Some(provider) = state.media_generators.get(&dto.generator) else {
+        tracing::error!(
+            "on_generate_media_completion failed: generator {} is not registered",
+            dto.generator
+        );
+        return Err(MediaApiError::GeneratorNotSupported.value());
+    };
+
+    if matches!(status, GenerateMediaRequestStatus::Error)
+        && generate_media_request.has_attempts_left()
+    {
+        let run_at = generate_media_request.next_run_at();
+
+        return match generate_media_requests::service::requeue_generate_media_request_by_id_as_admin(
+            &generate_media_request.id,
+            run_at,
+            &state.pool,
+        )
+        .await
+        {
+            Ok(_) => {
+                tracing::info!(
+                    "requeued request {} (attempt {})",
+                    generate_media_request.id,
+                    generate_media_request.attempts
+                );
+                generate_media_requests::events::publish(
+                    &generate_media_request.id,
+                    &GenerateMediaRequestEventType::Queued,
+                    None,
+                    state,
+                )
+                .await;
+                Ok(())
+            }
+            Err(e) if e.code == StatusCode::CONFLICT => {
+                tracing::warn!(
+                    "on_generate_media_completion skipped requeue, request {} is not processing",
+                    generate_media_request.id
+                );
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+    }
 
     let Ok(mut tx) = state.pool.begin().await else {
         tracing::warn!("on_generate_media_completion failed to begin pool transaction");
@@ -243,9 +429,18 @@ async fn on_generate_media_completion(
         )
         .await;
 
-    if edit_generate_media_request_by_id_as_tx_result.is_err() {
+    if let Err(e) = edit_generate_media_request_by_id_as_tx_result {
         let rollback_result = tx.rollback().await;
 
+        if e.code == StatusCode::CONFLICT {
+            // request was already completed, possibly by an earlier attempt
+            tracing::warn!(
+                "on_generate_media_completion skipped request {}, it is not processing",
+                generate_media_request.id
+            );
+            return Ok(());
+        }
+
         if let Some(e) = rollback_result.err() {
             tracing::error!("on_generate_media_completion failed to rollback edit_generate_media_request_by_id_as_tx_result: {:?}", e);
         } else {
@@ -263,13 +458,18 @@ async fn on_generate_media_completion(
         None => 0,
     };
 
-    let ink_cost_original =
-        users::util::ink::ink::calculate_ink_cost(&generate_media_request.generate_media_dto, None);
-
-    let ink_cost_actual = users::util::ink::ink::calculate_ink_cost(
-        &generate_media_request.generate_media_dto,
-        Some(media_generated),
-    );
+    // charged at the price quoted when the request was made
+    let ink_cost_original = generate_media_request
+        .quoted_ink_cost(None)
+        .unwrap_or_else(|| state.ink_pricing.calculate_ink_cost(provider, dto, None));
+
+    let ink_cost_actual = generate_media_request
+        .quoted_ink_cost(Some(media_generated))
+        .unwrap_or_else(|| {
+            state
+                .ink_pricing
+                .calculate_ink_cost(provider, dto, Some(media_generated))
+        });
 
     let edit_user_ink_dto = EditUserInkDto {
         ink_increase: None,
@@ -283,8 +483,14 @@ async fn on_generate_media_completion(
         ink_pending_decrease: Some(ink_cost_original),
     };
 
-    let edit_user_ink_by_id_result =
-        users::util::ink::ink::edit_user_ink_by_id(uid, &edit_user_ink_dto, &mut tx).await;
+    let edit_user_ink_by_id_result = users::util::ink::ink::edit_user_ink_by_id(
+        uid,
+        &edit_user_ink_dto,
+        InkLedgerReason::GENERATION_CHARGE,
+        Some(&generate_media_request.id),
+        &mut tx,
+    )
+    .await;
 
     if edit_user_ink_by_id_result.is_err() {
         let rollback_result = tx.rollback().await;
@@ -312,6 +518,21 @@ async fn on_generate_media_completion(
         });
     }
 
+    let media_ids: Option<Vec<String>> = media
+        .as_ref()
+        .map(|media| media.iter().map(|m| m.id.to_string()).collect());
+
+    // only announced once committed, so listeners never fetch media that isn't there yet
+    if let Some(event_type) = GenerateMediaRequestEventType::from_status(status.value()) {
+        generate_media_requests::events::publish(
+            &generate_media_request.id,
+            &event_type,
+            media_ids,
+            state,
+        )
+        .await;
+    }
+
     if let Some(media) = media {
         if let Some(post) = Post::from_media(media.clone()) {
             devices::service::send_notifications_to_devices_with_user_id(
@@ -343,14 +564,80 @@ pub async fn import_media(
         });
     }
 
+    if files_properties.len() > MAX_IMPORT_FILES {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: format!("You can import up to {} files at once.", MAX_IMPORT_FILES),
+        });
+    }
+
+    // decoding and encoding is cpu bound, so keep it off the async workers
+    let sanitize_result = task::spawn_blocking(move || {
+        files_properties
+            .iter()
+            .map(sanitizer::sanitize)
+            .collect::<Result<Vec<FileProperties>, ApiError>>()
+    })
+    .await;
+
+    let files_properties = match sanitize_result {
+        Ok(Ok(files_properties)) => files_properties,
+        Ok(Err(e)) => return Err(e),
+        Err(e) => {
+            tracing::error!("import_media failed to sanitize files: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let import_size: i64 = files_properties
+        .iter()
+        .map(|file_properties| file_properties.data.len() as i64)
+        .sum();
+
+    let get_import_storage_used_result =
+        get_import_storage_used_by_user_id(&claims.id, &state.pool).await;
+    let Ok(import_storage_used) = get_import_storage_used_result else {
+        return Err(get_import_storage_used_result.unwrap_err());
+    };
+
+    if import_storage_used + import_size > IMPORT_STORAGE_QUOTA {
+        return Err(ApiError {
+            code: StatusCode::FORBIDDEN,
+            message: "Not enough storage left to import these files.".to_string(),
+        });
+    }
+
+    let mut moderation_futures = Vec::with_capacity(files_properties.len());
+
     for file_properties in &files_properties {
-        if !file_properties
-            .mime_type
-            .starts_with(&mime::IMAGE.to_string())
+        moderation_futures.push(openai::moderation::check_image(
+            &file_properties.data,
+            &file_properties.mime_type,
+            &state.envy.openai_api_key,
+            &state.client,
+        ));
+    }
+
+    // unlike prompts, imported images are not filtered any further
+    // so they are rejected when moderation is unavailable
+    for moderation_result in futures::future::join_all(moderation_futures).await {
+        let Ok(openai_moderation_response) = moderation_result else {
+            return Err(ApiError {
+                code: StatusCode::SERVICE_UNAVAILABLE,
+                message: "Failed to check images, try again later.".to_string(),
+            });
+        };
+
+        if openai_moderation_response
+            .results
+            .iter()
+            .any(|result| result.flagged)
         {
+            tracing::info!("import was flagged for user {}", claims.id);
+
             return Err(ApiError {
                 code: StatusCode::BAD_REQUEST,
-                message: "Files must be images.".to_string(),
+                message: "Your image was flagged for inappropriate content.".to_string(),
             });
         }
     }
@@ -381,15 +668,113 @@ pub async fn import_media(
         });
     }
 
-    match upload_media_with_retry(&media, &state.pool).await {
+    match create_imported_media(&media, &claims.id, &state.pool).await {
         Ok(m) => Ok(m),
         Err(e) => {
-            tracing::error!("import_media failed upload_media_with_retry: {:?}", e);
+            tracing::error!("import_media failed create_imported_media: {:?}", e);
+
+            for m in &media {
+                let file_name = ["media/", &claims.id, "/", &m.id].concat();
+                if let Err(e) = backblaze::service::delete_file(
+                    &file_name,
+                    &m.file_id,
+                    &state.b2,
+                    &state.client,
+                )
+                .await
+                {
+                    tracing::error!("import_media failed to delete file {}: {:?}", m.id, e);
+                }
+            }
+
             Err(e)
         }
     }
 }
 
+// the quota checked up front only spares uploads that can't fit,
+// concurrent imports are settled here with the user row locked
+async fn create_imported_media(
+    media: &[Media],
+    user_id: &str,
+    pool: &PgPool,
+) -> Result<Vec<Media>, ApiError> {
+    let import_size: i64 = media.iter().map(|m| m.file_size).sum();
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut tx)
+        .await;
+
+    match sqlx_result {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::PermissionDenied.value());
+        }
+        Err(e) => {
+            tracing::error!("create_imported_media: {:?}", e);
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+
+    let get_import_storage_used_result = get_import_storage_used_by_user_id(user_id, &mut tx).await;
+    let Ok(import_storage_used) = get_import_storage_used_result else {
+        let _ = tx.rollback().await;
+        return Err(get_import_storage_used_result.unwrap_err());
+    };
+
+    if import_storage_used + import_size > IMPORT_STORAGE_QUOTA {
+        let _ = tx.rollback().await;
+        return Err(ApiError {
+            code: StatusCode::FORBIDDEN,
+            message: "Not enough storage left to import these files.".to_string(),
+        });
+    }
+
+    if let Err(e) = upload_media(media.to_vec(), &mut tx).await {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(media.to_vec()),
+        Err(e) => {
+            tracing::error!("create_imported_media failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn get_import_storage_used_by_user_id<'e>(
+    user_id: &str,
+    executor: impl PgExecutor<'e>,
+) -> Result<i64, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT COALESCE(SUM(file_size), 0)::BIGINT FROM media
+        WHERE user_id = $1 AND source = $2
+        ",
+    )
+    .bind(user_id)
+    .bind(MediaSource::Import.value())
+    .fetch_one(executor)
+    .await;
+
+    match sqlx_result {
+        Ok((import_storage_used,)) => Ok(import_storage_used),
+        Err(e) => {
+            tracing::error!("get_import_storage_used_by_user_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
 async fn upload_image_from_import_and_create_media(
     file_properties: &FileProperties,
     claims: &Claims,
@@ -444,7 +829,10 @@ pub async fn upload_media_with_retry(
     .await
 }
 
-async fn upload_media(media: Vec<Media>, pool: &PgPool) -> Result<Vec<Media>, ApiError> {
+async fn upload_media<'e>(
+    media: Vec<Media>,
+    executor: impl PgExecutor<'e>,
+) -> Result<Vec<Media>, ApiError> {
     // IMPORTANT NOTE
     // due to the genius who made this (that's me...)
     // you need to update the num_properties to match the number of
@@ -455,13 +843,13 @@ async fn upload_media(media: Vec<Media>, pool: &PgPool) -> Result<Vec<Media>, Ap
     // we need to insert num_properties * media.len()
     // therefore we loop to map each binding to a VALUE number
 
-    let num_properties: u8 = 13;
+    let num_properties: u8 = 16;
 
     let mut sql = "
     INSERT INTO media (
-        id, user_id, file_id, post_id, url,
+        id, user_id, file_id, file_size, post_id, url,
         width, height, mime_type,
-        generate_media_dto, seed, source, model, created_at
+        generate_media_dto, seed, source, model, input_media_id, ink_cost, created_at
     ) "
     .to_string();
 
@@ -491,6 +879,7 @@ async fn upload_media(media: Vec<Media>, pool: &PgPool) -> Result<Vec<Media>, Ap
         sqlx = sqlx.bind(&m.id);
         sqlx = sqlx.bind(&m.user_id);
         sqlx = sqlx.bind(&m.file_id);
+        sqlx = sqlx.bind(m.file_size);
         sqlx = sqlx.bind(&m.post_id);
         sqlx = sqlx.bind(&m.url);
         sqlx = sqlx.bind(m.width.to_owned() as i16);
@@ -500,10 +889,12 @@ async fn upload_media(media: Vec<Media>, pool: &PgPool) -> Result<Vec<Media>, Ap
         sqlx = sqlx.bind(&m.seed);
         sqlx = sqlx.bind(&m.source);
         sqlx = sqlx.bind(&m.model);
+        sqlx = sqlx.bind(&m.input_media_id);
+        sqlx = sqlx.bind(m.ink_cost);
         sqlx = sqlx.bind(m.created_at.to_owned() as i64);
     }
 
-    match sqlx.execute(pool).await {
+    match sqlx.execute(executor).await {
         Ok(_) => Ok(media),
         Err(e) => {
             tracing::warn!("upload_media: {:?}", e);
@@ -597,6 +988,30 @@ pub async fn get_media_by_id_as_anonymous(id: &str, pool: &PgPool) -> Result<Med
     }
 }
 
+pub async fn get_media_ids_by_post_id_as_admin(
+    post_id: &str,
+    pool: &PgPool,
+) -> Result<Vec<String>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (String,)>(
+        "
+        SELECT id FROM media
+        WHERE post_id = $1
+        ORDER BY created_at ASC
+        ",
+    )
+    .bind(post_id)
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(rows) => Ok(rows.into_iter().map(|row| row.0).collect()),
+        Err(e) => {
+            tracing::error!("get_media_ids_by_post_id_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
 pub async fn delete_media_by_id(
     id: &str,
     claims: &Claims,
@@ -619,13 +1034,19 @@ pub async fn delete_media_by_id(
         .await
     {
         Ok(_) => {
-            let dto = media.generate_media_dto;
+            // refunds what was charged, not what the media would cost at today's prices
+            let ink_refunded = media.ink_cost.filter(|_| {
+                state
+                    .media_generators
+                    .get(&media.source)
+                    .is_some_and(|provider| provider.refunds_on_delete())
+            });
 
-            if media.source == MediaGenerator::STABLE_HORDE && dto.is_some() {
+            if let Some(ink_refunded) = ink_refunded {
                 match delete_media_and_refund_ink(
                     &media.id,
                     &media.user_id,
-                    &dto.unwrap().0,
+                    ink_refunded,
                     &state.pool,
                 )
                 .await
@@ -681,7 +1102,7 @@ pub async fn delete_media_by_id(
 async fn delete_media_and_refund_ink(
     media_id: &str,
     user_id: &str,
-    dto: &GenerateMediaDto,
+    ink_refunded: i64,
     pool: &PgPool,
 ) -> Result<(), ApiError> {
     let Ok(mut tx) = pool.begin().await else {
@@ -711,8 +1132,6 @@ async fn delete_media_and_refund_ink(
         });
     }
 
-    let ink_refunded = users::util::ink::ink::calculate_ink_cost(dto, Some(1));
-
     let edit_user_ink_dto = EditUserInkDto {
         ink_increase: match ink_refunded > 0 {
             true => Some(ink_refunded),
@@ -725,8 +1144,14 @@ async fn delete_media_and_refund_ink(
         ink_pending_decrease: None,
     };
 
-    let edit_user_ink_by_id_result =
-        users::util::ink::ink::edit_user_ink_by_id(user_id, &edit_user_ink_dto, &mut tx).await;
+    let edit_user_ink_by_id_result = users::util::ink::ink::edit_user_ink_by_id(
+        user_id,
+        &edit_user_ink_dto,
+        InkLedgerReason::MEDIA_REFUND,
+        Some(media_id),
+        &mut tx,
+    )
+    .await;
 
     if edit_user_ink_by_id_result.is_err() {
         let rollback_result = tx.rollback().await;
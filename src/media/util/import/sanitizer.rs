@@ -0,0 +1,93 @@
+use std::io::Cursor;
+
+use axum::http::StatusCode;
+use bytes::Bytes;
+use image::{ImageFormat, ImageReader, Limits};
+
+use crate::{
+    app::{models::api_error::ApiError, util::multipart::models::file_properties::FileProperties},
+    media::config::{
+        MAX_IMPORT_DECODE_ALLOC, MAX_IMPORT_DIMENSION, MAX_IMPORT_FILE_SIZE, MIN_IMPORT_DIMENSION,
+    },
+};
+
+// the client declared mime type is ignored, the format is sniffed from the
+// magic bytes and the image is re-encoded to webp, which also strips metadata
+pub fn sanitize(file_properties: &FileProperties) -> Result<FileProperties, ApiError> {
+    if file_properties.data.len() > MAX_IMPORT_FILE_SIZE {
+        return Err(ApiError {
+            code: StatusCode::PAYLOAD_TOO_LARGE,
+            message: "Files must be 1 MB or less.".to_string(),
+        });
+    }
+
+    let format = match image::guess_format(&file_properties.data) {
+        Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP)) => format,
+        _ => {
+            return Err(ApiError {
+                code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                message: "Files must be PNG, JPEG or WebP images.".to_string(),
+            })
+        }
+    };
+
+    // the header is read first so a small file claiming a huge canvas is
+    // rejected before anything is allocated for its pixels
+    let reader = ImageReader::with_format(Cursor::new(&file_properties.data), format);
+    let Ok((width, height)) = reader.into_dimensions() else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Failed to read image.".to_string(),
+        });
+    };
+
+    if width < MIN_IMPORT_DIMENSION
+        || height < MIN_IMPORT_DIMENSION
+        || width > MAX_IMPORT_DIMENSION
+        || height > MAX_IMPORT_DIMENSION
+    {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: format!(
+                "Images must be between {} and {} pixels wide and tall.",
+                MIN_IMPORT_DIMENSION, MAX_IMPORT_DIMENSION
+            ),
+        });
+    }
+
+    // the decoder is held to the same bounds in case the header lied
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMPORT_DIMENSION);
+    limits.max_image_height = Some(MAX_IMPORT_DIMENSION);
+    limits.max_alloc = Some(MAX_IMPORT_DECODE_ALLOC);
+
+    let mut reader = ImageReader::with_format(Cursor::new(&file_properties.data), format);
+    reader.limits(limits);
+
+    let Ok(image) = reader.decode() else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Failed to read image.".to_string(),
+        });
+    };
+
+    let mut data = Cursor::new(Vec::new());
+
+    if let Err(e) =
+        image::DynamicImage::ImageRgba8(image.to_rgba8()).write_to(&mut data, ImageFormat::WebP)
+    {
+        tracing::error!("sanitize: {:?}", e);
+        return Err(ApiError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Failed to encode image.".to_string(),
+        });
+    }
+
+    Ok(FileProperties {
+        id: file_properties.id.to_string(),
+        field_name: file_properties.field_name.to_string(),
+        file_name: file_properties.id.to_string(),
+        mime_type: "image/webp".to_string(),
+        data: Bytes::from(data.into_inner()),
+    })
+}
@@ -1,2 +1,3 @@
 pub mod backblaze;
+pub mod import;
 pub mod openai;
@@ -50,6 +50,56 @@ pub async fn check_prompt(
     }
 }
 
+pub async fn check_image(
+    data: &[u8],
+    mime_type: &str,
+    openai_api_key: &str,
+    client: &reqwest::Client,
+) -> Result<OpenAiModerationResponse, ApiError> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert("Content-Type", "application/json".parse().unwrap());
+    headers.insert(
+        "Authorization",
+        ["Bearer ", openai_api_key].concat().parse().unwrap(),
+    );
+
+    let data_url = ["data:", mime_type, ";base64,", &base64::encode(data)].concat();
+
+    let url = "https://api.openai.com/v1/moderations";
+    let result = client
+        .post(url)
+        .headers(headers)
+        .json(&json!({
+            "model": "omni-moderation-latest",
+            "input": [{ "type": "image_url", "image_url": { "url": data_url } }]
+        }))
+        .send()
+        .await;
+
+    match result {
+        Ok(res) => match res.text().await {
+            Ok(text) => match serde_json::from_str(&text) {
+                Ok(openai_moderation_response) => Ok(openai_moderation_response),
+                Err(_) => {
+                    tracing::error!(%text);
+                    Err(DefaultApiError::InternalServerError.value())
+                }
+            },
+            Err(e) => {
+                tracing::error!(%e);
+                Err(DefaultApiError::InternalServerError.value())
+            }
+        },
+        Err(e) => {
+            tracing::error!("check_image, {:?}", e);
+            Err(ApiError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: "Failed to check image.".to_string(),
+            })
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct OpenAiModerationResponse {
     pub id: String,
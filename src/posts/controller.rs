@@ -2,15 +2,14 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    headers::{authorization::Bearer, Authorization},
     http::StatusCode,
-    Json, TypedHeader,
+    Json,
 };
 use validator::Validate;
 
 use crate::{
     app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
-    auth::jwt::models::claims::Claims,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
     AppState,
 };
 
@@ -22,10 +21,10 @@ use super::{
 
 // pub async fn create_post(
 //     State(state): State<Arc<AppState>>,
-//     TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+//     authorization: BearerAuthorization,
 //     JsonFromRequest(dto): JsonFromRequest<CreatePostDto>,
 // ) -> Result<Json<Post>, ApiError> {
-//     match Claims::from_header(authorization, &state.envy.jwt_secret) {
+//     match Claims::from_header(authorization, &state.jwt_keys) {
 //         Ok(claims) => {
 //             if let Err(e) = dto.validate() {
 //                 return Err(ApiError {
@@ -45,10 +44,10 @@ use super::{
 
 pub async fn get_posts(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetPostsFilterDto>,
 ) -> Result<Json<Vec<Post>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -69,14 +68,14 @@ pub async fn get_posts(
 pub async fn get_post_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    // TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    // authorization: BearerAuthorization,
 ) -> Result<Json<Post>, ApiError> {
     match service::get_post_by_id(&id, &state.pool).await {
         Ok(post) => Ok(Json(post)),
         Err(e) => Err(e),
     }
 
-    // match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    // match Claims::from_header(authorization, &state.jwt_keys) {
     //     Ok(claims) => match service::get_post_by_id(&id, &claims, &state.pool).await {
     //         Ok(post) => Ok(Json(post)),
     //         Err(e) => Err(e),
@@ -88,10 +87,10 @@ pub async fn get_post_by_id(
 pub async fn edit_post_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<EditPostDto>,
 ) -> Result<Json<Post>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -112,9 +111,9 @@ pub async fn edit_post_by_id(
 pub async fn report_post_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => service::report_post_by_id(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
@@ -123,9 +122,9 @@ pub async fn report_post_by_id(
 pub async fn delete_post_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<(), ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => return service::delete_post_by_id(&id, &claims, &state.pool).await,
         Err(e) => Err(e),
     }
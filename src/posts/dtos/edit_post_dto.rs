@@ -4,7 +4,7 @@ use validator::Validate;
 
 use crate::{
     app::{errors::DefaultApiError, models::api_error::ApiError, util::time},
-    auth::jwt::models::claims::Claims,
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
 };
 
 #[derive(Debug, Deserialize, Validate)]
@@ -27,7 +27,7 @@ pub struct EditPostDto {
 
 impl EditPostDto {
     pub fn to_sql(&self, claims: &Claims) -> Result<String, ApiError> {
-        let is_mod = claims.is_mod();
+        let can_feature = claims.has_permission(Permissions::FEATURE_POSTS);
 
         let mut sql = "UPDATE posts SET ".to_string();
         let mut clauses = Vec::new();
@@ -48,7 +48,7 @@ impl EditPostDto {
             index += 1;
         }
         if self.featured.is_some() {
-            if !is_mod {
+            if !can_feature {
                 return Err(DefaultApiError::PermissionDenied.value());
             }
 
@@ -77,7 +77,7 @@ impl EditPostDto {
 
         sql.push_str(&[" WHERE id = $", &index.to_string()].concat());
 
-        if !is_mod {
+        if !can_feature {
             sql.push_str(&[" AND user_id = '", &claims.id, "'"].concat());
         }
 
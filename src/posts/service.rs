@@ -9,7 +9,7 @@ use crate::{
         models::api_error::ApiError,
         util::sqlx::{get_code_from_db_err, SqlStateCodes},
     },
-    auth::jwt::{enums::roles::Roles, models::claims::Claims},
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
     AppState,
 };
 
@@ -316,18 +316,15 @@ pub async fn delete_post_by_id(id: &str, claims: &Claims, pool: &PgPool) -> Resu
     "
     .to_string();
 
-    let is_mod = match &claims.roles {
-        Some(roles) => roles.contains(&Roles::MODERATOR.to_string()),
-        None => false,
-    };
+    let can_delete_any = claims.has_permission(Permissions::DELETE_ANY_POST);
 
-    if !is_mod {
+    if !can_delete_any {
         sql.push_str(" AND user_id = $2");
     }
 
     let mut sqlx = sqlx::query(&sql).bind(id);
 
-    if !is_mod {
+    if !can_delete_any {
         sqlx = sqlx.bind(&claims.id);
     }
 
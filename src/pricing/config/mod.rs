@@ -0,0 +1,4 @@
+// seconds between reloads of the ink prices table, so edits apply without a deploy
+pub static PRICING_REFRESH_INTERVAL: u64 = 60;
+// pixels base_ink pays for when a price doesn't say, a 512x512 image
+pub static DEFAULT_BASE_PIXELS: i32 = 512 * 512;
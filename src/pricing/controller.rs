@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    media::dtos::generate_media_dto::GenerateMediaDto,
+    AppState,
+};
+
+use super::{
+    dtos::{create_ink_price_dto::CreateInkPriceDto, edit_ink_price_dto::EditInkPriceDto},
+    models::{ink_price::InkPrice, ink_quote::InkQuote},
+    service,
+};
+
+pub async fn get_ink_quote(
+    State(state): State<Arc<AppState>>,
+    Query(dto): Query<GenerateMediaDto>,
+) -> Result<Json<InkQuote>, ApiError> {
+    if let Err(e) = dto.validate() {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: e.to_string(),
+        });
+    }
+
+    match service::get_ink_quote(&dto, &state) {
+        Ok(ink_quote) => Ok(Json(ink_quote)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn create_ink_price(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<CreateInkPriceDto>,
+) -> Result<Json<InkPrice>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::create_ink_price(&dto, &claims, &state).await {
+                Ok(ink_price) => Ok(Json(ink_price)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_ink_prices(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Vec<InkPrice>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::get_ink_prices(&claims, &state.pool).await {
+            Ok(ink_prices) => Ok(Json(ink_prices)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn edit_ink_price_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<EditInkPriceDto>,
+) -> Result<Json<InkPrice>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::edit_ink_price_by_id(&id, &dto, &claims, &state).await {
+                Ok(ink_price) => Ok(Json(ink_price)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_ink_price_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::delete_ink_price_by_id(&id, &claims, &state).await,
+        Err(e) => Err(e),
+    }
+}
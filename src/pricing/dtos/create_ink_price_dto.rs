@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInkPriceDto {
+    pub generator: String,
+    pub model: Option<String>,
+    pub mode: Option<String>,
+    #[validate(range(min = 10, max = 100, message = "max_steps must be between 10 and 100."))]
+    pub max_steps: Option<i16>,
+    #[validate(range(min = 0.0, message = "base_ink must not be negative."))]
+    pub base_ink: f64,
+    #[validate(range(min = 0.0, message = "multiplier must not be negative."))]
+    pub multiplier: Option<f64>,
+    #[validate(range(
+        min = 0.0,
+        max = 1.0,
+        message = "min_strength must be between 0 and 1."
+    ))]
+    pub min_strength: Option<f32>,
+    #[validate(range(min = 1, message = "base_pixels must be positive."))]
+    pub base_pixels: Option<i32>,
+    pub subscription_required: Option<bool>,
+}
@@ -0,0 +1,86 @@
+use axum::http::StatusCode;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::app::{models::api_error::ApiError, util::time};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EditInkPriceDto {
+    #[validate(range(min = 10, max = 100, message = "max_steps must be between 10 and 100."))]
+    pub max_steps: Option<i16>,
+    #[validate(range(min = 0.0, message = "base_ink must not be negative."))]
+    pub base_ink: Option<f64>,
+    #[validate(range(min = 0.0, message = "multiplier must not be negative."))]
+    pub multiplier: Option<f64>,
+    #[validate(range(
+        min = 0.0,
+        max = 1.0,
+        message = "min_strength must be between 0 and 1."
+    ))]
+    pub min_strength: Option<f32>,
+    #[validate(range(min = 1, message = "base_pixels must be positive."))]
+    pub base_pixels: Option<i32>,
+    pub subscription_required: Option<bool>,
+}
+
+impl EditInkPriceDto {
+    pub fn to_sql(&self) -> Result<String, ApiError> {
+        let mut sql = "UPDATE ink_prices SET ".to_string();
+        let mut clauses = Vec::new();
+
+        let mut index: u8 = 1;
+
+        // SET CLAUSES
+        if self.max_steps.is_some() {
+            clauses.push(["max_steps = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.base_ink.is_some() {
+            clauses.push(["base_ink = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.multiplier.is_some() {
+            clauses.push(["multiplier = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.min_strength.is_some() {
+            clauses.push(["min_strength = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.base_pixels.is_some() {
+            clauses.push(["base_pixels = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.subscription_required.is_some() {
+            clauses.push(["subscription_required = $", &index.to_string()].concat());
+            index += 1;
+        }
+
+        // CLAUSES BUILDER
+        if clauses.is_empty() {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Received nothing to edit.".to_string(),
+            });
+        }
+
+        for (i, clause) in clauses.iter().enumerate() {
+            if i != 0 {
+                sql.push_str(", ");
+            }
+
+            sql.push_str(clause);
+        }
+
+        let updated_at = time::current_time_in_secs();
+        sql.push_str(&[", updated_at = ", &updated_at.to_string()].concat());
+
+        sql.push_str(&[" WHERE id = $", &index.to_string()].concat());
+
+        sql.push_str(" RETURNING *");
+
+        tracing::debug!(sql);
+
+        Ok(sql)
+    }
+}
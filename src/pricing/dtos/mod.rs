@@ -0,0 +1,2 @@
+pub mod create_ink_price_dto;
+pub mod edit_ink_price_dto;
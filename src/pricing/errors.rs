@@ -0,0 +1,34 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum PricingApiError {
+    InkPriceNotFound,
+    InvalidGenerator,
+    InvalidModel,
+    InvalidMode,
+}
+
+impl PricingApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::InkPriceNotFound => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "Ink price not found.".to_string(),
+            },
+            Self::InvalidGenerator => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Invalid generator.".to_string(),
+            },
+            Self::InvalidModel => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "This generator does not support this model.".to_string(),
+            },
+            Self::InvalidMode => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "mode must be txt2img, img2img, inpaint or outpaint.".to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod controller;
+pub mod dtos;
+pub mod errors;
+pub mod models;
+pub mod refresher;
+pub mod service;
+pub mod structs;
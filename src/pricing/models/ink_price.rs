@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    app::util::time,
+    media::dtos::generate_media_dto::GenerateMediaDto,
+    pricing::{config::DEFAULT_BASE_PIXELS, dtos::create_ink_price_dto::CreateInkPriceDto},
+};
+
+// ink charged for an image of base_pixels, for every request matching the row.
+// model, mode and max_steps left empty match anything.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InkPrice {
+    pub id: String,
+    pub generator: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<i16>,
+    pub base_ink: f64,
+    // applied on top of base_ink, for modes that cost more to run
+    pub multiplier: f64,
+    // when set the price also scales with the request's strength, never below this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_strength: Option<f32>,
+    pub base_pixels: i32,
+    // the generator, or the model when set, is only available to subscribers
+    pub subscription_required: bool,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl InkPrice {
+    pub fn new(dto: &CreateInkPriceDto) -> Self {
+        let current_time = time::current_time_in_secs() as i64;
+
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            generator: dto.generator.to_string(),
+            model: dto.model.clone(),
+            mode: dto.mode.clone(),
+            max_steps: dto.max_steps,
+            base_ink: dto.base_ink,
+            multiplier: dto.multiplier.unwrap_or(1.0),
+            min_strength: dto.min_strength,
+            base_pixels: dto.base_pixels.unwrap_or(DEFAULT_BASE_PIXELS),
+            subscription_required: dto.subscription_required.unwrap_or(false),
+            updated_at: current_time,
+            created_at: current_time,
+        };
+    }
+
+    pub fn matches(&self, dto: &GenerateMediaDto) -> bool {
+        if self.generator != dto.generator {
+            return false;
+        }
+        if let Some(model) = &self.model {
+            if dto.model.as_ref() != Some(model) {
+                return false;
+            }
+        }
+        if let Some(mode) = &self.mode {
+            if *mode != dto.mode() {
+                return false;
+            }
+        }
+        if let Some(max_steps) = self.max_steps {
+            if dto.steps() as i16 > max_steps {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    pub fn ink_per_pixel(&self, dto: &GenerateMediaDto) -> f64 {
+        let mut multiplier = self.multiplier;
+
+        if let Some(min_strength) = self.min_strength {
+            let strength = dto.strength.unwrap_or(GenerateMediaDto::default_strength());
+            multiplier *= strength.max(min_strength) as f64;
+        }
+
+        return self.base_ink / self.base_pixels.max(1) as f64 * multiplier;
+    }
+
+    // rows naming a model win over rows naming a mode, then the tightest step tier wins
+    pub fn specificity(&self) -> (bool, bool, i16) {
+        return (
+            self.model.is_none(),
+            self.mode.is_none(),
+            self.max_steps.unwrap_or(i16::MAX),
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct InkQuote {
+    pub generator: String,
+    pub model: String,
+    pub mode: String,
+    pub steps: u16,
+    pub number: u8,
+    pub width: u16,
+    pub height: u16,
+    pub ink_cost: i64,
+}
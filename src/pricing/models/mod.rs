@@ -0,0 +1,2 @@
+pub mod ink_price;
+pub mod ink_quote;
@@ -0,0 +1,21 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{task, time::interval};
+
+use crate::AppState;
+
+use super::{config::PRICING_REFRESH_INTERVAL, service};
+
+pub fn spawn(state: Arc<AppState>) {
+    task::spawn(async move {
+        let mut interval = interval(Duration::from_secs(PRICING_REFRESH_INTERVAL));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = service::refresh_ink_prices_as_admin(&state).await {
+                tracing::error!("refresh_ink_prices_as_admin: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError},
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
+    media::{
+        dtos::generate_media_dto::GenerateMediaDto, enums::media_generate_mode::MediaGenerateMode,
+        errors::MediaApiError,
+    },
+    AppState,
+};
+
+use super::{
+    dtos::{create_ink_price_dto::CreateInkPriceDto, edit_ink_price_dto::EditInkPriceDto},
+    errors::PricingApiError,
+    models::{ink_price::InkPrice, ink_quote::InkQuote},
+};
+
+pub fn get_ink_quote(dto: &GenerateMediaDto, state: &Arc<AppState>) -> Result<InkQuote, ApiError> {
+    let Some(provider) = state.media_generators.get(&dto.generator) else {
+        return Err(MediaApiError::GeneratorNotSupported.value());
+    };
+
+    // quotes are shown to everyone, subscription checks happen when generating
+    if let Err(e) = dto.is_valid(provider, &state.ink_pricing, true) {
+        return Err(e);
+    }
+
+    let dto = &dto.sanitized(provider);
+
+    return Ok(InkQuote {
+        generator: dto.generator.to_string(),
+        model: dto.model.clone().unwrap_or_default(),
+        mode: dto.mode(),
+        steps: dto.steps(),
+        number: dto.number,
+        width: dto.width,
+        height: dto.height,
+        ink_cost: state.ink_pricing.calculate_ink_cost(provider, dto, None),
+    });
+}
+
+pub async fn create_ink_price(
+    dto: &CreateInkPriceDto,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<InkPrice, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRICING) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let Some(provider) = state.media_generators.get(&dto.generator) else {
+        return Err(PricingApiError::InvalidGenerator.value());
+    };
+
+    if let Some(model) = &dto.model {
+        if !provider.is_valid_model(model) {
+            return Err(PricingApiError::InvalidModel.value());
+        }
+    }
+
+    if let Some(mode) = &dto.mode {
+        if !MediaGenerateMode::all().contains(&mode.as_str()) {
+            return Err(PricingApiError::InvalidMode.value());
+        }
+    }
+
+    let ink_price = InkPrice::new(dto);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO ink_prices (
+            id, generator, model, mode, max_steps, base_ink,
+            multiplier, min_strength, base_pixels, subscription_required, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ",
+    )
+    .bind(&ink_price.id)
+    .bind(&ink_price.generator)
+    .bind(&ink_price.model)
+    .bind(&ink_price.mode)
+    .bind(ink_price.max_steps)
+    .bind(ink_price.base_ink)
+    .bind(ink_price.multiplier)
+    .bind(ink_price.min_strength)
+    .bind(ink_price.base_pixels)
+    .bind(ink_price.subscription_required)
+    .bind(ink_price.updated_at)
+    .bind(ink_price.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => {
+            on_ink_prices_changed(state).await;
+            Ok(ink_price)
+        }
+        Err(e) => {
+            tracing::error!("create_ink_price: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_ink_prices(claims: &Claims, pool: &PgPool) -> Result<Vec<InkPrice>, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRICING) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    return get_ink_prices_as_admin(pool).await;
+}
+
+pub async fn get_ink_prices_as_admin(pool: &PgPool) -> Result<Vec<InkPrice>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, InkPrice>(
+        "
+        SELECT * FROM ink_prices
+        ORDER BY generator ASC, created_at ASC
+        ",
+    )
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(ink_prices) => Ok(ink_prices),
+        Err(e) => {
+            tracing::error!("get_ink_prices_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn edit_ink_price_by_id(
+    id: &str,
+    dto: &EditInkPriceDto,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<InkPrice, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRICING) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, InkPrice>(&sql);
+
+    if let Some(max_steps) = dto.max_steps {
+        sqlx = sqlx.bind(max_steps);
+    }
+    if let Some(base_ink) = dto.base_ink {
+        sqlx = sqlx.bind(base_ink);
+    }
+    if let Some(multiplier) = dto.multiplier {
+        sqlx = sqlx.bind(multiplier);
+    }
+    if let Some(min_strength) = dto.min_strength {
+        sqlx = sqlx.bind(min_strength);
+    }
+    if let Some(base_pixels) = dto.base_pixels {
+        sqlx = sqlx.bind(base_pixels);
+    }
+    if let Some(subscription_required) = dto.subscription_required {
+        sqlx = sqlx.bind(subscription_required);
+    }
+    sqlx = sqlx.bind(id);
+
+    match sqlx.fetch_optional(&state.pool).await {
+        Ok(ink_price) => match ink_price {
+            Some(ink_price) => {
+                on_ink_prices_changed(state).await;
+                Ok(ink_price)
+            }
+            None => Err(PricingApiError::InkPriceNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("edit_ink_price_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn delete_ink_price_by_id(
+    id: &str,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRICING) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sqlx_result = sqlx::query("DELETE FROM ink_prices WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => {
+                on_ink_prices_changed(state).await;
+                Ok(())
+            }
+            false => Err(PricingApiError::InkPriceNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("delete_ink_price_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn refresh_ink_prices_as_admin(state: &Arc<AppState>) -> Result<(), ApiError> {
+    let get_ink_prices_result = get_ink_prices_as_admin(&state.pool).await;
+    let Ok(ink_prices) = get_ink_prices_result else {
+        return Err(get_ink_prices_result.unwrap_err());
+    };
+
+    state.ink_pricing.set(ink_prices);
+
+    Ok(())
+}
+
+// other instances pick the change up on their next refresh
+async fn on_ink_prices_changed(state: &Arc<AppState>) {
+    if let Err(e) = refresh_ink_prices_as_admin(state).await {
+        tracing::error!("on_ink_prices_changed: {:?}", e);
+    }
+}
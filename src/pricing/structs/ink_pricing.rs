@@ -0,0 +1,71 @@
+use std::sync::RwLock;
+
+use crate::{
+    media::{
+        dtos::generate_media_dto::GenerateMediaDto,
+        providers::media_generator_provider::MediaGeneratorProvider,
+    },
+    pricing::{config::DEFAULT_BASE_PIXELS, models::ink_price::InkPrice},
+    users,
+};
+
+pub struct InkPricing {
+    prices: RwLock<Vec<InkPrice>>,
+}
+
+impl InkPricing {
+    pub fn new() -> Self {
+        return Self {
+            prices: RwLock::new(Vec::new()),
+        };
+    }
+
+    pub fn set(&self, prices: Vec<InkPrice>) {
+        *self.prices.write().unwrap() = prices;
+    }
+
+    // falls back to the provider's own price for a 512x512 image when no row matches
+    pub fn ink_per_pixel(
+        &self,
+        provider: &dyn MediaGeneratorProvider,
+        dto: &GenerateMediaDto,
+    ) -> f64 {
+        let prices = self.prices.read().unwrap();
+
+        return match prices
+            .iter()
+            .filter(|price| price.matches(dto))
+            .min_by_key(|price| price.specificity())
+        {
+            Some(price) => price.ink_per_pixel(dto),
+            None => provider.base_ink() / DEFAULT_BASE_PIXELS as f64,
+        };
+    }
+
+    // any row marking the generator, or the model, keeps it to subscribers
+    pub fn requires_subscription(&self, generator: &str, model: &str) -> bool {
+        let prices = self.prices.read().unwrap();
+
+        return prices.iter().any(|price| {
+            price.subscription_required
+                && price.generator == generator
+                && match &price.model {
+                    Some(price_model) => price_model == model,
+                    None => true,
+                }
+        });
+    }
+
+    pub fn calculate_ink_cost(
+        &self,
+        provider: &dyn MediaGeneratorProvider,
+        dto: &GenerateMediaDto,
+        number_generated: Option<u8>,
+    ) -> i64 {
+        return users::util::ink::ink::calculate_ink_cost(
+            dto,
+            self.ink_per_pixel(provider, dto),
+            number_generated,
+        );
+    }
+}
@@ -0,0 +1 @@
+pub mod ink_pricing;
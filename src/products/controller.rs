@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
+
+use super::{
+    dtos::{
+        create_product_dto::CreateProductDto, edit_product_dto::EditProductDto,
+        get_products_filter_dto::GetProductsFilterDto,
+    },
+    models::product::Product,
+    service,
+};
+
+pub async fn create_product(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<CreateProductDto>,
+) -> Result<Json<Product>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::create_product(&dto, &claims, &state.pool).await {
+                Ok(product) => Ok(Json(product)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_products(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    Query(dto): Query<GetProductsFilterDto>,
+) -> Result<Json<Vec<Product>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::get_products(&dto, &claims, &state.pool).await {
+                Ok(products) => Ok(Json(products)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_product_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Product>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::get_product_by_id(&id, &claims, &state.pool).await {
+            Ok(product) => Ok(Json(product)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn edit_product_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<EditProductDto>,
+) -> Result<Json<Product>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::edit_product_by_id(&id, &dto, &claims, &state.pool).await {
+                Ok(product) => Ok(Json(product)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_product_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::delete_product_by_id(&id, &claims, &state.pool).await,
+        Err(e) => Err(e),
+    }
+}
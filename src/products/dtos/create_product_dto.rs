@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateProductDto {
+    pub store: String,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "product_id must be between 1 and 255 characters."
+    ))]
+    pub product_id: String,
+    pub kind: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "entitlement must be between 1 and 255 characters."
+    ))]
+    pub entitlement: Option<String>,
+    #[validate(range(min = 1, message = "ink_amount must be greater than 0."))]
+    pub ink_amount: i64,
+    #[validate(range(
+        min = 1.0,
+        max = 10.0,
+        message = "bonus_multiplier must be between 1 and 10."
+    ))]
+    pub bonus_multiplier: Option<f64>,
+    pub active: Option<bool>,
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+}
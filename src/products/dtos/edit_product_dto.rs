@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::app::{models::api_error::ApiError, util::time};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EditProductDto {
+    #[validate(range(min = 1, message = "ink_amount must be greater than 0."))]
+    pub ink_amount: Option<i64>,
+    #[validate(range(
+        min = 1.0,
+        max = 10.0,
+        message = "bonus_multiplier must be between 1 and 10."
+    ))]
+    pub bonus_multiplier: Option<f64>,
+    pub active: Option<bool>,
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+}
+
+impl EditProductDto {
+    pub fn to_sql(&self) -> Result<String, ApiError> {
+        let mut sql = "UPDATE products SET ".to_string();
+        let mut clauses = Vec::new();
+
+        let mut index: u8 = 1;
+
+        // SET CLAUSES
+        if self.ink_amount.is_some() {
+            clauses.push(["ink_amount = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.bonus_multiplier.is_some() {
+            clauses.push(["bonus_multiplier = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.active.is_some() {
+            clauses.push(["active = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.starts_at.is_some() {
+            clauses.push(["starts_at = $", &index.to_string()].concat());
+            index += 1;
+        }
+        if self.ends_at.is_some() {
+            clauses.push(["ends_at = $", &index.to_string()].concat());
+            index += 1;
+        }
+
+        // CLAUSES BUILDER
+        if clauses.is_empty() {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Received nothing to edit.".to_string(),
+            });
+        }
+
+        for (i, clause) in clauses.iter().enumerate() {
+            if i != 0 {
+                sql.push_str(", ");
+            }
+
+            sql.push_str(clause);
+        }
+
+        let updated_at = time::current_time_in_secs();
+        sql.push_str(&[", updated_at = ", &updated_at.to_string()].concat());
+
+        sql.push_str(&[" WHERE id = $", &index.to_string()].concat());
+
+        sql.push_str(" RETURNING *");
+
+        tracing::debug!(sql);
+
+        Ok(sql)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod create_product_dto;
+pub mod edit_product_dto;
+pub mod get_products_filter_dto;
@@ -0,0 +1,2 @@
+pub mod product_kind;
+pub mod product_store;
@@ -0,0 +1,9 @@
+#[non_exhaustive]
+pub struct ProductKind;
+
+impl ProductKind {
+    // ink bundles credited once per purchase
+    pub const CONSUMABLE: &'static str = "consumable";
+    // plans that grant their ink amount on every renewal
+    pub const SUBSCRIPTION: &'static str = "subscription";
+}
@@ -0,0 +1,14 @@
+// matches the store values sent by revenuecat webhooks
+#[non_exhaustive]
+pub struct ProductStore;
+
+impl ProductStore {
+    pub const APP_STORE: &'static str = "APP_STORE";
+    pub const PLAY_STORE: &'static str = "PLAY_STORE";
+    // web checkout, product_id holds the stripe price id
+    pub const STRIPE: &'static str = "STRIPE";
+
+    pub fn all() -> [&'static str; 3] {
+        return [Self::APP_STORE, Self::PLAY_STORE, Self::STRIPE];
+    }
+}
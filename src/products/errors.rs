@@ -0,0 +1,39 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum ProductsApiError {
+    ProductNotFound,
+    InvalidStore,
+    InvalidValidityWindow,
+    InvalidKind,
+    MissingEntitlement,
+}
+
+impl ProductsApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::ProductNotFound => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "Product not found.".to_string(),
+            },
+            Self::InvalidStore => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Invalid store.".to_string(),
+            },
+            Self::InvalidValidityWindow => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "starts_at must be before ends_at.".to_string(),
+            },
+            Self::InvalidKind => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "kind must be consumable or subscription.".to_string(),
+            },
+            Self::MissingEntitlement => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Subscriptions require an entitlement.".to_string(),
+            },
+        }
+    }
+}
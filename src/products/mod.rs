@@ -0,0 +1,6 @@
+pub mod controller;
+pub mod dtos;
+pub mod enums;
+pub mod errors;
+pub mod models;
+pub mod service;
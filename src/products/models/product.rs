@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    app::util::time,
+    products::{dtos::create_product_dto::CreateProductDto, enums::product_kind::ProductKind},
+};
+
+pub static PRODUCT_SORTABLE_FIELDS: [&str; 2] = ["created_at", "updated_at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Product {
+    pub id: String,
+    pub store: String,
+    pub product_id: String,
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entitlement: Option<String>,
+    pub ink_amount: i64,
+    pub bonus_multiplier: f64,
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<i64>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl Product {
+    pub fn new(dto: &CreateProductDto) -> Self {
+        let current_time = time::current_time_in_secs() as i64;
+
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            store: dto.store.to_string(),
+            product_id: dto.product_id.to_string(),
+            kind: dto
+                .kind
+                .clone()
+                .unwrap_or(ProductKind::CONSUMABLE.to_string()),
+            entitlement: dto.entitlement.clone(),
+            ink_amount: dto.ink_amount,
+            bonus_multiplier: dto.bonus_multiplier.unwrap_or(1.0),
+            active: dto.active.unwrap_or(true),
+            starts_at: dto.starts_at,
+            ends_at: dto.ends_at,
+            updated_at: current_time,
+            created_at: current_time,
+        };
+    }
+
+    pub fn ink_amount_with_bonus(&self) -> i64 {
+        return (self.ink_amount as f64 * self.bonus_multiplier).round() as i64;
+    }
+}
@@ -0,0 +1,316 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+
+use crate::{
+    app::{
+        errors::DefaultApiError,
+        models::api_error::ApiError,
+        util::{
+            sqlx::{get_code_from_db_err, SqlStateCodes},
+            time,
+        },
+    },
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
+};
+
+use super::{
+    dtos::{
+        create_product_dto::CreateProductDto, edit_product_dto::EditProductDto,
+        get_products_filter_dto::GetProductsFilterDto,
+    },
+    enums::{product_kind::ProductKind, product_store::ProductStore},
+    errors::ProductsApiError,
+    models::product::Product,
+};
+
+pub async fn create_product(
+    dto: &CreateProductDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Product, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRODUCTS) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    if !ProductStore::all().contains(&dto.store.as_str()) {
+        return Err(ProductsApiError::InvalidStore.value());
+    }
+
+    if let (Some(starts_at), Some(ends_at)) = (dto.starts_at, dto.ends_at) {
+        if starts_at >= ends_at {
+            return Err(ProductsApiError::InvalidValidityWindow.value());
+        }
+    }
+
+    let product = Product::new(dto);
+
+    match product.kind.as_str() {
+        ProductKind::CONSUMABLE => {}
+        ProductKind::SUBSCRIPTION => {
+            if product.entitlement.is_none() {
+                return Err(ProductsApiError::MissingEntitlement.value());
+            }
+        }
+        _ => return Err(ProductsApiError::InvalidKind.value()),
+    }
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO products (
+            id, store, product_id, kind, entitlement, ink_amount, bonus_multiplier,
+            active, starts_at, ends_at, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ",
+    )
+    .bind(&product.id)
+    .bind(&product.store)
+    .bind(&product.product_id)
+    .bind(&product.kind)
+    .bind(&product.entitlement)
+    .bind(product.ink_amount)
+    .bind(product.bonus_multiplier)
+    .bind(product.active)
+    .bind(product.starts_at)
+    .bind(product.ends_at)
+    .bind(product.updated_at)
+    .bind(product.created_at)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(product),
+        Err(e) => {
+            let Some(db_err) = e.as_database_error() else {
+                tracing::error!(%e);
+                return Err(DefaultApiError::InternalServerError.value());
+            };
+
+            let Some(code) = get_code_from_db_err(db_err) else {
+                tracing::error!(%e);
+                return Err(DefaultApiError::InternalServerError.value());
+            };
+
+            match code.as_str() {
+                SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
+                    code: StatusCode::CONFLICT,
+                    message: "Product already exists.".to_string(),
+                }),
+                _ => {
+                    tracing::error!("create_product: {:?}", e);
+                    Err(DefaultApiError::InternalServerError.value())
+                }
+            }
+        }
+    }
+}
+
+pub async fn get_products(
+    dto: &GetProductsFilterDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Vec<Product>, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRODUCTS) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, Product>(&sql);
+
+    if let Some(id) = &dto.id {
+        sqlx = sqlx.bind(id);
+    }
+    if let Some(store) = &dto.store {
+        sqlx = sqlx.bind(store);
+    }
+    if let Some(product_id) = &dto.product_id {
+        sqlx = sqlx.bind(product_id);
+    }
+    if let Some(active) = dto.active {
+        sqlx = sqlx.bind(active);
+    }
+
+    match sqlx.fetch_all(pool).await {
+        Ok(products) => Ok(products),
+        Err(e) => {
+            tracing::error!("get_products: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_product_by_id(
+    id: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Product, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRODUCTS) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sqlx_result = sqlx::query_as::<_, Product>(
+        "
+        SELECT * FROM products
+        WHERE id = $1
+        ",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(product) => match product {
+            Some(product) => Ok(product),
+            None => Err(ProductsApiError::ProductNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("get_product_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_product_by_store_product_id_as_admin(
+    store: &str,
+    product_id: &str,
+    pool: &PgPool,
+) -> Result<Product, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, Product>(
+        "
+        SELECT * FROM products
+        WHERE store = $1 AND product_id = $2
+        ",
+    )
+    .bind(store)
+    .bind(product_id)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(product) => match product {
+            Some(product) => Ok(product),
+            None => Err(ProductsApiError::ProductNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("get_product_by_store_product_id_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// only active products inside their validity window can be purchased
+pub async fn get_purchasable_product_by_store_product_id_as_admin(
+    store: &str,
+    product_id: &str,
+    pool: &PgPool,
+) -> Result<Product, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, Product>(
+        "
+        SELECT * FROM products
+        WHERE store = $1 AND product_id = $2 AND active = TRUE
+        AND (starts_at IS NULL OR starts_at <= $3)
+        AND (ends_at IS NULL OR ends_at > $3)
+        ",
+    )
+    .bind(store)
+    .bind(product_id)
+    .bind(time::current_time_in_secs() as i64)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(product) => match product {
+            Some(product) => Ok(product),
+            None => Err(ProductsApiError::ProductNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!(
+                "get_purchasable_product_by_store_product_id_as_admin: {:?}",
+                e
+            );
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn edit_product_by_id(
+    id: &str,
+    dto: &EditProductDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Product, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRODUCTS) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    if let (Some(starts_at), Some(ends_at)) = (dto.starts_at, dto.ends_at) {
+        if starts_at >= ends_at {
+            return Err(ProductsApiError::InvalidValidityWindow.value());
+        }
+    }
+
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, Product>(&sql);
+
+    if let Some(ink_amount) = dto.ink_amount {
+        sqlx = sqlx.bind(ink_amount);
+    }
+    if let Some(bonus_multiplier) = dto.bonus_multiplier {
+        sqlx = sqlx.bind(bonus_multiplier);
+    }
+    if let Some(active) = dto.active {
+        sqlx = sqlx.bind(active);
+    }
+    if let Some(starts_at) = dto.starts_at {
+        sqlx = sqlx.bind(starts_at);
+    }
+    if let Some(ends_at) = dto.ends_at {
+        sqlx = sqlx.bind(ends_at);
+    }
+    sqlx = sqlx.bind(id);
+
+    match sqlx.fetch_optional(pool).await {
+        Ok(product) => match product {
+            Some(product) => Ok(product),
+            None => Err(ProductsApiError::ProductNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("edit_product_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn delete_product_by_id(
+    id: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PRODUCTS) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sqlx_result = sqlx::query("DELETE FROM products WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ProductsApiError::ProductNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("delete_product_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
+
+use super::{
+    dtos::{
+        create_promo_code_dto::CreatePromoCodeDto,
+        get_promo_codes_filter_dto::GetPromoCodesFilterDto,
+        redeem_promo_code_dto::RedeemPromoCodeDto,
+    },
+    models::promo_code::PromoCode,
+    service,
+};
+
+pub async fn create_promo_code(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<CreatePromoCodeDto>,
+) -> Result<Json<PromoCode>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::create_promo_code(&dto, &claims, &state.pool).await {
+                Ok(promo_code) => Ok(Json(promo_code)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_promo_codes(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    Query(dto): Query<GetPromoCodesFilterDto>,
+) -> Result<Json<Vec<PromoCode>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::get_promo_codes(&dto, &claims, &state.pool).await {
+                Ok(promo_codes) => Ok(Json(promo_codes)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_promo_code_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => service::delete_promo_code_by_id(&id, &claims, &state.pool).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn redeem_promo_code(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<RedeemPromoCodeDto>,
+) -> Result<Json<PromoCode>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::redeem_promo_code(&dto, &claims, &state.pool).await {
+                Ok(promo_code) => Ok(Json(promo_code)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePromoCodeDto {
+    #[validate(length(
+        min = 4,
+        max = 32,
+        message = "code must be between 4 and 32 characters."
+    ))]
+    pub code: String,
+    #[validate(range(min = 1, message = "ink_amount must be greater than 0."))]
+    pub ink_amount: i64,
+    #[validate(range(min = 1, message = "max_redemptions must be greater than 0."))]
+    pub max_redemptions: i32,
+    pub expires_at: Option<i64>,
+}
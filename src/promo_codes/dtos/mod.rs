@@ -0,0 +1,3 @@
+pub mod create_promo_code_dto;
+pub mod get_promo_codes_filter_dto;
+pub mod redeem_promo_code_dto;
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RedeemPromoCodeDto {
+    #[validate(length(
+        min = 4,
+        max = 32,
+        message = "code must be between 4 and 32 characters."
+    ))]
+    pub code: String,
+}
@@ -0,0 +1,29 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum PromoCodesApiError {
+    PromoCodeNotFound,
+    PromoCodeUnavailable,
+    AlreadyRedeemed,
+}
+
+impl PromoCodesApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::PromoCodeNotFound => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "Promo code not found.".to_string(),
+            },
+            Self::PromoCodeUnavailable => ApiError {
+                code: StatusCode::GONE,
+                message: "Promo code has expired or reached its redemption limit.".to_string(),
+            },
+            Self::AlreadyRedeemed => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "You have already redeemed this promo code.".to_string(),
+            },
+        }
+    }
+}
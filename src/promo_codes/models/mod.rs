@@ -0,0 +1 @@
+pub mod promo_code;
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{app::util::time, promo_codes::dtos::create_promo_code_dto::CreatePromoCodeDto};
+
+pub static PROMO_CODE_SORTABLE_FIELDS: [&str; 1] = ["created_at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromoCode {
+    pub id: String,
+    pub code: String,
+    pub ink_amount: i64,
+    pub max_redemptions: i32,
+    pub redemptions_count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl PromoCode {
+    pub fn new(dto: &CreatePromoCodeDto) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            code: dto.code.to_uppercase(),
+            ink_amount: dto.ink_amount,
+            max_redemptions: dto.max_redemptions,
+            redemptions_count: 0,
+            expires_at: dto.expires_at,
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
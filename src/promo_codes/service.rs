@@ -0,0 +1,259 @@
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    app::{
+        errors::DefaultApiError,
+        models::api_error::ApiError,
+        util::{
+            sqlx::{get_code_from_db_err, SqlStateCodes},
+            time,
+        },
+    },
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
+    transactions::{self, enums::transaction_type::TransactionType},
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+};
+
+use super::{
+    dtos::{
+        create_promo_code_dto::CreatePromoCodeDto,
+        get_promo_codes_filter_dto::GetPromoCodesFilterDto,
+        redeem_promo_code_dto::RedeemPromoCodeDto,
+    },
+    errors::PromoCodesApiError,
+    models::promo_code::PromoCode,
+};
+
+pub async fn create_promo_code(
+    dto: &CreatePromoCodeDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<PromoCode, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PROMO_CODES) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let promo_code = PromoCode::new(dto);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO promo_codes (
+            id, code, ink_amount, max_redemptions, redemptions_count, expires_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ",
+    )
+    .bind(&promo_code.id)
+    .bind(&promo_code.code)
+    .bind(promo_code.ink_amount)
+    .bind(promo_code.max_redemptions)
+    .bind(promo_code.redemptions_count)
+    .bind(promo_code.expires_at)
+    .bind(promo_code.created_at)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(promo_code),
+        Err(e) => {
+            let Some(db_err) = e.as_database_error() else {
+                tracing::error!(%e);
+                return Err(DefaultApiError::InternalServerError.value());
+            };
+
+            let Some(code) = get_code_from_db_err(db_err) else {
+                tracing::error!(%e);
+                return Err(DefaultApiError::InternalServerError.value());
+            };
+
+            match code.as_str() {
+                SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
+                    code: StatusCode::CONFLICT,
+                    message: "Promo code already exists.".to_string(),
+                }),
+                _ => {
+                    tracing::error!("create_promo_code: {:?}", e);
+                    Err(DefaultApiError::InternalServerError.value())
+                }
+            }
+        }
+    }
+}
+
+pub async fn get_promo_codes(
+    dto: &GetPromoCodesFilterDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Vec<PromoCode>, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PROMO_CODES) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, PromoCode>(&sql);
+
+    if let Some(id) = &dto.id {
+        sqlx = sqlx.bind(id);
+    }
+    if let Some(code) = &dto.code {
+        sqlx = sqlx.bind(code.to_uppercase());
+    }
+
+    match sqlx.fetch_all(pool).await {
+        Ok(promo_codes) => Ok(promo_codes),
+        Err(e) => {
+            tracing::error!("get_promo_codes: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn delete_promo_code_by_id(
+    id: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_PROMO_CODES) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sqlx_result = sqlx::query("DELETE FROM promo_codes WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(PromoCodesApiError::PromoCodeNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("delete_promo_code_by_id: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn redeem_promo_code(
+    dto: &RedeemPromoCodeDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<PromoCode, ApiError> {
+    let Ok(mut tx) = pool.begin().await else {
+        tracing::warn!("redeem_promo_code failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    // claiming a redemption slot and crediting the ink commit or roll back together
+    let sqlx_result = sqlx::query_as::<_, PromoCode>(
+        "
+        UPDATE promo_codes SET redemptions_count = redemptions_count + 1
+        WHERE code = $1
+        AND redemptions_count < max_redemptions
+        AND (expires_at IS NULL OR expires_at > $2)
+        RETURNING *
+        ",
+    )
+    .bind(dto.code.to_uppercase())
+    .bind(time::current_time_in_secs() as i64)
+    .fetch_optional(&mut tx)
+    .await;
+
+    let promo_code = match sqlx_result {
+        Ok(Some(promo_code)) => promo_code,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Err(get_promo_code_unavailable_reason(&dto.code, pool).await);
+        }
+        Err(e) => {
+            tracing::error!("redeem_promo_code: {:?}", e);
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    // one redemption per user is enforced by the transaction id
+    let transaction_id = format!("{}{}", promo_code.id, claims.id);
+    let data = json!({
+        "promo_code_id": promo_code.id,
+        "code": promo_code.code,
+        "ink_amount": promo_code.ink_amount,
+    });
+
+    if let Err(e) = transactions::service::create_transaction_of_type(
+        &transaction_id,
+        &claims.id,
+        TransactionType::PROMO_CODE,
+        data,
+        &mut tx,
+    )
+    .await
+    {
+        let _ = tx.rollback().await;
+
+        return match e.code {
+            StatusCode::CONFLICT => Err(PromoCodesApiError::AlreadyRedeemed.value()),
+            _ => Err(e),
+        };
+    }
+
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: Some(promo_code.ink_amount),
+        ink_decrease: None,
+        ink_sum_increase: Some(promo_code.ink_amount),
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &edit_user_ink_dto,
+        InkLedgerReason::PROMO_CODE,
+        Some(&transaction_id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("redeem_promo_code failed to roll back: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(promo_code),
+        Err(e) => {
+            tracing::error!("redeem_promo_code failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn get_promo_code_unavailable_reason(code: &str, pool: &PgPool) -> ApiError {
+    let sqlx_result = sqlx::query("SELECT id FROM promo_codes WHERE code = $1")
+        .bind(code.to_uppercase())
+        .fetch_optional(pool)
+        .await;
+
+    match sqlx_result {
+        Ok(Some(_)) => PromoCodesApiError::PromoCodeUnavailable.value(),
+        Ok(None) => PromoCodesApiError::PromoCodeNotFound.value(),
+        Err(e) => {
+            tracing::error!("get_promo_code_unavailable_reason: {:?}", e);
+            DefaultApiError::InternalServerError.value()
+        }
+    }
+}
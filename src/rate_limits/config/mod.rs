@@ -0,0 +1,24 @@
+use super::models::rate_limit_budget::RateLimitBudget;
+
+pub static GENERATE_BUDGET: RateLimitBudget = RateLimitBudget {
+    name: "generate",
+    limit: 10,
+    window: 60,
+};
+pub static AUTH_BUDGET: RateLimitBudget = RateLimitBudget {
+    name: "auth",
+    limit: 30,
+    window: 60,
+};
+pub static FEED_BUDGET: RateLimitBudget = RateLimitBudget {
+    name: "feed",
+    limit: 600,
+    window: 60,
+};
+pub static DEFAULT_BUDGET: RateLimitBudget = RateLimitBudget {
+    name: "default",
+    limit: 120,
+    window: 60,
+};
+// seconds between sweeps of expired windows
+pub static PRUNE_INTERVAL: u64 = 60;
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    api_keys,
+    app::{models::api_error::ApiError, structs::client_ip::ClientIp},
+    auth::jwt::{enums::token_type::TokenType, util::decode_jwt},
+    AppState,
+};
+
+use super::{models::rate_limit_decision::RateLimitDecision, util::budget_for};
+
+pub async fn rate_limit<B>(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Some(budget) = budget_for(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let client = verify_client(&mut request, &client_ip, &state).await;
+    let key = [budget.name, ":", &client].concat();
+
+    let decision = state.rate_limiter.check(&key, budget.limit, budget.window);
+
+    if !decision.allowed {
+        let mut response = ApiError {
+            code: StatusCode::TOO_MANY_REQUESTS,
+            message: "Too many requests, try again later.".to_string(),
+        }
+        .into_response();
+
+        if let Ok(retry_after) = HeaderValue::from_str(&decision.reset_in.to_string()) {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after);
+        }
+        insert_rate_limit_headers(response.headers_mut(), &decision);
+
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    insert_rate_limit_headers(response.headers_mut(), &decision);
+
+    return response;
+}
+
+// signed in users and api keys get their own budget wherever they connect from,
+// everyone else shares the budget of their ip. a credential only picks the budget
+// once verified, otherwise made up tokens would each get a fresh one. what was
+// verified is kept in the request extensions for the handlers to reuse.
+async fn verify_client<B>(
+    request: &mut Request<B>,
+    client_ip: &str,
+    state: &Arc<AppState>,
+) -> String {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.to_string());
+
+    if let Some(token) = token {
+        if api_keys::service::is_api_key(&token) {
+            if let Ok(api_key) = api_keys::service::verify_api_key(&token, &state.pool).await {
+                let client = ["api-key:", &api_key.id].concat();
+                request.extensions_mut().insert(api_key);
+
+                return client;
+            }
+        } else if let Ok(claims) = decode_jwt(token, &state.jwt_keys, TokenType::ACCESS) {
+            let client = ["user:", &claims.id].concat();
+            request.extensions_mut().insert(claims);
+
+            return client;
+        }
+    }
+
+    return ["ip:", client_ip].concat();
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    let values = [
+        ("x-ratelimit-limit", decision.limit as u64),
+        ("x-ratelimit-remaining", decision.remaining as u64),
+        ("x-ratelimit-reset", decision.reset_in),
+    ];
+
+    for (name, value) in values {
+        if let Ok(value) = HeaderValue::from_str(&value.to_string()) {
+            headers.insert(name, value);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod middleware;
+pub mod models;
+pub mod structs;
+pub mod util;
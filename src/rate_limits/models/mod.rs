@@ -0,0 +1,2 @@
+pub mod rate_limit_budget;
+pub mod rate_limit_decision;
@@ -0,0 +1,7 @@
+// requests a single client can make to a group of routes per window
+#[derive(Debug)]
+pub struct RateLimitBudget {
+    pub name: &'static str,
+    pub limit: u32,
+    pub window: u64,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // seconds until the window resets
+    pub reset_in: u64,
+}
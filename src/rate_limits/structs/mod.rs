@@ -0,0 +1 @@
+pub mod rate_limiter;
@@ -0,0 +1,72 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    app::util::time,
+    rate_limits::{config::PRUNE_INTERVAL, models::rate_limit_decision::RateLimitDecision},
+};
+
+struct Window {
+    started_at: u64,
+    length: u64,
+    count: u32,
+}
+
+struct Windows {
+    by_key: HashMap<String, Window>,
+    pruned_at: u64,
+}
+
+// fixed windows per key, counted on each instance
+pub struct RateLimiter {
+    windows: Mutex<Windows>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        return Self {
+            windows: Mutex::new(Windows {
+                by_key: HashMap::new(),
+                pruned_at: time::current_time_in_secs(),
+            }),
+        };
+    }
+
+    // counts the request against the key, unless it is already over the limit
+    pub fn check(&self, key: &str, limit: u32, window: u64) -> RateLimitDecision {
+        let current_time = time::current_time_in_secs();
+        let window_started_at = current_time - current_time % window;
+
+        let mut windows = self.windows.lock().unwrap();
+
+        if current_time - windows.pruned_at >= PRUNE_INTERVAL {
+            windows
+                .by_key
+                .retain(|_, window| window.started_at + window.length > current_time);
+            windows.pruned_at = current_time;
+        }
+
+        let entry = windows.by_key.entry(key.to_string()).or_insert(Window {
+            started_at: window_started_at,
+            length: window,
+            count: 0,
+        });
+
+        if entry.started_at != window_started_at {
+            entry.started_at = window_started_at;
+            entry.length = window;
+            entry.count = 0;
+        }
+
+        let allowed = entry.count < limit;
+        if allowed {
+            entry.count += 1;
+        }
+
+        return RateLimitDecision {
+            allowed,
+            limit,
+            remaining: limit - entry.count.min(limit),
+            reset_in: window_started_at + window - current_time,
+        };
+    }
+}
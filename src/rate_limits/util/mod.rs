@@ -0,0 +1,29 @@
+use axum::http::Method;
+
+use super::{
+    config::{AUTH_BUDGET, DEFAULT_BUDGET, FEED_BUDGET, GENERATE_BUDGET},
+    models::rate_limit_budget::RateLimitBudget,
+};
+
+// webhooks come from payment and media providers, which must never be throttled
+pub fn budget_for(method: &Method, path: &str) -> Option<&'static RateLimitBudget> {
+    if path.starts_with("/transactions") || path.starts_with("/webhooks/") {
+        return None;
+    }
+
+    if *method == Method::POST && path == "/media/generate" {
+        return Some(&GENERATE_BUDGET);
+    }
+
+    if path.starts_with("/auth/") {
+        return Some(&AUTH_BUDGET);
+    }
+
+    let is_feed =
+        path.starts_with("/posts") || path.starts_with("/media") || path.starts_with("/users");
+    if *method == Method::GET && is_feed {
+        return Some(&FEED_BUDGET);
+    }
+
+    return Some(&DEFAULT_BUDGET);
+}
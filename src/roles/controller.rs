@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{
+    app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    users::models::user::User,
+    AppState,
+};
+
+use super::{
+    dtos::{grant_permission_dto::GrantPermissionDto, grant_role_dto::GrantRoleDto},
+    models::role_change::RoleChange,
+    service,
+};
+
+pub async fn grant_role(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<GrantRoleDto>,
+) -> Result<Json<User>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::grant_role(&id, &dto, &claims, &state.pool).await {
+            Ok(user) => Ok(Json(user)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn revoke_role(
+    State(state): State<Arc<AppState>>,
+    Path((id, role)): Path<(String, String)>,
+    authorization: BearerAuthorization,
+) -> Result<Json<User>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::revoke_role(&id, &role, &claims, &state.pool).await {
+            Ok(user) => Ok(Json(user)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn grant_permission(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<GrantPermissionDto>,
+) -> Result<Json<User>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::grant_permission(&id, &dto, &claims, &state.pool).await {
+            Ok(user) => Ok(Json(user)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn revoke_permission(
+    State(state): State<Arc<AppState>>,
+    Path((id, permission)): Path<(String, String)>,
+    authorization: BearerAuthorization,
+) -> Result<Json<User>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            match service::revoke_permission(&id, &permission, &claims, &state.pool).await {
+                Ok(user) => Ok(Json(user)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_role_changes(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Vec<RoleChange>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::get_role_changes(&id, &claims, &state.pool).await {
+            Ok(role_changes) => Ok(Json(role_changes)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,2 @@
+pub mod grant_permission_dto;
+pub mod grant_role_dto;
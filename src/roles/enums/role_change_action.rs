@@ -0,0 +1,7 @@
+#[non_exhaustive]
+pub struct RoleChangeAction;
+
+impl RoleChangeAction {
+    pub const GRANT: &'static str = "grant";
+    pub const REVOKE: &'static str = "revoke";
+}
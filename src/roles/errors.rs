@@ -0,0 +1,49 @@
+use axum::http::StatusCode;
+
+use crate::app::models::api_error::ApiError;
+
+#[derive(Debug)]
+pub enum RolesApiError {
+    InvalidRole,
+    InvalidPermission,
+    RoleAlreadyGranted,
+    RoleNotGranted,
+    PermissionAlreadyGranted,
+    PermissionNotGranted,
+    CannotChangeOwnRoles,
+}
+
+impl RolesApiError {
+    pub fn value(&self) -> ApiError {
+        match *self {
+            Self::InvalidRole => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "role must be mod or admin.".to_string(),
+            },
+            Self::InvalidPermission => ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Invalid permission.".to_string(),
+            },
+            Self::RoleAlreadyGranted => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "User already has this role.".to_string(),
+            },
+            Self::RoleNotGranted => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "User does not have this role.".to_string(),
+            },
+            Self::PermissionAlreadyGranted => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "User already has this permission.".to_string(),
+            },
+            Self::PermissionNotGranted => ApiError {
+                code: StatusCode::NOT_FOUND,
+                message: "User does not have this permission.".to_string(),
+            },
+            Self::CannotChangeOwnRoles => ApiError {
+                code: StatusCode::FORBIDDEN,
+                message: "You cannot change your own roles.".to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{app::util::time, auth::jwt::models::claims::Claims};
+
+// audit log entry, either role or permission is set
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RoleChange {
+    pub id: String,
+    pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_by: Option<String>,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+    pub created_at: i64,
+}
+
+impl RoleChange {
+    pub fn new(
+        user_id: &str,
+        action: &str,
+        role: Option<&str>,
+        permission: Option<&str>,
+        claims: &Claims,
+    ) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            changed_by: Some(claims.id.to_string()),
+            action: action.to_string(),
+            role: role.map(|role| role.to_string()),
+            permission: permission.map(|permission| permission.to_string()),
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
@@ -0,0 +1,317 @@
+use sqlx::PgPool;
+
+use crate::{
+    app::{errors::DefaultApiError, models::api_error::ApiError, util::time},
+    auth::jwt::{
+        enums::{permissions::Permissions, roles::Roles},
+        models::claims::Claims,
+    },
+    users::{errors::UsersApiError, models::user::User},
+};
+
+use super::{
+    dtos::{grant_permission_dto::GrantPermissionDto, grant_role_dto::GrantRoleDto},
+    enums::role_change_action::RoleChangeAction,
+    errors::RolesApiError,
+    models::role_change::RoleChange,
+};
+
+pub async fn grant_role(
+    user_id: &str,
+    dto: &GrantRoleDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    if !Roles::all().contains(&dto.role.as_str()) {
+        return Err(RolesApiError::InvalidRole.value());
+    }
+
+    return edit_access_by_user_id(
+        user_id,
+        RoleChangeAction::GRANT,
+        Some(&dto.role),
+        None,
+        claims,
+        pool,
+    )
+    .await;
+}
+
+pub async fn revoke_role(
+    user_id: &str,
+    role: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    return edit_access_by_user_id(
+        user_id,
+        RoleChangeAction::REVOKE,
+        Some(role),
+        None,
+        claims,
+        pool,
+    )
+    .await;
+}
+
+pub async fn grant_permission(
+    user_id: &str,
+    dto: &GrantPermissionDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    if !Permissions::all().contains(&dto.permission.as_str()) {
+        return Err(RolesApiError::InvalidPermission.value());
+    }
+
+    return edit_access_by_user_id(
+        user_id,
+        RoleChangeAction::GRANT,
+        None,
+        Some(&dto.permission),
+        claims,
+        pool,
+    )
+    .await;
+}
+
+pub async fn revoke_permission(
+    user_id: &str,
+    permission: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    return edit_access_by_user_id(
+        user_id,
+        RoleChangeAction::REVOKE,
+        None,
+        Some(permission),
+        claims,
+        pool,
+    )
+    .await;
+}
+
+pub async fn get_role_changes(
+    user_id: &str,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Vec<RoleChange>, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_ROLES) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let sqlx_result = sqlx::query_as::<_, RoleChange>(
+        "
+        SELECT * FROM role_changes
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(role_changes) => Ok(role_changes),
+        Err(e) => {
+            tracing::error!("get_role_changes: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// edits the user and every device they are logged in on, so refreshed tokens
+// carry the change, and records it in the audit log. access tokens already issued
+// are not revoked, they keep the old roles and permissions until they expire,
+// which is at most JWT_EXP seconds after the change.
+// only admins can hand out or take away admin, a permission that moves ink or money
+// or a role carrying one, or edit an admin. otherwise anyone managing roles could
+// mint ink or make themselves an admin through an accomplice.
+async fn edit_access_by_user_id(
+    user_id: &str,
+    action: &str,
+    role: Option<&str>,
+    permission: Option<&str>,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    if !claims.has_permission(Permissions::MANAGE_ROLES) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    if user_id == claims.id {
+        return Err(RolesApiError::CannotChangeOwnRoles.value());
+    }
+
+    let escalates = role.is_some_and(|role| {
+        role == Roles::ADMIN
+            || Roles::permissions(role)
+                .iter()
+                .any(|permission| Permissions::is_admin_only(permission))
+    }) || permission.is_some_and(Permissions::is_admin_only);
+    if escalates && !claims.is_admin() {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    let Ok(mut tx) = pool.begin().await else {
+        tracing::warn!("edit_access_by_user_id failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut tx)
+        .await;
+
+    let user = match sqlx_result {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Err(UsersApiError::UserNotFound.value());
+        }
+        Err(e) => {
+            tracing::error!("edit_access_by_user_id: {:?}", e);
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let mut roles = user.roles.unwrap_or_default();
+    if roles.iter().any(|r| r == Roles::ADMIN) && !claims.is_admin() {
+        let _ = tx.rollback().await;
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+    let mut permissions = user.permissions.unwrap_or_default();
+
+    let edit_result = match (role, permission) {
+        (Some(role), _) => match edit_values(&mut roles, role, action) {
+            true => Ok(()),
+            false if action == RoleChangeAction::GRANT => {
+                Err(RolesApiError::RoleAlreadyGranted.value())
+            }
+            false => Err(RolesApiError::RoleNotGranted.value()),
+        },
+        (None, Some(permission)) => match edit_values(&mut permissions, permission, action) {
+            true => Ok(()),
+            false if action == RoleChangeAction::GRANT => {
+                Err(RolesApiError::PermissionAlreadyGranted.value())
+            }
+            false => Err(RolesApiError::PermissionNotGranted.value()),
+        },
+        (None, None) => Err(DefaultApiError::InternalServerError.value()),
+    };
+
+    if let Err(e) = edit_result {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    let roles = (!roles.is_empty()).then_some(roles);
+    let permissions = (!permissions.is_empty()).then_some(permissions);
+
+    let sqlx_result = sqlx::query_as::<_, User>(
+        "
+        UPDATE users SET roles = $1, permissions = $2, updated_at = $3
+        WHERE id = $4
+        RETURNING *
+        ",
+    )
+    .bind(&roles)
+    .bind(&permissions)
+    .bind(time::current_time_in_secs() as i64)
+    .bind(user_id)
+    .fetch_one(&mut tx)
+    .await;
+
+    let user = match sqlx_result {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::error!("edit_access_by_user_id: {:?}", e);
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE devices SET roles = $1, permissions = $2
+        WHERE user_id = $3
+        ",
+    )
+    .bind(&roles)
+    .bind(&permissions)
+    .bind(user_id)
+    .execute(&mut tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("edit_access_by_user_id: {:?}", e);
+        let _ = tx.rollback().await;
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    let role_change = RoleChange::new(user_id, action, role, permission, claims);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO role_changes (
+            id, user_id, changed_by, action, role, permission, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ",
+    )
+    .bind(&role_change.id)
+    .bind(&role_change.user_id)
+    .bind(&role_change.changed_by)
+    .bind(&role_change.action)
+    .bind(&role_change.role)
+    .bind(&role_change.permission)
+    .bind(role_change.created_at)
+    .execute(&mut tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("edit_access_by_user_id: {:?}", e);
+        let _ = tx.rollback().await;
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    match tx.commit().await {
+        Ok(_) => {
+            tracing::info!(
+                "{} {} {} for user {}",
+                claims.id,
+                action,
+                role.or(permission).unwrap_or_default(),
+                user_id
+            );
+            Ok(user)
+        }
+        Err(e) => {
+            tracing::error!("edit_access_by_user_id failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// returns whether anything changed
+fn edit_values(values: &mut Vec<String>, value: &str, action: &str) -> bool {
+    let contains = values.iter().any(|v| v == value);
+
+    if action == RoleChangeAction::GRANT {
+        if contains {
+            return false;
+        }
+
+        values.push(value.to_string());
+        return true;
+    }
+
+    if !contains {
+        return false;
+    }
+
+    values.retain(|v| v != value);
+    return true;
+}
@@ -0,0 +1 @@
+pub mod subscription_status;
@@ -0,0 +1,9 @@
+#[non_exhaustive]
+pub struct SubscriptionStatus;
+
+impl SubscriptionStatus {
+    pub const ACTIVE: &'static str = "active";
+    // payment failed but the store is still retrying, access is kept until expiry
+    pub const BILLING_ISSUE: &'static str = "billing_issue";
+    pub const EXPIRED: &'static str = "expired";
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::app::util::time;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Subscription {
+    pub id: String,
+    pub user_id: String,
+    pub store: String,
+    pub product_id: String,
+    pub original_transaction_id: String,
+    pub entitlement: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl Subscription {
+    pub fn new(
+        user_id: &str,
+        store: &str,
+        product_id: &str,
+        original_transaction_id: &str,
+        entitlement: &str,
+        status: &str,
+        expires_at: Option<i64>,
+    ) -> Self {
+        let current_time = time::current_time_in_secs() as i64;
+
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            store: store.to_string(),
+            product_id: product_id.to_string(),
+            original_transaction_id: original_transaction_id.to_string(),
+            entitlement: entitlement.to_string(),
+            status: status.to_string(),
+            expires_at,
+            updated_at: current_time,
+            created_at: current_time,
+        };
+    }
+}
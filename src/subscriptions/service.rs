@@ -0,0 +1,81 @@
+use sqlx::{PgPool, Postgres};
+
+use crate::app::{errors::DefaultApiError, models::api_error::ApiError, util::time};
+
+use super::{enums::subscription_status::SubscriptionStatus, models::subscription::Subscription};
+
+// a redelivered or out of order event never moves the expiry backwards
+pub async fn upsert_subscription_as_tx(
+    subscription: &Subscription,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO subscriptions (
+            id, user_id, store, product_id, original_transaction_id,
+            entitlement, status, expires_at, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT (original_transaction_id) DO UPDATE SET
+            user_id = EXCLUDED.user_id,
+            product_id = EXCLUDED.product_id,
+            entitlement = EXCLUDED.entitlement,
+            status = EXCLUDED.status,
+            expires_at = COALESCE(EXCLUDED.expires_at, subscriptions.expires_at),
+            updated_at = EXCLUDED.updated_at
+        WHERE subscriptions.expires_at IS NULL
+            OR EXCLUDED.expires_at IS NULL
+            OR EXCLUDED.expires_at >= subscriptions.expires_at
+        ",
+    )
+    .bind(&subscription.id)
+    .bind(&subscription.user_id)
+    .bind(&subscription.store)
+    .bind(&subscription.product_id)
+    .bind(&subscription.original_transaction_id)
+    .bind(&subscription.entitlement)
+    .bind(&subscription.status)
+    .bind(subscription.expires_at)
+    .bind(subscription.updated_at)
+    .bind(subscription.created_at)
+    .execute(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("upsert_subscription_as_tx: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+}
+
+pub async fn get_entitlement_by_user_id(
+    user_id: &str,
+    pool: &PgPool,
+) -> Result<Option<String>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (String,)>(
+        "
+        SELECT entitlement FROM subscriptions
+        WHERE user_id = $1
+        AND status IN ($2, $3)
+        AND (expires_at IS NULL OR expires_at > $4)
+        ORDER BY expires_at DESC NULLS FIRST
+        LIMIT 1
+        ",
+    )
+    .bind(user_id)
+    .bind(SubscriptionStatus::ACTIVE)
+    .bind(SubscriptionStatus::BILLING_ISSUE)
+    .bind(time::current_time_in_secs() as i64)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(row) => Ok(row.map(|row| row.0)),
+        Err(e) => {
+            tracing::error!("get_entitlement_by_user_id: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+}
@@ -0,0 +1,2 @@
+// how old a stripe signature timestamp may be before the delivery is rejected as a replay
+pub static STRIPE_SIGNATURE_TOLERANCE: u64 = 300;
@@ -3,8 +3,10 @@ use std::sync::Arc;
 use axum::{
     extract::State,
     headers::{authorization::Bearer, Authorization},
+    http::HeaderMap,
     TypedHeader,
 };
+use bytes::Bytes;
 use reqwest::StatusCode;
 
 use crate::{
@@ -28,3 +30,22 @@ pub async fn handle_webhook(
 
     return service::handle_webhook(webhook, &state).await;
 }
+
+// the raw body is needed because the signature covers the exact bytes stripe sent
+pub async fn handle_stripe_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(), ApiError> {
+    let Some(signature) = headers
+        .get("Stripe-Signature")
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Err(ApiError {
+            code: StatusCode::UNAUTHORIZED,
+            message: "Invalid authorization".to_string(),
+        });
+    };
+
+    return service::handle_stripe_webhook(signature, &body, &state).await;
+}
@@ -0,0 +1,5 @@
+pub mod revenuecat_cancel_reason;
+pub mod revenuecat_event_type;
+pub mod stripe_event_type;
+pub mod transaction_status;
+pub mod transaction_type;
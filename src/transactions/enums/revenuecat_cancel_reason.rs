@@ -0,0 +1,12 @@
+#[non_exhaustive]
+pub struct RevenueCatCancelReason;
+
+impl RevenueCatCancelReason {
+    pub const UNSUBSCRIBE: &'static str = "UNSUBSCRIBE";
+    pub const BILLING_ERROR: &'static str = "BILLING_ERROR";
+    pub const DEVELOPER_INITIATED: &'static str = "DEVELOPER_INITIATED";
+    pub const PRICE_INCREASE: &'static str = "PRICE_INCREASE";
+    // the store refunded the transaction
+    pub const CUSTOMER_SUPPORT: &'static str = "CUSTOMER_SUPPORT";
+    pub const UNKNOWN: &'static str = "UNKNOWN";
+}
@@ -0,0 +1,17 @@
+#[non_exhaustive]
+pub struct RevenueCatEventType;
+
+impl RevenueCatEventType {
+    pub const INITIAL_PURCHASE: &'static str = "INITIAL_PURCHASE";
+    pub const RENEWAL: &'static str = "RENEWAL";
+    pub const EXPIRATION: &'static str = "EXPIRATION";
+    pub const BILLING_ISSUE: &'static str = "BILLING_ISSUE";
+    pub const NON_RENEWING_PURCHASE: &'static str = "NON_RENEWING_PURCHASE";
+    pub const CANCELLATION: &'static str = "CANCELLATION";
+    pub const TRANSFER: &'static str = "TRANSFER";
+
+    // events that grant a subscription's ink allowance
+    pub fn subscription_grants() -> [&'static str; 2] {
+        return [Self::INITIAL_PURCHASE, Self::RENEWAL];
+    }
+}
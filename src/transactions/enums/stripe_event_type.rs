@@ -0,0 +1,20 @@
+#[non_exhaustive]
+pub struct StripeEventType;
+
+impl StripeEventType {
+    pub const CHECKOUT_SESSION_COMPLETED: &'static str = "checkout.session.completed";
+    // sent once a delayed payment method settles a session that completed unpaid
+    pub const CHECKOUT_SESSION_ASYNC_PAYMENT_SUCCEEDED: &'static str =
+        "checkout.session.async_payment_succeeded";
+    pub const CHECKOUT_SESSION_ASYNC_PAYMENT_FAILED: &'static str =
+        "checkout.session.async_payment_failed";
+    pub const CHARGE_REFUNDED: &'static str = "charge.refunded";
+
+    // events that can credit a checkout session
+    pub fn purchases() -> [&'static str; 2] {
+        return [
+            Self::CHECKOUT_SESSION_COMPLETED,
+            Self::CHECKOUT_SESSION_ASYNC_PAYMENT_SUCCEEDED,
+        ];
+    }
+}
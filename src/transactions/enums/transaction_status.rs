@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct TransactionStatus;
+
+impl TransactionStatus {
+    pub const PROCESSED: &'static str = "processed";
+    // stored as-is so the event can be replayed once a handler exists
+    pub const UNHANDLED: &'static str = "unhandled";
+}
@@ -0,0 +1,8 @@
+// transactions created by the api itself rather than a store webhook
+#[non_exhaustive]
+pub struct TransactionType;
+
+impl TransactionType {
+    pub const PROMO_CODE: &'static str = "PROMO_CODE";
+    pub const GIFT: &'static str = "GIFT";
+}
@@ -2,16 +2,25 @@ use reqwest::StatusCode;
 
 use crate::{
     app::models::api_error::ApiError,
+    products::{
+        self,
+        enums::{product_kind::ProductKind, product_store::ProductStore},
+    },
     transactions::{
+        enums::revenuecat_cancel_reason::RevenueCatCancelReason,
         handlers::errors::HandlersApiError,
         service,
         structs::{
             revenuecat_event_cancellation::RevenueCatWebhookEventCancellation,
             revenuecat_webbook::RevenueCatWebhook,
         },
-        INK_LARGE_AMOUNT, INK_MEDIUM_AMOUNT, INK_MEGA_AMOUNT, INK_SMALL_AMOUNT,
     },
-    users::{self, util::ink::dtos::edit_user_ink_dto::EditUserInkDto},
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
     AppState,
 };
 
@@ -25,7 +34,7 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
         &event.aliases,
     ) else {
         tracing::error!(
-            "WEBHOOK ERROR<handle_non_renewing_purchase>: NO USER_ID FOUND ({})",
+            "WEBHOOK ERROR<handle_cancellation>: NO USER_ID FOUND ({})",
             event.id
         );
         return Err(ApiError {
@@ -34,15 +43,47 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
         });
     };
 
-    let amount = match event.product_id.as_ref() {
-        "com.greenknightlabs.mist.ios.ink_small.111622" => INK_SMALL_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_medium.111622" => INK_MEDIUM_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_large.111622" => INK_LARGE_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_mega.111622" => INK_MEGA_AMOUNT,
-        _ => {
-            tracing::error!("Not implemented product_id: {}", event.product_id);
-            return Err(HandlersApiError::ProductNotImplemented.value());
+    let store = event.store.as_deref().unwrap_or(ProductStore::APP_STORE);
+
+    let product_result = products::service::get_product_by_store_product_id_as_admin(
+        store,
+        &event.product_id,
+        &state.pool,
+    )
+    .await;
+    let Ok(product) = product_result else {
+        tracing::error!(
+            "Not implemented product_id: {} ({})",
+            event.product_id,
+            store
+        );
+        return Err(HandlersApiError::ProductNotImplemented.value());
+    };
+
+    let is_subscription = product.kind == ProductKind::SUBSCRIPTION;
+    let is_refund =
+        event.cancel_reason.as_deref() == Some(RevenueCatCancelReason::CUSTOMER_SUPPORT);
+
+    // claw back what the transaction actually credited, since the product's bonus may have changed
+    let credited_result = match &event.transaction_id {
+        Some(transaction_id) if is_subscription => {
+            service::get_granted_ink_by_store_transaction_id(transaction_id, &state.pool).await
+        }
+        Some(transaction_id) => {
+            service::get_purchased_ink_by_store_transaction_id(transaction_id, &state.pool).await
         }
+        None => Ok(None),
+    };
+    let Ok(credited) = credited_result else {
+        return Err(credited_result.unwrap_err());
+    };
+
+    // a subscription cancellation that isn't a refund only turns off auto-renew,
+    // the subscription keeps its entitlement until the EXPIRATION event arrives.
+    // a refunded one takes back the allowance granted for that billing period.
+    let amount = match is_subscription && !is_refund {
+        true => 0,
+        false => credited.unwrap_or(product.ink_amount_with_bonus()),
     };
 
     let Ok(mut tx) = state.pool.begin().await else {
@@ -52,55 +93,63 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
         });
     };
 
-    let edit_user_ink_dto = EditUserInkDto {
-        ink_increase: None,
-        ink_decrease: Some(amount),
-        ink_sum_increase: None,
-        ink_sum_decrease: Some(amount),
-        ink_pending_increase: None,
-        ink_pending_decrease: None,
-    };
+    if amount > 0 {
+        // ink is consumable, so a refund claws the ink back in full
+        // even if it was already spent. the balance is allowed to go negative, which locks
+        // generation until the user buys enough ink to cover it.
+        let edit_user_ink_dto = EditUserInkDto {
+            ink_increase: None,
+            ink_decrease: Some(amount),
+            ink_sum_increase: None,
+            ink_sum_decrease: Some(amount),
+            ink_pending_increase: None,
+            ink_pending_decrease: None,
+        };
 
-    let edit_user_ink_by_id_result =
-        users::util::ink::ink::edit_user_ink_by_id(&user_id, &edit_user_ink_dto, &mut tx).await;
+        let edit_user_ink_by_id_result = users::util::ink::ink::edit_user_ink_by_id(
+            &user_id,
+            &edit_user_ink_dto,
+            InkLedgerReason::CANCELLATION,
+            Some(&event.id),
+            &mut tx,
+        )
+        .await;
 
-    if edit_user_ink_by_id_result.is_err() {
-        let rollback_result = tx.rollback().await;
+        if edit_user_ink_by_id_result.is_err() {
+            let rollback_result = tx.rollback().await;
 
-        if let Some(e) = rollback_result.err() {
-            tracing::error!(
-                "handle_cancellation failed to rollback edit_user_ink_by_id_result: {:?}",
-                e
-            );
-        } else {
-            tracing::warn!("handle_cancellation rolled back edit_user_ink_by_id_result");
-        }
+            if let Some(e) = rollback_result.err() {
+                tracing::error!(
+                    "handle_cancellation failed to rollback edit_user_ink_by_id_result: {:?}",
+                    e
+                );
+            } else {
+                tracing::warn!("handle_cancellation rolled back edit_user_ink_by_id_result");
+            }
 
-        return Err(ApiError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: "Failed to edit user ink.".to_string(),
-        });
+            return Err(ApiError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: "Failed to edit user ink.".to_string(),
+            });
+        }
     }
 
     let create_transaction_result =
         service::create_transaction(&event.id, webhook, &user_id, &mut tx).await;
 
-    if create_transaction_result.is_err() {
+    if let Err(e) = create_transaction_result {
         let rollback_result = tx.rollback().await;
 
-        if let Some(e) = rollback_result.err() {
+        if let Some(rollback_e) = rollback_result.err() {
             tracing::error!(
                 "handle_cancellation failed to rollback create_transaction_result: {:?}",
-                e
+                rollback_e
             );
         } else {
             tracing::warn!("handle_cancellation rolled back create_transaction_result");
         }
 
-        return Err(ApiError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: "Failed to save transaction.".to_string(),
-        });
+        return Err(e);
     }
 
     match tx.commit().await {
@@ -7,6 +7,7 @@ pub enum HandlersApiError {
     TransactionError,
     TransactionFailure,
     ProductNotImplemented,
+    DuplicateEvent,
 }
 
 impl HandlersApiError {
@@ -24,6 +25,10 @@ impl HandlersApiError {
                 code: StatusCode::NOT_IMPLEMENTED,
                 message: "Product Id not implemented.".to_string(),
             },
+            Self::DuplicateEvent => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Event was already processed.".to_string(),
+            },
         }
     }
 }
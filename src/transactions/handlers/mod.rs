@@ -1,3 +1,6 @@
 pub mod cancellation;
 pub mod errors;
 pub mod non_renewing_purchase;
+pub mod stripe_charge_refunded;
+pub mod stripe_checkout_session_completed;
+pub mod subscription;
@@ -2,6 +2,7 @@ use reqwest::StatusCode;
 
 use crate::{
     app::models::api_error::ApiError,
+    products::{self, enums::product_store::ProductStore},
     transactions::{
         handlers::errors::HandlersApiError,
         service,
@@ -9,9 +10,13 @@ use crate::{
             revenuecat_event_non_renewing::RevenueCatWebhookEventNonRenewing,
             revenuecat_webbook::RevenueCatWebhook,
         },
-        INK_LARGE_AMOUNT, INK_MEDIUM_AMOUNT, INK_MEGA_AMOUNT, INK_SMALL_AMOUNT,
     },
-    users::{self, util::ink::dtos::edit_user_ink_dto::EditUserInkDto},
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
     AppState,
 };
 
@@ -34,17 +39,25 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
         });
     };
 
-    let amount = match event.product_id.as_ref() {
-        "com.greenknightlabs.mist.ios.ink_small.111622" => INK_SMALL_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_medium.111622" => INK_MEDIUM_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_large.111622" => INK_LARGE_AMOUNT,
-        "com.greenknightlabs.mist.ios.ink_mega.111622" => INK_MEGA_AMOUNT,
-        _ => {
-            tracing::error!("product_id not implemented: {}", event.product_id);
-            return Err(HandlersApiError::ProductNotImplemented.value());
-        }
+    let store = event.store.as_deref().unwrap_or(ProductStore::APP_STORE);
+
+    let product_result = products::service::get_purchasable_product_by_store_product_id_as_admin(
+        store,
+        &event.product_id,
+        &state.pool,
+    )
+    .await;
+    let Ok(product) = product_result else {
+        tracing::error!(
+            "product_id not implemented: {} ({})",
+            event.product_id,
+            store
+        );
+        return Err(HandlersApiError::ProductNotImplemented.value());
     };
 
+    let amount = product.ink_amount_with_bonus();
+
     let Ok(mut tx) = state.pool.begin().await else {
         return Err(ApiError {
             code: StatusCode::INTERNAL_SERVER_ERROR,
@@ -61,8 +74,14 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
         ink_pending_decrease: None,
     };
 
-    let edit_user_ink_by_id_result =
-        users::util::ink::ink::edit_user_ink_by_id(&user_id, &edit_user_ink_dto, &mut tx).await;
+    let edit_user_ink_by_id_result = users::util::ink::ink::edit_user_ink_by_id(
+        &user_id,
+        &edit_user_ink_dto,
+        InkLedgerReason::PURCHASE,
+        Some(&event.id),
+        &mut tx,
+    )
+    .await;
 
     if edit_user_ink_by_id_result.is_err() {
         let rollback_result = tx.rollback().await;
@@ -85,22 +104,19 @@ pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(),
     let create_transaction_result =
         service::create_transaction(&event.id, webhook, &user_id, &mut tx).await;
 
-    if create_transaction_result.is_err() {
+    if let Err(e) = create_transaction_result {
         let rollback_result = tx.rollback().await;
 
-        if let Some(e) = rollback_result.err() {
+        if let Some(rollback_e) = rollback_result.err() {
             tracing::error!(
                 "handle_non_renewing_purchase failed to roll back create_transaction_result: {:?}",
-                e
+                rollback_e
             );
         } else {
             tracing::warn!("handle_non_renewing_purchase rolled back create_transaction_result");
         }
 
-        return Err(ApiError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: "Failed to save transaction.".to_string(),
-        });
+        return Err(e);
     }
 
     match tx.commit().await {
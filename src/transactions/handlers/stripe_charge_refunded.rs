@@ -0,0 +1,117 @@
+use reqwest::StatusCode;
+
+use crate::{
+    app::models::api_error::ApiError,
+    transactions::{
+        handlers::errors::HandlersApiError,
+        service,
+        structs::{stripe_charge::StripeCharge, stripe_event::StripeEvent},
+    },
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+    AppState,
+};
+
+pub async fn handle(event: StripeEvent, state: &AppState) -> Result<(), ApiError> {
+    let Ok(charge) = serde_json::from_value::<StripeCharge>(event.data.object.clone()) else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Malformed charge.".to_string(),
+        });
+    };
+
+    let purchase_result = match &charge.payment_intent {
+        Some(payment_intent) => {
+            service::get_stripe_purchase_by_payment_intent(payment_intent, &state.pool).await
+        }
+        None => Ok(None),
+    };
+    let Ok(purchase) = purchase_result else {
+        return Err(purchase_result.unwrap_err());
+    };
+
+    // partial refunds and charges we never credited are kept for manual review
+    let Some((user_id, amount)) = purchase.filter(|_| charge.refunded) else {
+        tracing::warn!(
+            "handle_stripe_charge_refunded: not clawing back charge {} ({} of {} refunded)",
+            charge.id,
+            charge.amount_refunded,
+            charge.amount
+        );
+        return service::create_unhandled_transaction(
+            &event.id,
+            None,
+            &event.event_type,
+            event.data.object,
+            &state.pool,
+        )
+        .await;
+    };
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        return Err(ApiError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Failed to begin pool transaction.".to_string(),
+        });
+    };
+
+    if let Err(e) = service::create_transaction_of_type(
+        &event.id,
+        &user_id,
+        &event.event_type,
+        event.data.object.clone(),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "handle_stripe_charge_refunded failed to roll back create_transaction_of_type: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    // same as app store refunds, the balance may go negative
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: Some(amount),
+        ink_sum_increase: None,
+        ink_sum_decrease: Some(amount),
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &user_id,
+        &edit_user_ink_dto,
+        InkLedgerReason::REFUND,
+        Some(&event.id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "handle_stripe_charge_refunded failed to roll back edit_user_ink_by_id: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("handle_stripe_charge_refunded failed to commit tx: {:?}", e);
+            return Err(HandlersApiError::TransactionError.value());
+        }
+    }
+}
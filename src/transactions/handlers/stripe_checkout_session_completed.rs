@@ -0,0 +1,188 @@
+use reqwest::StatusCode;
+
+use crate::{
+    app::models::api_error::ApiError,
+    products::{self, enums::product_store::ProductStore},
+    transactions::{
+        handlers::errors::HandlersApiError,
+        service,
+        structs::{stripe_checkout_session::StripeCheckoutSession, stripe_event::StripeEvent},
+    },
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+    AppState,
+};
+
+// handles checkout.session.completed and its async_payment_succeeded and
+// async_payment_failed follow ups, only a paid session is credited
+pub async fn handle(event: StripeEvent, state: &AppState) -> Result<(), ApiError> {
+    let Ok(session) = serde_json::from_value::<StripeCheckoutSession>(event.data.object.clone())
+    else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Malformed checkout session.".to_string(),
+        });
+    };
+
+    let metadata = session.metadata.clone().unwrap_or_default();
+
+    let Some(user_id) = session
+        .client_reference_id
+        .clone()
+        .or(metadata.get("user_id").cloned())
+    else {
+        tracing::error!(
+            "WEBHOOK ERROR<handle_stripe_checkout_session_completed>: NO USER_ID FOUND ({})",
+            event.id
+        );
+        return Err(ApiError {
+            code: StatusCode::NOT_FOUND,
+            message: "Failed to get user_id from event.".to_string(),
+        });
+    };
+
+    // delayed payment methods complete the session before the money arrives,
+    // it is credited from async_payment_succeeded instead
+    if session.payment_status != "paid" {
+        tracing::warn!(
+            "handle_stripe_checkout_session_completed: session {} is {}",
+            session.id,
+            session.payment_status
+        );
+        return service::create_unhandled_transaction(
+            &event.id,
+            Some(user_id),
+            &event.event_type,
+            event.data.object,
+            &state.pool,
+        )
+        .await;
+    }
+
+    let Some(price_id) = metadata.get("price_id") else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Checkout session has no price_id.".to_string(),
+        });
+    };
+
+    let product_result = products::service::get_purchasable_product_by_store_product_id_as_admin(
+        ProductStore::STRIPE,
+        price_id,
+        &state.pool,
+    )
+    .await;
+    let Ok(product) = product_result else {
+        tracing::error!("price_id not implemented: {}", price_id);
+        return Err(HandlersApiError::ProductNotImplemented.value());
+    };
+
+    let amount = product.ink_amount_with_bonus();
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        return Err(ApiError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Failed to begin pool transaction.".to_string(),
+        });
+    };
+
+    match service::get_stripe_purchase_exists_by_session_id(&session.id, &mut tx).await {
+        Ok(false) => {}
+        Ok(true) => {
+            tracing::info!(
+                "handle_stripe_checkout_session_completed: session {} was already credited",
+                session.id
+            );
+
+            if let Err(e) = service::create_transaction_of_type(
+                &event.id,
+                &user_id,
+                &event.event_type,
+                event.data.object.clone(),
+                &mut tx,
+            )
+            .await
+            {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+
+            match tx.commit().await {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    tracing::error!(
+                        "handle_stripe_checkout_session_completed failed to commit tx: {:?}",
+                        e
+                    );
+                    return Err(HandlersApiError::TransactionError.value());
+                }
+            }
+        }
+        Err(e) => {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+    }
+
+    if let Err(e) = service::create_transaction_of_type(
+        &event.id,
+        &user_id,
+        &event.event_type,
+        event.data.object.clone(),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "handle_stripe_checkout_session_completed failed to roll back create_transaction_of_type: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: Some(amount),
+        ink_decrease: None,
+        ink_sum_increase: Some(amount),
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &user_id,
+        &edit_user_ink_dto,
+        InkLedgerReason::PURCHASE,
+        Some(&event.id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "handle_stripe_checkout_session_completed failed to roll back edit_user_ink_by_id: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(
+                "handle_stripe_checkout_session_completed failed to commit tx: {:?}",
+                e
+            );
+            return Err(HandlersApiError::TransactionError.value());
+        }
+    }
+}
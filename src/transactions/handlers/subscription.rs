@@ -0,0 +1,193 @@
+use reqwest::StatusCode;
+
+use crate::{
+    app::models::api_error::ApiError,
+    products::{
+        self,
+        enums::{product_kind::ProductKind, product_store::ProductStore},
+    },
+    subscriptions::{
+        self, enums::subscription_status::SubscriptionStatus, models::subscription::Subscription,
+    },
+    transactions::{
+        enums::revenuecat_event_type::RevenueCatEventType,
+        handlers::errors::HandlersApiError,
+        service,
+        structs::{
+            revenuecat_event_subscription::RevenueCatWebhookEventSubscription,
+            revenuecat_webbook::RevenueCatWebhook,
+        },
+    },
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+    AppState,
+};
+
+pub async fn handle(webhook: RevenueCatWebhook, state: &AppState) -> Result<(), ApiError> {
+    let Ok(event) =
+        serde_json::from_value::<RevenueCatWebhookEventSubscription>(webhook.clone().event)
+    else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Malformed subscription event.".to_string(),
+        });
+    };
+
+    let Some(user_id) = service::retrieve_user_id(
+        &event.app_user_id,
+        &event.original_app_user_id,
+        &event.aliases,
+    ) else {
+        tracing::error!(
+            "WEBHOOK ERROR<handle_subscription>: NO USER_ID FOUND ({})",
+            event.id
+        );
+        return Err(ApiError {
+            code: StatusCode::NOT_FOUND,
+            message: "Failed to get user_id from event.".to_string(),
+        });
+    };
+
+    let Some(original_transaction_id) = &event.original_transaction_id else {
+        tracing::error!(
+            "WEBHOOK ERROR<handle_subscription>: NO ORIGINAL_TRANSACTION_ID ({})",
+            event.id
+        );
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Event has no original_transaction_id.".to_string(),
+        });
+    };
+
+    let store = event.store.as_deref().unwrap_or(ProductStore::APP_STORE);
+
+    let product_result = products::service::get_product_by_store_product_id_as_admin(
+        store,
+        &event.product_id,
+        &state.pool,
+    )
+    .await;
+    let Ok(product) = product_result else {
+        tracing::error!(
+            "product_id not implemented: {} ({})",
+            event.product_id,
+            store
+        );
+        return Err(HandlersApiError::ProductNotImplemented.value());
+    };
+
+    if product.kind != ProductKind::SUBSCRIPTION {
+        tracing::error!("product_id is not a subscription: {}", event.product_id);
+        return Err(HandlersApiError::ProductNotImplemented.value());
+    }
+
+    // the allowance is granted up front for every billing period
+    let (status, grants_ink) = match event.event_type.as_str() {
+        RevenueCatEventType::INITIAL_PURCHASE | RevenueCatEventType::RENEWAL => {
+            (SubscriptionStatus::ACTIVE, true)
+        }
+        RevenueCatEventType::BILLING_ISSUE => (SubscriptionStatus::BILLING_ISSUE, false),
+        RevenueCatEventType::EXPIRATION => (SubscriptionStatus::EXPIRED, false),
+        _ => {
+            return Err(ApiError {
+                code: StatusCode::BAD_REQUEST,
+                message: "Not a subscription event.".to_string(),
+            })
+        }
+    };
+
+    let subscription = Subscription::new(
+        &user_id,
+        store,
+        &event.product_id,
+        original_transaction_id,
+        &product
+            .entitlement
+            .clone()
+            .unwrap_or(product.product_id.to_string()),
+        status,
+        event
+            .expiration_at_ms
+            .map(|expiration_at_ms| expiration_at_ms / 1000),
+    );
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        return Err(ApiError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Failed to begin pool transaction.".to_string(),
+        });
+    };
+
+    if let Err(e) = subscriptions::service::upsert_subscription_as_tx(&subscription, &mut tx).await
+    {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(rollback_e) = rollback_result.err() {
+            tracing::error!(
+                "handle_subscription failed to roll back upsert_subscription_as_tx: {:?}",
+                rollback_e
+            );
+        }
+
+        return Err(e);
+    }
+
+    if grants_ink {
+        let amount = product.ink_amount_with_bonus();
+
+        let edit_user_ink_dto = EditUserInkDto {
+            ink_increase: Some(amount),
+            ink_decrease: None,
+            ink_sum_increase: Some(amount),
+            ink_sum_decrease: None,
+            ink_pending_increase: None,
+            ink_pending_decrease: None,
+        };
+
+        if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+            &user_id,
+            &edit_user_ink_dto,
+            InkLedgerReason::SUBSCRIPTION_GRANT,
+            Some(&event.id),
+            &mut tx,
+        )
+        .await
+        {
+            let rollback_result = tx.rollback().await;
+
+            if let Some(rollback_e) = rollback_result.err() {
+                tracing::error!(
+                    "handle_subscription failed to roll back edit_user_ink_by_id: {:?}",
+                    rollback_e
+                );
+            }
+
+            return Err(e);
+        }
+    }
+
+    if let Err(e) = service::create_transaction(&event.id, webhook, &user_id, &mut tx).await {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(rollback_e) = rollback_result.err() {
+            tracing::error!(
+                "handle_subscription failed to roll back create_transaction: {:?}",
+                rollback_e
+            );
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("handle_subscription failed to commit tx: {:?}", e);
+            return Err(HandlersApiError::TransactionError.value());
+        }
+    }
+}
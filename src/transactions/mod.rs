@@ -1,10 +1,8 @@
+pub mod config;
 pub mod controller;
+pub mod enums;
 pub mod handlers;
 pub mod models;
 pub mod service;
 pub mod structs;
-
-pub static INK_SMALL_AMOUNT: i64 = 5000;
-pub static INK_MEDIUM_AMOUNT: i64 = 10000;
-pub static INK_LARGE_AMOUNT: i64 = 22000;
-pub static INK_MEGA_AMOUNT: i64 = 55000;
+pub mod util;
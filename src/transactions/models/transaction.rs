@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: String,
-    pub user_id: String,
+    pub user_id: Option<String>,
     pub data: sqlx::types::JsonValue,
+    pub event_type: Option<String>,
+    pub status: String,
     pub created_at: i64,
 }
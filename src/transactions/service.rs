@@ -1,47 +1,198 @@
 use std::sync::Arc;
 
 use reqwest::StatusCode;
-use sqlx::{Postgres, Transaction};
+use sqlx::{postgres::PgExecutor, PgPool, Postgres, Transaction};
 
 use crate::{
-    app::{models::api_error::ApiError, util::time},
-    transactions::handlers,
+    app::{
+        errors::DefaultApiError,
+        models::api_error::ApiError,
+        util::{
+            sqlx::{get_code_from_db_err, SqlStateCodes},
+            time,
+        },
+    },
+    transactions::handlers::{self, errors::HandlersApiError},
+    users::util::ink::enums::ink_ledger_reason::InkLedgerReason,
     AppState,
 };
 
-use super::structs::revenuecat_webbook::RevenueCatWebhook;
+use super::{
+    config::STRIPE_SIGNATURE_TOLERANCE,
+    enums::{
+        revenuecat_event_type::RevenueCatEventType, stripe_event_type::StripeEventType,
+        transaction_status::TransactionStatus,
+    },
+    structs::{revenuecat_webbook::RevenueCatWebhook, stripe_event::StripeEvent},
+    util::stripe_signature,
+};
 
 pub async fn handle_webhook(
     webhook: RevenueCatWebhook,
     state: &Arc<AppState>,
 ) -> Result<(), ApiError> {
-    let Some(event_type) = webhook.event.get("type") else {
+    let Some(event_type) = webhook.event.get("type").and_then(|value| value.as_str()) else {
         return Err(ApiError {
             code: StatusCode::BAD_REQUEST,
             message: "Event has no type.".to_string(),
         });
     };
+    let Some(event_id) = webhook.event.get("id").and_then(|value| value.as_str()) else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Event has no id.".to_string(),
+        });
+    };
+    let event_type = event_type.to_string();
+    let event_id = event_id.to_string();
 
-    let event = event_type.as_str().unwrap();
-
-    match event {
-        "NON_RENEWING_PURCHASE" => handlers::non_renewing_purchase::handle(webhook, state).await,
-        // "CANCELLATION" => handlers::cancellation::handle(webhook, state).await,
-        "TRANSFER" => {
-            // not handling for now
-            // ink is consumable so an app store account buying ink on different mist accounts
-            // will "transfer" their purchases to the latest mist account on revenuecat
-            // and the ink will be delivered to the new mist account user id as it should
-            // if they go back to their original mist account and buy ink again, the purchases will
-            // "transfer" back to the original mist account user id and the ink should
-            // deliver to the correctly logged in user
-            Ok(())
+    // revenuecat retries until it receives a 200, so redelivered events are acknowledged
+    match get_transaction_is_processed_by_id(&event_id, &state.pool).await {
+        Ok(true) => {
+            tracing::info!("handle_webhook: event {} was already processed", event_id);
+            return Ok(());
         }
+        Ok(false) => {}
+        Err(e) => return Err(e),
+    }
+
+    let handle_result = match event_type.as_str() {
+        RevenueCatEventType::NON_RENEWING_PURCHASE => {
+            handlers::non_renewing_purchase::handle(webhook, state).await
+        }
+        RevenueCatEventType::CANCELLATION => handlers::cancellation::handle(webhook, state).await,
+        RevenueCatEventType::INITIAL_PURCHASE
+        | RevenueCatEventType::RENEWAL
+        | RevenueCatEventType::EXPIRATION
+        | RevenueCatEventType::BILLING_ISSUE => {
+            handlers::subscription::handle(webhook, state).await
+        }
+        // ink is consumable so an app store account buying ink on different mist accounts
+        // will "transfer" their purchases to the latest mist account on revenuecat
+        // and the ink will be delivered to the new mist account user id as it should,
+        // so transfers are only stored in case they ever need to be replayed
         _ => {
-            tracing::error!("not handling webhook event type: {}", event);
-            tracing::error!("{:?}", webhook);
+            tracing::warn!("not handling webhook event type: {}", event_type);
+            let user_id = webhook
+                .event
+                .get("app_user_id")
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string());
+
+            create_unhandled_transaction(
+                &event_id,
+                user_id,
+                &event_type,
+                webhook.event,
+                &state.pool,
+            )
+            .await
+        }
+    };
+
+    match handle_result {
+        Ok(_) => Ok(()),
+        Err(e) => match e.code {
+            // a concurrent delivery of the same event won the insert
+            StatusCode::CONFLICT => {
+                tracing::info!("handle_webhook: event {} was already processed", event_id);
+                return Ok(());
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+pub async fn handle_stripe_webhook(
+    signature: &str,
+    payload: &[u8],
+    state: &Arc<AppState>,
+) -> Result<(), ApiError> {
+    if !stripe_signature::verify(
+        signature,
+        payload,
+        &state.envy.stripe_webhook_secret,
+        STRIPE_SIGNATURE_TOLERANCE,
+    ) {
+        return Err(ApiError {
+            code: StatusCode::UNAUTHORIZED,
+            message: "Invalid signature.".to_string(),
+        });
+    }
+
+    let Ok(event) = serde_json::from_slice::<StripeEvent>(payload) else {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Malformed event.".to_string(),
+        });
+    };
+    let event_id = event.id.to_string();
+
+    // stripe retries until it receives a 2xx, so redelivered events are acknowledged
+    match get_transaction_is_processed_by_id(&event_id, &state.pool).await {
+        Ok(true) => {
+            tracing::info!(
+                "handle_stripe_webhook: event {} was already processed",
+                event_id
+            );
             return Ok(());
         }
+        Ok(false) => {}
+        Err(e) => return Err(e),
+    }
+
+    let handle_result = match event.event_type.as_str() {
+        StripeEventType::CHECKOUT_SESSION_COMPLETED
+        | StripeEventType::CHECKOUT_SESSION_ASYNC_PAYMENT_SUCCEEDED
+        | StripeEventType::CHECKOUT_SESSION_ASYNC_PAYMENT_FAILED => {
+            handlers::stripe_checkout_session_completed::handle(event, state).await
+        }
+        StripeEventType::CHARGE_REFUNDED => {
+            handlers::stripe_charge_refunded::handle(event, state).await
+        }
+        _ => {
+            tracing::warn!("not handling stripe event type: {}", event.event_type);
+            create_unhandled_transaction(
+                &event.id,
+                None,
+                &event.event_type,
+                event.data.object,
+                &state.pool,
+            )
+            .await
+        }
+    };
+
+    match handle_result {
+        Ok(_) => Ok(()),
+        Err(e) => match e.code {
+            // a concurrent delivery of the same event won the insert
+            StatusCode::CONFLICT => {
+                tracing::info!(
+                    "handle_stripe_webhook: event {} was already processed",
+                    event_id
+                );
+                return Ok(());
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+// unhandled events are not deduplicated, so they get handled once a handler exists
+pub async fn get_transaction_is_processed_by_id(id: &str, pool: &PgPool) -> Result<bool, ApiError> {
+    let sqlx_result = sqlx::query("SELECT id FROM transactions WHERE id = $1 AND status = $2")
+        .bind(id)
+        .bind(TransactionStatus::PROCESSED)
+        .fetch_optional(pool)
+        .await;
+
+    match sqlx_result {
+        Ok(row) => Ok(row.is_some()),
+        Err(e) => {
+            tracing::error!("get_transaction_is_processed_by_id: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
     }
 }
 
@@ -51,31 +202,239 @@ pub async fn create_transaction(
     user_id: &str,
     tx: &mut Transaction<'_, Postgres>,
 ) -> Result<(), ApiError> {
-    let sql = r#"
-    INSERT INTO transactions (id, user_id, data, created_at)
-    VALUES ($1, $2, $3, $4)
-    "#;
+    let event_type = webhook
+        .event
+        .get("type")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
 
-    let sqlx_result = sqlx::query(&sql)
-        .bind(id)
-        .bind(user_id)
-        .bind(webhook.event)
-        .bind(time::current_time_in_secs() as i64)
-        .execute(&mut *tx)
-        .await;
+    return upsert_transaction(
+        id,
+        Some(user_id),
+        event_type.as_deref(),
+        webhook.event,
+        TransactionStatus::PROCESSED,
+        &mut *tx,
+    )
+    .await;
+}
+
+pub async fn create_transaction_of_type(
+    id: &str,
+    user_id: &str,
+    transaction_type: &str,
+    data: serde_json::Value,
+    tx: &mut Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    return upsert_transaction(
+        id,
+        Some(user_id),
+        Some(transaction_type),
+        data,
+        TransactionStatus::PROCESSED,
+        &mut *tx,
+    )
+    .await;
+}
+
+pub async fn create_unhandled_transaction(
+    id: &str,
+    user_id: Option<String>,
+    event_type: &str,
+    data: serde_json::Value,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    return upsert_transaction(
+        id,
+        user_id.as_deref(),
+        Some(event_type),
+        data,
+        TransactionStatus::UNHANDLED,
+        pool,
+    )
+    .await;
+}
+
+// an event stored as unhandled is upgraded in place once it is handled,
+// any other existing row makes it a duplicate
+async fn upsert_transaction<'e>(
+    id: &str,
+    user_id: Option<&str>,
+    event_type: Option<&str>,
+    data: serde_json::Value,
+    status: &str,
+    executor: impl PgExecutor<'e>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO transactions (id, user_id, data, event_type, status, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (id) DO UPDATE SET
+            user_id = COALESCE(EXCLUDED.user_id, transactions.user_id),
+            data = EXCLUDED.data,
+            event_type = EXCLUDED.event_type,
+            status = EXCLUDED.status
+        WHERE transactions.status = $7
+        ",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(data)
+    .bind(event_type)
+    .bind(status)
+    .bind(time::current_time_in_secs() as i64)
+    .bind(TransactionStatus::UNHANDLED)
+    .execute(executor)
+    .await;
 
     match sqlx_result {
-        Ok(_) => Ok(()),
+        Ok(result) => map_created_transaction(result.rows_affected()),
+        Err(e) => return Err(map_create_transaction_error(e)),
+    }
+}
+
+pub async fn get_purchased_ink_by_store_transaction_id(
+    transaction_id: &str,
+    pool: &PgPool,
+) -> Result<Option<i64>, ApiError> {
+    return get_credited_ink_by_store_transaction_id(
+        transaction_id,
+        &[RevenueCatEventType::NON_RENEWING_PURCHASE],
+        InkLedgerReason::PURCHASE,
+        pool,
+    )
+    .await;
+}
+
+pub async fn get_granted_ink_by_store_transaction_id(
+    transaction_id: &str,
+    pool: &PgPool,
+) -> Result<Option<i64>, ApiError> {
+    return get_credited_ink_by_store_transaction_id(
+        transaction_id,
+        &RevenueCatEventType::subscription_grants(),
+        InkLedgerReason::SUBSCRIPTION_GRANT,
+        pool,
+    )
+    .await;
+}
+
+async fn get_credited_ink_by_store_transaction_id(
+    transaction_id: &str,
+    event_types: &[&str],
+    reason: &str,
+    pool: &PgPool,
+) -> Result<Option<i64>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT ink_ledger.ink_delta FROM transactions
+        JOIN ink_ledger ON ink_ledger.reference_id = transactions.id
+        WHERE transactions.event_type = ANY($1)
+        AND transactions.data->>'transaction_id' = $2
+        AND ink_ledger.reason = $3
+        LIMIT 1
+        ",
+    )
+    .bind(event_types)
+    .bind(transaction_id)
+    .bind(reason)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(row) => Ok(row.map(|row| row.0)),
         Err(e) => {
-            tracing::error!(%e);
-            return Err(ApiError {
-                code: StatusCode::INTERNAL_SERVER_ERROR,
-                message: "Failed to create transaction.".to_string(),
-            });
+            tracing::error!("get_credited_ink_by_store_transaction_id: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
         }
     }
 }
 
+pub async fn get_stripe_purchase_by_payment_intent(
+    payment_intent: &str,
+    pool: &PgPool,
+) -> Result<Option<(String, i64)>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (String, i64)>(
+        "
+        SELECT transactions.user_id, ink_ledger.ink_delta FROM transactions
+        JOIN ink_ledger ON ink_ledger.reference_id = transactions.id
+        WHERE transactions.event_type = ANY($1)
+        AND transactions.data->>'payment_intent' = $2
+        AND ink_ledger.reason = $3
+        LIMIT 1
+        ",
+    )
+    .bind(StripeEventType::purchases())
+    .bind(payment_intent)
+    .bind(InkLedgerReason::PURCHASE)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(row) => Ok(row),
+        Err(e) => {
+            tracing::error!("get_stripe_purchase_by_payment_intent: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+}
+
+// a session completing unpaid and then settling sends two events,
+// only one of them may credit it
+pub async fn get_stripe_purchase_exists_by_session_id(
+    session_id: &str,
+    tx: &mut Transaction<'_, Postgres>,
+) -> Result<bool, ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        SELECT transactions.id FROM transactions
+        JOIN ink_ledger ON ink_ledger.reference_id = transactions.id
+        WHERE transactions.event_type = ANY($1)
+        AND transactions.data->>'id' = $2
+        AND ink_ledger.reason = $3
+        LIMIT 1
+        ",
+    )
+    .bind(StripeEventType::purchases())
+    .bind(session_id)
+    .bind(InkLedgerReason::PURCHASE)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(row) => Ok(row.is_some()),
+        Err(e) => {
+            tracing::error!("get_stripe_purchase_exists_by_session_id: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    }
+}
+
+// an event stored as unhandled is upgraded in place, a processed one is left alone
+fn map_created_transaction(rows_affected: u64) -> Result<(), ApiError> {
+    if rows_affected == 0 {
+        return Err(HandlersApiError::DuplicateEvent.value());
+    }
+
+    return Ok(());
+}
+
+fn map_create_transaction_error(e: sqlx::Error) -> ApiError {
+    if let Some(db_err) = e.as_database_error() {
+        if let Some(code) = get_code_from_db_err(db_err) {
+            if code == SqlStateCodes::UNIQUE_VIOLATION {
+                return HandlersApiError::DuplicateEvent.value();
+            }
+        }
+    }
+
+    tracing::error!(%e);
+    return ApiError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Failed to create transaction.".to_string(),
+    };
+}
+
 pub fn retrieve_user_id(
     app_user_id: &str,
     original_app_user_id: &str,
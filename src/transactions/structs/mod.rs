@@ -1,3 +1,7 @@
 pub mod revenuecat_event_cancellation;
 pub mod revenuecat_event_non_renewing;
+pub mod revenuecat_event_subscription;
 pub mod revenuecat_webbook;
+pub mod stripe_charge;
+pub mod stripe_checkout_session;
+pub mod stripe_event;
@@ -10,4 +10,7 @@ pub struct RevenueCatWebhookEventCancellation {
     pub original_app_user_id: String,
     pub aliases: Vec<String>,
     pub product_id: String,
+    pub store: Option<String>,
+    pub transaction_id: Option<String>,
+    pub cancel_reason: Option<String>,
 }
@@ -11,4 +11,6 @@ pub struct RevenueCatWebhookEventNonRenewing {
     pub original_app_user_id: String,
     pub aliases: Vec<String>,
     pub product_id: String,
+    pub store: Option<String>,
+    pub transaction_id: Option<String>,
 }
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+// shared by INITIAL_PURCHASE, RENEWAL, EXPIRATION and BILLING_ISSUE
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevenueCatWebhookEventSubscription {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub id: String,
+    pub event_timestamp_ms: i64,
+    pub app_user_id: String,
+    pub original_app_user_id: String,
+    pub aliases: Vec<String>,
+    pub product_id: String,
+    pub store: Option<String>,
+    pub original_transaction_id: Option<String>,
+    pub expiration_at_ms: Option<i64>,
+}
@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// the frontend creates sessions with our user id as client_reference_id
+// and the purchased price id in metadata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StripeCheckoutSession {
+    pub id: String,
+    pub client_reference_id: Option<String>,
+    pub payment_intent: Option<String>,
+    pub payment_status: String,
+    pub metadata: Option<HashMap<String, String>>,
+}
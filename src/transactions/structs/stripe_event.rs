@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+// https://stripe.com/docs/api/events/object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: StripeEventData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeEventData {
+    pub object: serde_json::Value,
+}
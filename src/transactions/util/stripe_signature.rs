@@ -0,0 +1,52 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::app::util::time;
+
+// https://stripe.com/docs/webhooks/signatures
+// the header looks like "t=1492774577,v1=5257a869...,v1=...", and every v1
+// is a hex encoded HMAC-SHA256 of "{t}.{payload}" keyed with the endpoint secret
+pub fn verify(header: &str, payload: &[u8], secret: &str, tolerance: u64) -> bool {
+    let mut timestamp: Option<u64> = None;
+    let mut signatures = Vec::new();
+
+    for part in header.split(',') {
+        let Some((key, value)) = part.trim().split_once('=') else {
+            continue;
+        };
+
+        match key {
+            "t" => timestamp = value.parse::<u64>().ok(),
+            "v1" => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+
+    if time::current_time_in_secs().abs_diff(timestamp) > tolerance {
+        return false;
+    }
+
+    for signature in signatures {
+        let Ok(signature) = hex::decode(signature) else {
+            continue;
+        };
+
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+
+        // verify_slice compares in constant time
+        if mac.verify_slice(&signature).is_ok() {
+            return true;
+        }
+    }
+
+    return false;
+}
@@ -0,0 +1,6 @@
+use crate::media::enums::media_model::MediaModel;
+
+pub static DEFAULT_MODEL: &str = MediaModel::REAL_ESRGAN_X4;
+pub static SCALE: u8 = 4;
+// ink charged for every 512x512 of output
+pub static BASE_INK: f64 = 2.0;
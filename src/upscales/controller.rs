@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
+    AppState,
+};
+
+use super::{
+    dtos::get_upscales_filter_dto::GetUpscalesFilterDto, models::upscale::Upscale, service,
+};
+
+pub async fn upscale_media(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Upscale>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::upscale_media(&id, &claims, &state).await {
+            Ok(upscale) => Ok(Json(upscale)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_upscales(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    Query(dto): Query<GetUpscalesFilterDto>,
+) -> Result<Json<Vec<Upscale>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match service::get_upscales(&dto, &claims, &state.pool).await {
+                Ok(upscales) => Ok(Json(upscales)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1 @@
+pub mod get_upscales_filter_dto;
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod controller;
+pub mod dtos;
+pub mod models;
+pub mod service;
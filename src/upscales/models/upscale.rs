@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    app::util::time,
+    media::{
+        dtos::generate_media_dto::GenerateMediaDto, models::media::Media,
+        util::backblaze::structs::backblaze_upload_file_response::BackblazeUploadFileResponse,
+    },
+};
+
+pub static UPSCALE_SORTABLE_FIELDS: [&str; 1] = ["created_at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Upscale {
+    pub id: String,
+    pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
+    pub file_id: String,
+    pub url: String,
+    pub width: i16,
+    pub height: i16,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate_media_dto: Option<sqlx::types::Json<GenerateMediaDto>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub created_at: i64,
+}
+
+impl Upscale {
+    pub fn from_media(
+        id: &str,
+        media: &Media,
+        model: &str,
+        scale: u8,
+        b2_upload_responses: &BackblazeUploadFileResponse,
+        b2_download_url: &str,
+    ) -> Upscale {
+        let download_url = [
+            b2_download_url,
+            "/b2api/v1/b2_download_file_by_id?fileId=",
+            &b2_upload_responses.file_id,
+        ]
+        .concat();
+
+        return Upscale {
+            id: id.to_string(),
+            user_id: media.user_id.to_string(),
+            media_id: Some(media.id.to_string()),
+            file_id: b2_upload_responses.file_id.to_string(),
+            url: download_url,
+            width: media.width * scale as i16,
+            height: media.height * scale as i16,
+            mime_type: b2_upload_responses.content_type.to_string(),
+            generate_media_dto: media.generate_media_dto.clone(),
+            seed: media.seed.clone(),
+            source: media.source.to_string(),
+            model: Some(model.to_string()),
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        errors::DefaultApiError, models::api_error::ApiError,
+        util::multipart::models::file_properties::FileProperties,
+    },
+    auth::jwt::models::claims::Claims,
+    media::{self, apis::modal, util::backblaze},
+    users::{
+        self,
+        util::ink::{
+            dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason,
+        },
+    },
+    AppState,
+};
+
+use super::{
+    config::{BASE_INK, DEFAULT_MODEL, SCALE},
+    dtos::get_upscales_filter_dto::GetUpscalesFilterDto,
+    models::upscale::Upscale,
+};
+
+pub async fn upscale_media(
+    media_id: &str,
+    claims: &Claims,
+    state: &Arc<AppState>,
+) -> Result<Upscale, ApiError> {
+    let get_media_by_id_result =
+        media::service::get_media_by_id(media_id, claims, &state.pool).await;
+    let Ok(media) = get_media_by_id_result else {
+        return Err(get_media_by_id_result.unwrap_err());
+    };
+
+    let width = media.width as i64 * SCALE as i64;
+    let height = media.height as i64 * SCALE as i64;
+
+    if width > i16::MAX as i64 || height > i16::MAX as i64 {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "Media is too large to upscale.".to_string(),
+        });
+    }
+
+    let ink_cost = calculate_ink_cost(width, height);
+
+    let upscale_id = Uuid::new_v4().to_string();
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        tracing::warn!("upscale_media failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) =
+        users::util::ink::ink::check_ink_available_as_tx(&claims.id, ink_cost, &mut tx).await
+    {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    // hold the ink while modal is working so it cannot be spent twice
+    let hold_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: None,
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: Some(ink_cost),
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &hold_ink_dto,
+        InkLedgerReason::UPSCALE_HOLD,
+        Some(&upscale_id),
+        &mut tx,
+    )
+    .await
+    {
+        let _ = tx.rollback().await;
+        return Err(e);
+    }
+
+    if let Err(e) = tx.commit().await {
+        tracing::error!("upscale_media failed to commit tx: {:?}", e);
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    let upscale_result =
+        upscale_and_create_upscale(&upscale_id, &media, claims, ink_cost, state).await;
+
+    if upscale_result.is_err() {
+        if let Err(e) = release_ink_hold(&claims.id, &upscale_id, ink_cost, &state.pool).await {
+            tracing::error!("upscale_media failed to release ink: {:?}", e);
+        }
+    }
+
+    upscale_result
+}
+
+async fn upscale_and_create_upscale(
+    upscale_id: &str,
+    media: &media::models::media::Media,
+    claims: &Claims,
+    ink_cost: i64,
+    state: &Arc<AppState>,
+) -> Result<Upscale, ApiError> {
+    let upscale_media_result =
+        modal::service::upscale_media(media, DEFAULT_MODEL, SCALE, state).await;
+    let Ok(bytes) = upscale_media_result else {
+        tracing::error!("upscale_and_create_upscale failed upscale_media");
+        return Err(upscale_media_result.unwrap_err());
+    };
+
+    let file_properties = FileProperties {
+        id: upscale_id.to_string(),
+        field_name: upscale_id.to_string(),
+        file_name: upscale_id.to_string(),
+        mime_type: mime::IMAGE_PNG.to_string(),
+        data: bytes,
+    };
+
+    let sub_folder = Some(["upscales/", &claims.id].concat());
+    let upload_file_result = backblaze::service::upload_file_with_retry(
+        &file_properties,
+        &sub_folder,
+        &state.b2,
+        &state.client,
+    )
+    .await;
+    let Ok(response) = upload_file_result else {
+        tracing::error!("upscale_and_create_upscale failed upload_file_with_retry");
+        return Err(upload_file_result.unwrap_err());
+    };
+
+    let b2_download_url = &state.b2.read().await.download_url;
+    let upscale = Upscale::from_media(
+        &file_properties.id,
+        media,
+        DEFAULT_MODEL,
+        SCALE,
+        &response,
+        b2_download_url,
+    );
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        tracing::warn!("upscale_and_create_upscale failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = create_upscale_as_tx(&upscale, &mut tx).await {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(e) = rollback_result.err() {
+            tracing::error!(
+                "upscale_and_create_upscale failed to roll back create_upscale_as_tx: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: Some(ink_cost),
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: Some(ink_cost),
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        &claims.id,
+        &edit_user_ink_dto,
+        InkLedgerReason::UPSCALE_CHARGE,
+        Some(upscale_id),
+        &mut tx,
+    )
+    .await
+    {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(e) = rollback_result.err() {
+            tracing::error!(
+                "upscale_and_create_upscale failed to roll back edit_user_ink_by_id: {:?}",
+                e
+            );
+        }
+
+        return Err(e);
+    }
+
+    if let Err(e) = tx.commit().await {
+        tracing::error!("upscale_and_create_upscale failed to commit tx: {:?}", e);
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    Ok(upscale)
+}
+
+async fn create_upscale_as_tx(
+    upscale: &Upscale,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO upscales (
+            id, user_id, media_id, file_id, url,
+            width, height, mime_type,
+            generate_media_dto, seed, source, model, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ",
+    )
+    .bind(&upscale.id)
+    .bind(&upscale.user_id)
+    .bind(&upscale.media_id)
+    .bind(&upscale.file_id)
+    .bind(&upscale.url)
+    .bind(upscale.width)
+    .bind(upscale.height)
+    .bind(&upscale.mime_type)
+    .bind(&upscale.generate_media_dto)
+    .bind(&upscale.seed)
+    .bind(&upscale.source)
+    .bind(&upscale.model)
+    .bind(upscale.created_at)
+    .execute(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("create_upscale_as_tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn release_ink_hold(
+    user_id: &str,
+    upscale_id: &str,
+    ink_cost: i64,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let release_ink_dto = EditUserInkDto {
+        ink_increase: None,
+        ink_decrease: None,
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: Some(ink_cost),
+    };
+
+    let Ok(mut tx) = pool.begin().await else {
+        tracing::warn!("release_ink_hold failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = users::util::ink::ink::edit_user_ink_by_id(
+        user_id,
+        &release_ink_dto,
+        InkLedgerReason::UPSCALE_RELEASE,
+        Some(upscale_id),
+        &mut tx,
+    )
+    .await
+    {
+        let rollback_result = tx.rollback().await;
+
+        if let Some(e) = rollback_result.err() {
+            tracing::error!("release_ink_hold failed to roll back: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    if let Err(e) = tx.commit().await {
+        tracing::error!("release_ink_hold failed to commit tx: {:?}", e);
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    Ok(())
+}
+
+pub fn calculate_ink_cost(width: i64, height: i64) -> i64 {
+    let ink_per_pixel: f64 = BASE_INK / (512.0 * 512.0);
+
+    return ((width * height) as f64 * ink_per_pixel).round() as i64;
+}
+
+pub async fn get_upscales(
+    dto: &GetUpscalesFilterDto,
+    _claims: &Claims,
+    pool: &PgPool,
+) -> Result<Vec<Upscale>, ApiError> {
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, Upscale>(&sql);
+
+    if let Some(id) = &dto.id {
+        sqlx = sqlx.bind(id);
+    }
+    if let Some(user_id) = &dto.user_id {
+        sqlx = sqlx.bind(user_id);
+    }
+    if let Some(media_id) = &dto.media_id {
+        sqlx = sqlx.bind(media_id);
+    }
+    if let Some(source) = &dto.source {
+        sqlx = sqlx.bind(source);
+    }
+    if let Some(model) = &dto.model {
+        sqlx = sqlx.bind(model);
+    }
+
+    match sqlx.fetch_all(pool).await {
+        Ok(upscales) => Ok(upscales),
+        Err(e) => {
+            tracing::error!("get_upscales: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
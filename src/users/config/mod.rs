@@ -0,0 +1,2 @@
+// ink granted to every new user
+pub static STARTER_INK: i64 = 100;
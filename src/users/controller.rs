@@ -2,15 +2,14 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    headers::{authorization::Bearer, Authorization},
     http::StatusCode,
-    Json, TypedHeader,
+    Json,
 };
 use validator::Validate;
 
 use crate::{
     app::{models::api_error::ApiError, structs::json_from_request::JsonFromRequest},
-    auth::jwt::models::claims::Claims,
+    auth::jwt::{models::claims::Claims, structs::bearer_authorization::BearerAuthorization},
     AppState,
 };
 
@@ -18,14 +17,21 @@ use super::{
     dtos::{edit_user_dto::EditUserDto, get_users_filter_dto::GetUsersFilterDto},
     models::user::User,
     service,
+    util::ink::{
+        dtos::{
+            adjust_user_ink_dto::AdjustUserInkDto, get_ink_ledger_filter_dto::GetInkLedgerFilterDto,
+        },
+        ink,
+        models::{ink_drift::InkDrift, ink_ledger_entry::InkLedgerEntry},
+    },
 };
 
 pub async fn get_users(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     Query(dto): Query<GetUsersFilterDto>,
 ) -> Result<Json<Vec<User>>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -45,10 +51,10 @@ pub async fn get_users(
 
 pub async fn get_user_from_request(
     State(state): State<Arc<AppState>>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<Json<User>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
-        Ok(claims) => match service::get_user_by_id(&claims.id, &claims, &state.pool).await {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match service::get_user_from_request(&claims, &state.pool).await {
             Ok(user) => Ok(Json(user)),
             Err(e) => Err(e),
         },
@@ -59,9 +65,9 @@ pub async fn get_user_from_request(
 pub async fn get_user_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
 ) -> Result<Json<User>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => match service::get_user_by_id(&id, &claims, &state.pool).await {
             Ok(user) => Ok(Json(user)),
             Err(e) => Err(e),
@@ -73,10 +79,10 @@ pub async fn get_user_by_id(
 pub async fn edit_user_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    TypedHeader(authorization): TypedHeader<Authorization<Bearer>>,
+    authorization: BearerAuthorization,
     JsonFromRequest(dto): JsonFromRequest<EditUserDto>,
 ) -> Result<Json<User>, ApiError> {
-    match Claims::from_header(authorization, &state.envy.jwt_secret) {
+    match Claims::from_header(authorization, &state.jwt_keys) {
         Ok(claims) => {
             if let Err(e) = dto.validate() {
                 return Err(ApiError {
@@ -93,3 +99,60 @@ pub async fn edit_user_by_id(
         Err(e) => Err(e),
     }
 }
+
+pub async fn get_ink_history(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+    Query(dto): Query<GetInkLedgerFilterDto>,
+) -> Result<Json<Vec<InkLedgerEntry>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            match ink::get_ink_ledger(&dto, &claims, &state.pool).await {
+                Ok(entries) => Ok(Json(entries)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn adjust_user_ink_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    authorization: BearerAuthorization,
+    JsonFromRequest(dto): JsonFromRequest<AdjustUserInkDto>,
+) -> Result<(), ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => {
+            if let Err(e) = dto.validate() {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: e.to_string(),
+                });
+            }
+
+            ink::adjust_user_ink_by_id(&id, &dto, &claims, &state.pool).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn reconcile_ink(
+    State(state): State<Arc<AppState>>,
+    authorization: BearerAuthorization,
+) -> Result<Json<Vec<InkDrift>>, ApiError> {
+    match Claims::from_header(authorization, &state.jwt_keys) {
+        Ok(claims) => match ink::reconcile_ink(&claims, &state.pool).await {
+            Ok(drifts) => Ok(Json(drifts)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
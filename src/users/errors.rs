@@ -6,6 +6,8 @@ use crate::app::models::api_error::ApiError;
 pub enum UsersApiError {
     UserNotFound,
     PermissionDenied,
+    EmailAlreadyVerified,
+    PasswordAlreadySet,
 }
 
 impl UsersApiError {
@@ -19,6 +21,14 @@ impl UsersApiError {
                 code: StatusCode::UNAUTHORIZED,
                 message: "Permission denied.".to_string(),
             },
+            Self::EmailAlreadyVerified => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Email is already verified.".to_string(),
+            },
+            Self::PasswordAlreadySet => ApiError {
+                code: StatusCode::CONFLICT,
+                message: "Password is already set.".to_string(),
+            },
         }
     }
 }
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::{app::util::time, auth::dtos::register_dto::RegisterDto};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: String,
     pub username: String,
@@ -18,22 +18,41 @@ pub struct User {
     #[serde(skip_serializing)]
     pub email_pending: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub avatar_url: Option<String>,
+    // none for users who only sign in with apple or google
     #[serde(skip_serializing)]
-    pub password_hash: String,
+    pub password_hash: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
     pub ink: i64,
     pub ink_sum: i64,
     pub ink_pending: i64,
+    // starter ink granted once the email is verified
+    #[serde(skip_serializing)]
+    pub ink_withheld: i64,
     #[serde(skip_serializing)]
     pub delete_pending: bool,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp_enabled_at: Option<i64>,
+    #[serde(skip_serializing)]
+    pub totp_last_used_step: Option<i64>,
     pub updated_at: i64,
     pub created_at: i64,
+
+    // ACTIVE SUBSCRIPTION
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)] // this is because the value does not exist on the users table itself
+    pub entitlement: Option<String>,
 }
 
 impl User {
-    pub fn new(dto: &RegisterDto, hash: String) -> Self {
+    pub fn new(dto: &RegisterDto, hash: String, starter_ink: i64, ink_withheld: i64) -> Self {
         let current_time = time::current_time_in_secs();
 
         return Self {
@@ -44,15 +63,61 @@ impl User {
             email: dto.email.to_string(),
             email_key: dto.email.to_lowercase(),
             email_pending: None,
+            email_verified_at: None,
             avatar_url: None,
-            password_hash: hash,
+            password_hash: Some(hash),
             roles: None,
-            ink: 100,
-            ink_sum: 100,
+            permissions: None,
+            ink: starter_ink,
+            ink_sum: starter_ink,
             ink_pending: 0,
+            ink_withheld,
             delete_pending: false,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
             updated_at: current_time as i64,
             created_at: current_time as i64,
+            entitlement: None,
+        };
+    }
+
+    pub fn new_from_identity(
+        username: &str,
+        email: &str,
+        email_verified: bool,
+        starter_ink: i64,
+        ink_withheld: i64,
+    ) -> Self {
+        let current_time = time::current_time_in_secs() as i64;
+
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            username: username.to_string(),
+            username_key: username.to_lowercase(),
+            displayname: username.to_string(),
+            email: email.to_string(),
+            email_key: email.to_lowercase(),
+            email_pending: None,
+            email_verified_at: match email_verified {
+                true => Some(current_time),
+                false => None,
+            },
+            avatar_url: None,
+            password_hash: None,
+            roles: None,
+            permissions: None,
+            ink: starter_ink,
+            ink_sum: starter_ink,
+            ink_pending: 0,
+            ink_withheld,
+            delete_pending: false,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
+            updated_at: current_time,
+            created_at: current_time,
+            entitlement: None,
         };
     }
 
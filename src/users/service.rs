@@ -1,5 +1,5 @@
 use axum::http::StatusCode;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres};
 
 use crate::{
     app::{
@@ -8,38 +8,115 @@ use crate::{
         util::{
             hasher,
             sqlx::{get_code_from_db_err, SqlStateCodes},
+            time,
         },
     },
     auth::{
         dtos::{
             edit_password_dto::EditPasswordDto, login_dto::LoginDto, register_dto::RegisterDto,
         },
+        identity::models::user_identity::UserIdentity,
         jwt::models::claims::Claims,
     },
-    media,
+    media, subscriptions,
 };
 
 use super::{
     dtos::{edit_user_dto::EditUserDto, get_users_filter_dto::GetUsersFilterDto},
     errors::UsersApiError,
     models::user::User,
+    util::ink::{
+        dtos::edit_user_ink_dto::EditUserInkDto, enums::ink_ledger_reason::InkLedgerReason, ink,
+        models::ink_ledger_entry::InkLedgerEntry,
+    },
 };
 
-pub async fn create_user_as_admin(dto: &RegisterDto, pool: &PgPool) -> Result<User, ApiError> {
+pub async fn create_user_as_admin(
+    dto: &RegisterDto,
+    starter_ink: i64,
+    ink_withheld: i64,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
     let Ok(hash) = hasher::hash(dto.password.to_string()).await else {
         return Err(DefaultApiError::InternalServerError.value());
     };
 
-    let user = User::new(dto, hash);
+    let user = User::new(dto, hash, starter_ink, ink_withheld);
+
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = create_user_as_tx(&user, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("create_user_as_admin failed to roll back: {:?}", e);
+        }
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(user),
+        Err(e) => {
+            tracing::error!("create_user_as_admin failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+// creates a user signing in with apple or google for the first time, linked to the identity
+pub async fn create_user_with_identity_as_admin(
+    user: &User,
+    identity: &UserIdentity,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = create_user_as_tx(user, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "create_user_with_identity_as_admin failed to roll back: {:?}",
+                e
+            );
+        }
+        return Err(e);
+    }
+
+    if let Err(e) = create_user_identity_as_tx(identity, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!(
+                "create_user_with_identity_as_admin failed to roll back: {:?}",
+                e
+            );
+        }
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(
+                "create_user_with_identity_as_admin failed to commit tx: {:?}",
+                e
+            );
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
 
+async fn create_user_as_tx(
+    user: &User,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
     let sqlx_result = sqlx::query(
         "
         INSERT INTO users (
             id, username, username_key, displayname,
-            email, email_key, password_hash,
-            ink, ink_sum, ink_pending, delete_pending, updated_at, created_at
+            email, email_key, email_verified_at, password_hash,
+            ink, ink_sum, ink_pending, ink_withheld, delete_pending, updated_at, created_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
         ",
     )
     .bind(&user.id)
@@ -48,18 +125,102 @@ pub async fn create_user_as_admin(dto: &RegisterDto, pool: &PgPool) -> Result<Us
     .bind(&user.displayname)
     .bind(&user.email)
     .bind(&user.email_key)
+    .bind(user.email_verified_at)
     .bind(&user.password_hash)
-    .bind(&user.ink)
-    .bind(&user.ink_sum)
-    .bind(&user.ink_pending)
-    .bind(&user.delete_pending)
-    .bind(user.updated_at.to_owned() as i64)
-    .bind(user.created_at.to_owned() as i64)
-    .execute(pool)
+    .bind(user.ink)
+    .bind(user.ink_sum)
+    .bind(user.ink_pending)
+    .bind(user.ink_withheld)
+    .bind(user.delete_pending)
+    .bind(user.updated_at)
+    .bind(user.created_at)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        let Some(db_err) = e.as_database_error() else {
+            tracing::error!(%e);
+            return Err(DefaultApiError::InternalServerError.value());
+        };
+
+        let Some(code) = get_code_from_db_err(db_err) else {
+            tracing::error!(%e);
+            return Err(DefaultApiError::InternalServerError.value());
+        };
+
+        return match code.as_str() {
+            SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
+                code: StatusCode::CONFLICT,
+                message: "User already exists.".to_string(),
+            }),
+            _ => {
+                tracing::error!("create_user_as_tx: {:?}", e);
+                Err(DefaultApiError::InternalServerError.value())
+            }
+        };
+    }
+
+    // the signup grant opens the user's ledger
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: Some(user.ink),
+        ink_decrease: None,
+        ink_sum_increase: Some(user.ink_sum),
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+    let entry = InkLedgerEntry::new(&user.id, &edit_user_ink_dto, InkLedgerReason::SIGNUP, None);
+
+    return ink::create_ink_ledger_entry_as_tx(&entry, tx).await;
+}
+
+pub async fn create_user_identity_as_admin(
+    identity: &UserIdentity,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    if let Err(e) = create_user_identity_as_tx(identity, &mut tx).await {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("create_user_identity_as_admin failed to roll back: {:?}", e);
+        }
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("create_user_identity_as_admin failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+async fn create_user_identity_as_tx(
+    identity: &UserIdentity,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO user_identities (
+            id, user_id, provider, subject, email, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ",
+    )
+    .bind(&identity.id)
+    .bind(&identity.user_id)
+    .bind(&identity.provider)
+    .bind(&identity.subject)
+    .bind(&identity.email)
+    .bind(identity.created_at)
+    .execute(&mut *tx)
     .await;
 
     match sqlx_result {
-        Ok(_) => Ok(user),
+        Ok(_) => Ok(()),
         Err(e) => {
             let Some(db_err) = e.as_database_error() else {
                 tracing::error!(%e);
@@ -74,10 +235,10 @@ pub async fn create_user_as_admin(dto: &RegisterDto, pool: &PgPool) -> Result<Us
             match code.as_str() {
                 SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
                     code: StatusCode::CONFLICT,
-                    message: "User already exists.".to_string(),
+                    message: "Identity is already linked.".to_string(),
                 }),
                 _ => {
-                    tracing::error!("create_user_as_admin: {:?}", e);
+                    tracing::error!("create_user_identity_as_tx: {:?}", e);
                     Err(DefaultApiError::InternalServerError.value())
                 }
             }
@@ -85,6 +246,35 @@ pub async fn create_user_as_admin(dto: &RegisterDto, pool: &PgPool) -> Result<Us
     }
 }
 
+pub async fn get_user_by_identity_as_admin(
+    provider: &str,
+    subject: &str,
+    pool: &PgPool,
+) -> Result<User, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, User>(
+        "
+        SELECT users.* FROM users
+        JOIN user_identities ON user_identities.user_id = users.id
+        WHERE user_identities.provider = $1 AND user_identities.subject = $2
+        ",
+    )
+    .bind(provider)
+    .bind(subject)
+    .fetch_optional(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(user) => match user {
+            Some(user) => Ok(user),
+            None => Err(UsersApiError::UserNotFound.value()),
+        },
+        Err(e) => {
+            tracing::error!("get_user_by_identity_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
 pub async fn get_users(
     dto: &GetUsersFilterDto,
     claims: &Claims,
@@ -121,6 +311,23 @@ pub async fn get_users(
     }
 }
 
+pub async fn get_user_from_request(claims: &Claims, pool: &PgPool) -> Result<User, ApiError> {
+    let user_result = get_user_by_id_as_admin(&claims.id, pool).await;
+    let Ok(mut user) = user_result else {
+        return Err(user_result.unwrap_err());
+    };
+
+    let entitlement_result =
+        subscriptions::service::get_entitlement_by_user_id(&claims.id, pool).await;
+    let Ok(entitlement) = entitlement_result else {
+        return Err(entitlement_result.unwrap_err());
+    };
+
+    user.entitlement = entitlement;
+
+    return Ok(user);
+}
+
 pub async fn get_user_by_id(id: &str, _claims: &Claims, pool: &PgPool) -> Result<User, ApiError> {
     return get_user_by_id_as_admin(id, pool).await;
 }
@@ -296,6 +503,97 @@ pub async fn edit_user_email_pending_by_id_as_admin(
     }
 }
 
+// grants the starter ink withheld at signup, if any, along with the verification
+pub async fn verify_user_email_by_id_as_admin(id: &str, pool: &PgPool) -> Result<(), ApiError> {
+    let Ok(mut tx) = pool.begin().await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query_as::<_, (i64,)>(
+        "
+        SELECT ink_withheld FROM users
+        WHERE id = $1 AND email_verified_at IS NULL
+        FOR UPDATE
+        ",
+    )
+    .bind(id)
+    .fetch_optional(&mut tx)
+    .await;
+
+    let ink_granted = match sqlx_result {
+        Ok(Some((ink_withheld,))) => ink_withheld,
+        Ok(None) => {
+            if let Err(e) = tx.rollback().await {
+                tracing::error!(
+                    "verify_user_email_by_id_as_admin failed to roll back: {:?}",
+                    e
+                );
+            }
+            return Err(UsersApiError::EmailAlreadyVerified.value());
+        }
+        Err(e) => {
+            tracing::error!("verify_user_email_by_id_as_admin: {:?}", e);
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET email_verified_at = $1, ink_withheld = 0
+        WHERE id = $2
+        ",
+    )
+    .bind(time::current_time_in_secs() as i64)
+    .bind(id)
+    .execute(&mut tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!("verify_user_email_by_id_as_admin: {:?}", e);
+        return Err(DefaultApiError::InternalServerError.value());
+    }
+
+    if ink_granted > 0 {
+        let edit_user_ink_dto = EditUserInkDto {
+            ink_increase: Some(ink_granted),
+            ink_decrease: None,
+            ink_sum_increase: Some(ink_granted),
+            ink_sum_decrease: None,
+            ink_pending_increase: None,
+            ink_pending_decrease: None,
+        };
+
+        if let Err(e) = ink::edit_user_ink_by_id(
+            id,
+            &edit_user_ink_dto,
+            InkLedgerReason::EMAIL_VERIFICATION,
+            None,
+            &mut tx,
+        )
+        .await
+        {
+            if let Err(e) = tx.rollback().await {
+                tracing::error!(
+                    "verify_user_email_by_id_as_admin failed to roll back: {:?}",
+                    e
+                );
+            }
+            return Err(e);
+        }
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(
+                "verify_user_email_by_id_as_admin failed to commit tx: {:?}",
+                e
+            );
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
 pub async fn approve_user_email_pending_by_id_as_admin(
     id: &str,
     pool: &PgPool,
@@ -303,10 +601,12 @@ pub async fn approve_user_email_pending_by_id_as_admin(
     let sqlx_result = sqlx::query(
         "
         UPDATE users
-        SET email = email_pending, email_key = LOWER(email_pending), email_pending = NULL
-        WHERE id = $1 AND email_pending IS NOT NULL
+        SET email = email_pending, email_key = LOWER(email_pending), email_pending = NULL,
+        email_verified_at = $1
+        WHERE id = $2 AND email_pending IS NOT NULL
         ",
     )
+    .bind(time::current_time_in_secs() as i64)
     .bind(id)
     .execute(pool)
     .await;
@@ -326,6 +626,39 @@ pub async fn approve_user_email_pending_by_id_as_admin(
     }
 }
 
+// only for users who signed up with apple or google and never set a password
+pub async fn set_user_password_by_id_as_admin(
+    id: &str,
+    dto: &EditPasswordDto,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    let Ok(hash) = hasher::hash(dto.password.to_string()).await else {
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET password_hash = $1
+        WHERE id = $2 AND password_hash IS NULL
+        ",
+    )
+    .bind(hash)
+    .bind(id)
+    .execute(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(UsersApiError::PasswordAlreadySet.value()),
+        },
+        Err(e) => {
+            tracing::error!("set_user_password_by_id_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
 pub async fn edit_user_password_by_id_as_admin(
     id: &str,
     dto: &EditPasswordDto,
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AdjustUserInkDto {
+    // negative to take ink away
+    #[validate(range(
+        min = -1000000,
+        max = 1000000,
+        message = "amount must be between -1000000 and 1000000."
+    ))]
+    pub amount: i64,
+}
@@ -0,0 +1,136 @@
+use axum::http::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::models::api_error::ApiError,
+    users::util::ink::models::ink_ledger_entry::INK_LEDGER_ENTRY_SORTABLE_FIELDS,
+};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct GetInkLedgerFilterDto {
+    pub reason: Option<String>,
+    pub reference_id: Option<String>,
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
+    #[validate(range(max = 100, message = "limit must be equal or less than 100."))]
+    pub limit: Option<u8>,
+}
+
+impl GetInkLedgerFilterDto {
+    // the ledger is always scoped to a single user, bound as $1
+    pub fn to_sql(&self) -> Result<String, ApiError> {
+        let mut sql = "SELECT * FROM ink_ledger WHERE user_id = $1".to_string();
+        let mut clauses = Vec::new();
+
+        let mut sort_field = "created_at".to_string();
+        let mut sort_order = "DESC".to_string();
+        let mut page_limit: u8 = 50;
+
+        let mut index: u8 = 1;
+
+        // WHERE CLAUSES
+        if self.reason.is_some() {
+            index += 1;
+            clauses.push(["reason = $", &index.to_string()].concat());
+        }
+        if self.reference_id.is_some() {
+            index += 1;
+            clauses.push(["reference_id = $", &index.to_string()].concat());
+        }
+
+        // SORT
+        if let Some(sort) = &self.sort {
+            let sort_params: Vec<&str> = sort.split(",").collect();
+
+            if sort_params.len() != 2 {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: "Malformed sort query.".to_string(),
+                });
+            }
+            if !INK_LEDGER_ENTRY_SORTABLE_FIELDS.contains(&sort_params[0]) {
+                return Err(ApiError {
+                    code: StatusCode::BAD_REQUEST,
+                    message: "Invalid sort field.".to_string(),
+                });
+            }
+
+            sort_field = sort_params[0].to_string();
+            sort_order = sort_params[1].to_uppercase();
+
+            let direction = match sort_order.as_str() {
+                "ASC" => ">",
+                "DESC" => "<",
+                _ => {
+                    return Err(ApiError {
+                        code: StatusCode::BAD_REQUEST,
+                        message: "Malformed sort query.".to_string(),
+                    })
+                }
+            };
+
+            if let Some(cursor) = &self.cursor {
+                let cursor_params: Vec<&str> = cursor.split(",").collect();
+
+                if cursor_params.len() != 2 {
+                    return Err(ApiError {
+                        code: StatusCode::BAD_REQUEST,
+                        message: "Malformed cursor.".to_string(),
+                    });
+                }
+
+                // parsed rather than pasted into the query, both come from the client
+                let (Ok(cursor_value), Ok(cursor_id)) = (
+                    cursor_params[0].parse::<i64>(),
+                    Uuid::parse_str(cursor_params[1]),
+                ) else {
+                    return Err(ApiError {
+                        code: StatusCode::BAD_REQUEST,
+                        message: "Malformed cursor.".to_string(),
+                    });
+                };
+
+                clauses.push(
+                    [
+                        "(",
+                        &sort_field,
+                        ", id) ",
+                        direction,
+                        " (",
+                        &cursor_value.to_string(),
+                        ", '",
+                        &cursor_id.to_string(),
+                        "')",
+                    ]
+                    .concat(),
+                );
+            }
+        }
+
+        // CLAUSES BUILDER
+        for clause in clauses {
+            sql.push_str(" AND ");
+            sql.push_str(&clause);
+        }
+
+        // ORDER BY
+        sql.push_str(&[" ORDER BY ", &sort_field, " ", &sort_order].concat());
+
+        if self.cursor.is_some() {
+            sql.push_str(&[", id ", &sort_order].concat());
+        }
+
+        // LIMIT
+        if let Some(limit) = self.limit {
+            page_limit = limit;
+        }
+
+        sql.push_str(&[" LIMIT ", &page_limit.to_string()].concat());
+
+        tracing::debug!(%sql);
+
+        Ok(sql.to_string())
+    }
+}
@@ -1 +1,3 @@
+pub mod adjust_user_ink_dto;
 pub mod edit_user_ink_dto;
+pub mod get_ink_ledger_filter_dto;
@@ -0,0 +1,23 @@
+#[non_exhaustive]
+pub struct InkLedgerReason;
+
+impl InkLedgerReason {
+    pub const OPENING_BALANCE: &'static str = "opening_balance";
+    pub const SIGNUP: &'static str = "signup";
+    pub const EMAIL_VERIFICATION: &'static str = "email_verification";
+    pub const GENERATION_HOLD: &'static str = "generation_hold";
+    pub const GENERATION_CHARGE: &'static str = "generation_charge";
+    pub const GENERATION_CANCEL: &'static str = "generation_cancel";
+    pub const MEDIA_REFUND: &'static str = "media_refund";
+    pub const UPSCALE_HOLD: &'static str = "upscale_hold";
+    pub const UPSCALE_RELEASE: &'static str = "upscale_release";
+    pub const UPSCALE_CHARGE: &'static str = "upscale_charge";
+    pub const PURCHASE: &'static str = "purchase";
+    pub const SUBSCRIPTION_GRANT: &'static str = "subscription_grant";
+    pub const PROMO_CODE: &'static str = "promo_code";
+    pub const GIFT_SENT: &'static str = "gift_sent";
+    pub const GIFT_RECEIVED: &'static str = "gift_received";
+    pub const CANCELLATION: &'static str = "cancellation";
+    pub const REFUND: &'static str = "refund";
+    pub const ADMIN_ADJUSTMENT: &'static str = "admin_adjustment";
+}
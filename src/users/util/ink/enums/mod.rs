@@ -0,0 +1 @@
+pub mod ink_ledger_reason;
@@ -1,26 +1,27 @@
 use reqwest::StatusCode;
-use sqlx::Postgres;
+use sqlx::{PgPool, Postgres};
 
 use crate::{
-    app::models::api_error::ApiError,
-    media::{dtos::generate_media_dto::GenerateMediaDto, enums::media_generator::MediaGenerator},
+    app::{errors::DefaultApiError, models::api_error::ApiError},
+    auth::jwt::{enums::permissions::Permissions, models::claims::Claims},
+    media::dtos::generate_media_dto::GenerateMediaDto,
+    users::errors::UsersApiError,
 };
 
-use super::dtos::edit_user_ink_dto::EditUserInkDto;
-
-pub fn calculate_ink_cost(dto: &GenerateMediaDto, number_generated: Option<u8>) -> i64 {
-    let base_ink = match dto.generator.as_ref() {
-        MediaGenerator::MIST => 10.0,
-        MediaGenerator::STABLE_HORDE => 10.0,
-        MediaGenerator::DALLE => 40.0,
-        _ => panic!(
-            "calculate_ink_cost for generator {} not implemented.",
-            dto.generator
-        ),
-    };
-
-    let ink_per_pixel: f64 = base_ink / (512.0 * 512.0);
+use super::{
+    dtos::{
+        adjust_user_ink_dto::AdjustUserInkDto, edit_user_ink_dto::EditUserInkDto,
+        get_ink_ledger_filter_dto::GetInkLedgerFilterDto,
+    },
+    enums::ink_ledger_reason::InkLedgerReason,
+    models::{ink_drift::InkDrift, ink_ledger_entry::InkLedgerEntry},
+};
 
+pub fn calculate_ink_cost(
+    dto: &GenerateMediaDto,
+    ink_per_pixel: f64,
+    number_generated: Option<u8>,
+) -> i64 {
     let number = match number_generated {
         Some(number_generated) => match number_generated > dto.number {
             true => dto.number,
@@ -39,6 +40,8 @@ pub fn calculate_ink_cost(dto: &GenerateMediaDto, number_generated: Option<u8>)
 pub async fn edit_user_ink_by_id(
     id: &str,
     dto: &EditUserInkDto,
+    reason: &str,
+    reference_id: Option<&str>,
     tx: &mut sqlx::Transaction<'_, Postgres>,
 ) -> Result<(), ApiError> {
     let sql_result = dto.to_sql();
@@ -71,7 +74,10 @@ pub async fn edit_user_ink_by_id(
 
     match sqlx.execute(&mut *tx).await {
         Ok(result) => match result.rows_affected() > 0 {
-            true => Ok(()),
+            true => {
+                let entry = InkLedgerEntry::new(id, dto, reason, reference_id);
+                return create_ink_ledger_entry_as_tx(&entry, tx).await;
+            }
             false => {
                 tracing::error!("update_user_ink_by_id ({}): NO ROWS AFFECTED", id);
 
@@ -90,3 +96,252 @@ pub async fn edit_user_ink_by_id(
         }
     }
 }
+
+// locks the user row, so the ink held after the check can't be spent twice
+// by concurrent requests that all passed it
+pub async fn check_ink_available_as_tx(
+    id: &str,
+    ink_cost: i64,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT ink::BIGINT, ink_pending::BIGINT FROM users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(Some((ink, ink_pending))) => match ink - ink_pending >= ink_cost {
+            true => Ok(()),
+            false => Err(ApiError {
+                code: StatusCode::NOT_ACCEPTABLE,
+                message: "Not enough ink.".to_string(),
+            }),
+        },
+        Ok(None) => Err(UsersApiError::UserNotFound.value()),
+        Err(e) => {
+            tracing::error!("check_ink_available_as_tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn create_ink_ledger_entry_as_tx(
+    entry: &InkLedgerEntry,
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO ink_ledger (
+            id, user_id, ink_delta, ink_sum_delta, ink_pending_delta,
+            reason, reference_id, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ",
+    )
+    .bind(&entry.id)
+    .bind(&entry.user_id)
+    .bind(entry.ink_delta)
+    .bind(entry.ink_sum_delta)
+    .bind(entry.ink_pending_delta)
+    .bind(&entry.reason)
+    .bind(&entry.reference_id)
+    .bind(entry.created_at)
+    .execute(&mut *tx)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("create_ink_ledger_entry_as_tx: {:?}", e);
+            return Err(ApiError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: "Failed to record ink ledger entry.".to_string(),
+            });
+        }
+    }
+}
+
+pub async fn get_ink_ledger(
+    dto: &GetInkLedgerFilterDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<Vec<InkLedgerEntry>, ApiError> {
+    let sql_result = dto.to_sql();
+    let Ok(sql) = sql_result else {
+        return Err(sql_result.err().unwrap());
+    };
+
+    let mut sqlx = sqlx::query_as::<_, InkLedgerEntry>(&sql).bind(&claims.id);
+
+    if let Some(reason) = &dto.reason {
+        sqlx = sqlx.bind(reason);
+    }
+    if let Some(reference_id) = &dto.reference_id {
+        sqlx = sqlx.bind(reference_id);
+    }
+
+    match sqlx.fetch_all(pool).await {
+        Ok(entries) => Ok(entries),
+        Err(e) => {
+            tracing::error!("get_ink_ledger: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn get_ink_drift_as_admin(pool: &PgPool) -> Result<Vec<InkDrift>, ApiError> {
+    let sqlx_result = sqlx::query_as::<_, InkDrift>(
+        "
+        SELECT
+            users.id AS user_id,
+            users.ink::BIGINT AS ink,
+            users.ink_sum::BIGINT AS ink_sum,
+            users.ink_pending::BIGINT AS ink_pending,
+            COALESCE(SUM(ink_ledger.ink_delta), 0)::BIGINT AS ledger_ink,
+            COALESCE(SUM(ink_ledger.ink_sum_delta), 0)::BIGINT AS ledger_ink_sum,
+            COALESCE(SUM(ink_ledger.ink_pending_delta), 0)::BIGINT AS ledger_ink_pending
+        FROM users
+        LEFT JOIN ink_ledger ON ink_ledger.user_id = users.id
+        GROUP BY users.id
+        HAVING users.ink <> COALESCE(SUM(ink_ledger.ink_delta), 0)
+            OR users.ink_sum <> COALESCE(SUM(ink_ledger.ink_sum_delta), 0)
+            OR users.ink_pending <> COALESCE(SUM(ink_ledger.ink_pending_delta), 0)
+        ",
+    )
+    .fetch_all(pool)
+    .await;
+
+    match sqlx_result {
+        Ok(drifts) => Ok(drifts),
+        Err(e) => {
+            tracing::error!("get_ink_drift_as_admin: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
+
+pub async fn reconcile_ink(claims: &Claims, pool: &PgPool) -> Result<Vec<InkDrift>, ApiError> {
+    if !claims.has_permission(Permissions::ADJUST_INK) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    return reconcile_ink_as_admin(pool).await;
+}
+
+pub async fn reconcile_ink_as_admin(pool: &PgPool) -> Result<Vec<InkDrift>, ApiError> {
+    let drifts_result = get_ink_drift_as_admin(pool).await;
+    let Ok(drifts) = drifts_result else {
+        return Err(drifts_result.unwrap_err());
+    };
+
+    for drift in &drifts {
+        tracing::warn!(
+            "reconcile_ink_as_admin: user {} drifted (ink {} vs {}, ink_sum {} vs {}, ink_pending {} vs {})",
+            drift.user_id,
+            drift.ink,
+            drift.ledger_ink,
+            drift.ink_sum,
+            drift.ledger_ink_sum,
+            drift.ink_pending,
+            drift.ledger_ink_pending
+        );
+    }
+
+    return Ok(drifts);
+}
+
+// the ledger entry references the admin who made the adjustment
+pub async fn adjust_user_ink_by_id(
+    id: &str,
+    dto: &AdjustUserInkDto,
+    claims: &Claims,
+    pool: &PgPool,
+) -> Result<(), ApiError> {
+    if !claims.has_permission(Permissions::ADJUST_INK) {
+        return Err(DefaultApiError::PermissionDenied.value());
+    }
+
+    if dto.amount == 0 {
+        return Err(ApiError {
+            code: StatusCode::BAD_REQUEST,
+            message: "amount must not be 0.".to_string(),
+        });
+    }
+
+    let Ok(mut tx) = pool.begin().await else {
+        tracing::warn!("adjust_user_ink_by_id failed to begin pool transaction");
+        return Err(DefaultApiError::InternalServerError.value());
+    };
+
+    let sqlx_result = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT ink::BIGINT, ink_pending::BIGINT FROM users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut tx)
+    .await;
+
+    let (ink, ink_pending) = match sqlx_result {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Err(UsersApiError::UserNotFound.value());
+        }
+        Err(e) => {
+            tracing::error!("adjust_user_ink_by_id: {:?}", e);
+            let _ = tx.rollback().await;
+            return Err(DefaultApiError::InternalServerError.value());
+        }
+    };
+
+    // ink held for pending requests can't be taken away
+    if dto.amount < 0 && ink - ink_pending < -dto.amount {
+        let _ = tx.rollback().await;
+        return Err(ApiError {
+            code: StatusCode::NOT_ACCEPTABLE,
+            message: "Not enough ink.".to_string(),
+        });
+    }
+
+    let edit_user_ink_dto = EditUserInkDto {
+        ink_increase: (dto.amount > 0).then_some(dto.amount),
+        ink_decrease: (dto.amount < 0).then_some(-dto.amount),
+        ink_sum_increase: None,
+        ink_sum_decrease: None,
+        ink_pending_increase: None,
+        ink_pending_decrease: None,
+    };
+
+    if let Err(e) = edit_user_ink_by_id(
+        id,
+        &edit_user_ink_dto,
+        InkLedgerReason::ADMIN_ADJUSTMENT,
+        Some(&claims.id),
+        &mut tx,
+    )
+    .await
+    {
+        if let Err(e) = tx.rollback().await {
+            tracing::error!("adjust_user_ink_by_id failed to roll back: {:?}", e);
+        }
+
+        return Err(e);
+    }
+
+    match tx.commit().await {
+        Ok(_) => {
+            tracing::info!(
+                "{} adjusted ink of user {} by {}",
+                claims.id,
+                id,
+                dto.amount
+            );
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("adjust_user_ink_by_id failed to commit tx: {:?}", e);
+            Err(DefaultApiError::InternalServerError.value())
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod dtos;
+pub mod enums;
 pub mod ink;
+pub mod models;
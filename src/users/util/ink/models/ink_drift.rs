@@ -0,0 +1,14 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// A user whose stored balances disagree with the sum of their ledger entries.
+#[derive(Debug, Serialize, FromRow)]
+pub struct InkDrift {
+    pub user_id: String,
+    pub ink: i64,
+    pub ink_sum: i64,
+    pub ink_pending: i64,
+    pub ledger_ink: i64,
+    pub ledger_ink_sum: i64,
+    pub ledger_ink_pending: i64,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{app::util::time, users::util::ink::dtos::edit_user_ink_dto::EditUserInkDto};
+
+pub static INK_LEDGER_ENTRY_SORTABLE_FIELDS: [&str; 1] = ["created_at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InkLedgerEntry {
+    pub id: String,
+    pub user_id: String,
+    pub ink_delta: i64,
+    pub ink_sum_delta: i64,
+    pub ink_pending_delta: i64,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_id: Option<String>,
+    pub created_at: i64,
+}
+
+impl InkLedgerEntry {
+    pub fn new(
+        user_id: &str,
+        dto: &EditUserInkDto,
+        reason: &str,
+        reference_id: Option<&str>,
+    ) -> Self {
+        return Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            ink_delta: dto.ink_increase.unwrap_or(0) - dto.ink_decrease.unwrap_or(0),
+            ink_sum_delta: dto.ink_sum_increase.unwrap_or(0) - dto.ink_sum_decrease.unwrap_or(0),
+            ink_pending_delta: dto.ink_pending_increase.unwrap_or(0)
+                - dto.ink_pending_decrease.unwrap_or(0),
+            reason: reason.to_string(),
+            reference_id: reference_id.map(|reference_id| reference_id.to_string()),
+            created_at: time::current_time_in_secs() as i64,
+        };
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ink_drift;
+pub mod ink_ledger_entry;
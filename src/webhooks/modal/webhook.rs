@@ -37,7 +37,18 @@ pub async fn receive_webhook(
     )
     .await
     {
-        Ok(request) => media::apis::modal::service::on_receive_webhook(request, dto, state),
+        Ok(request) => {
+            if !request.is_processing() {
+                tracing::warn!(
+                    "receive_webhook ignored: request {} is {}",
+                    request.id,
+                    request.status
+                );
+                return Ok(());
+            }
+
+            media::apis::modal::service::on_receive_webhook(request, dto, state)
+        }
         Err(e) => {
             if e.code == StatusCode::NOT_FOUND {
                 tracing::error!("receive_webhook failed: request not found");